@@ -1,4 +1,7 @@
-use crate::language::editor::{EditorCommand, EditorInput, EditorInputBuffer};
+use crate::language::{
+    code::ConflictSide,
+    editor::{EditorCommand, EditorInput, EditorInputBuffer},
+};
 
 #[derive(Debug)]
 pub struct TerminalEditorInput {
@@ -36,10 +39,49 @@ impl TerminalEditorInput {
             },
             EditorMode::Command { buffer, cursor } => match input {
                 TerminalInput::Enter => {
-                    let command = match buffer.contents() {
-                        "clear" => Some(EditorCommand::Clear),
-                        "dump" => Some(EditorCommand::Dump),
-                        "reset" => Some(EditorCommand::Reset),
+                    let words = buffer
+                        .contents()
+                        .split_whitespace()
+                        .collect::<Vec<_>>();
+
+                    let command = match words[..] {
+                        ["branch", name] => Some(EditorCommand::Branch {
+                            name: name.to_string(),
+                        }),
+                        ["checkout", tag] => Some(EditorCommand::Checkout {
+                            tag: tag.to_string(),
+                        }),
+                        ["clear"] => Some(EditorCommand::Clear),
+                        ["conflict"] => Some(EditorCommand::Conflict),
+                        ["diff"] => Some(EditorCommand::Diff { tag: None }),
+                        ["diff", tag] => Some(EditorCommand::Diff {
+                            tag: Some(tag.to_string()),
+                        }),
+                        ["dump"] => Some(EditorCommand::Dump),
+                        ["export", path] => Some(EditorCommand::Export {
+                            path: path.to_string(),
+                        }),
+                        ["import", path] => Some(EditorCommand::Import {
+                            path: path.to_string(),
+                        }),
+                        ["merge", branch] => Some(EditorCommand::Merge {
+                            branch: branch.to_string(),
+                        }),
+                        ["pick", "ours"] => Some(EditorCommand::Pick {
+                            side: ConflictSide::Ours,
+                        }),
+                        ["pick", "theirs"] => Some(EditorCommand::Pick {
+                            side: ConflictSide::Theirs,
+                        }),
+                        ["redo"] => Some(EditorCommand::Redo),
+                        ["reset"] => Some(EditorCommand::Reset),
+                        ["switch", branch] => Some(EditorCommand::Switch {
+                            branch: branch.to_string(),
+                        }),
+                        ["tag", name] => Some(EditorCommand::Tag {
+                            name: name.to_string(),
+                        }),
+                        ["undo"] => Some(EditorCommand::Undo),
                         _ => {
                             // Command was not recognized.
                             //
@@ -60,6 +102,11 @@ impl TerminalEditorInput {
                 TerminalInput::Escape => {
                     self.mode = EditorMode::Edit;
                 }
+                TerminalInput::Character { ch } => {
+                    // Commands can have arguments, so unlike in the code,
+                    // whitespace is just another character here.
+                    buffer.insert(ch, cursor);
+                }
                 input => {
                     if let Some(input) = input.into_editor_input() {
                         buffer.update(input, cursor);
@@ -102,6 +149,9 @@ pub enum TerminalInput {
     Enter,
     Escape,
 
+    Undo,
+    Redo,
+
     /// # An event that has no effect when processed
     ///
     /// If a thread shuts down, either because of an error, or because the
@@ -140,6 +190,9 @@ impl TerminalInput {
 
             Self::Enter => Some(EditorInput::MoveCursorDown),
 
+            Self::Undo => Some(EditorInput::Undo),
+            Self::Redo => Some(EditorInput::Redo),
+
             _ => None,
         }
     }
@@ -188,6 +241,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn submit_command_with_argument() {
+        let input = "tag working";
+        let expected = EditorCommand::Tag {
+            name: String::from("working"),
+        };
+
+        let mut editor_input = TerminalEditorInput::new();
+
+        // enter command mode
+        assert_eq!(editor_input.on_input(TerminalInput::Escape), None);
+
+        for ch in input.chars() {
+            assert_eq!(
+                editor_input.on_input(TerminalInput::Character { ch }),
+                None,
+            );
+        }
+
+        // submit command
+        assert_eq!(
+            editor_input.on_input(TerminalInput::Enter),
+            Some(EditorInputOrCommand::Command { command: expected }),
+        );
+    }
+
     #[test]
     fn abort_command() {
         // The code that recognizes the different commands is completely
@@ -2,10 +2,15 @@ use crossterm::style::{Attribute, Color};
 
 use crate::{
     language::{
-        code::{Codebase, NodePath, SyntaxNode},
+        code::{
+            Codebase, Diff, DiffNode, NodeHash, NodePath, Nodes, SyntaxNode,
+        },
         editor::{Editor, EditorLayout, EditorLine},
+        inference::Types,
         language::Language,
-        runtime::{Effect, Evaluator, RuntimeState},
+        runtime::{
+            ArithmeticError, Effect, Evaluator, HostFunctions, RuntimeState,
+        },
     },
     terminal::{Cursor, TerminalOutputAdapter},
 };
@@ -45,6 +50,8 @@ fn node_to_adapter(
         codebase,
         editor: None,
         evaluator: None,
+        host_functions: None,
+        types: None,
         cursor: None,
     };
 
@@ -69,11 +76,14 @@ where
         &mut self,
         language: &Language,
         editor_input: &TerminalEditorInput,
+        command_error: Option<&str>,
     ) -> anyhow::Result<()> {
         let mut context = RenderContext {
             codebase: language.codebase(),
             editor: Some(language.editor()),
             evaluator: Some(language.evaluator()),
+            host_functions: Some(language.host_functions()),
+            types: Some(language.types()),
             cursor: None,
         };
 
@@ -83,8 +93,16 @@ where
         self.adapter.clear()?;
 
         render_runtime_state(&mut self.adapter, &context)?;
+        render_versions(&mut self.adapter, &context)?;
         render_layout(&layout, &mut self.adapter, &mut context)?;
-        render_prompt(&mut self.adapter, editor_input, &mut context)?;
+        render_diff_to_base(&mut self.adapter, &context)?;
+        render_prompt(
+            &mut self.adapter,
+            editor_input,
+            command_error,
+            &mut context,
+        )?;
+        render_type(&mut self.adapter, &context)?;
         render_help(&mut self.adapter, &context)?;
 
         if let Some(cursor) = context.cursor {
@@ -118,6 +136,12 @@ fn render_runtime_state<A: TerminalOutputAdapter>(
                     write!(adapter, "Effect: ")?;
 
                     match effect {
+                        Effect::AbortOutsideOfHandler => {
+                            writeln!(
+                                adapter,
+                                "`abort` applied outside of effect handler"
+                            )?;
+                        }
                         Effect::ApplyProvidedFunction { name, input } => {
                             writeln!(
                                 adapter,
@@ -125,6 +149,44 @@ fn render_runtime_state<A: TerminalOutputAdapter>(
                                 {input})",
                             )?;
                         }
+                        Effect::Arithmetic { error, input } => {
+                            let error = match error {
+                                ArithmeticError::DivisionByZero => {
+                                    "division by zero"
+                                }
+                                ArithmeticError::Overflow => "overflow",
+                            };
+
+                            writeln!(
+                                adapter,
+                                "arithmetic {error} (input: `{input}`)"
+                            )?;
+                        }
+                        Effect::FieldNotFound { name, record } => {
+                            writeln!(
+                                adapter,
+                                "field `{name}` not found (in `{record}`)"
+                            )?;
+                        }
+                        Effect::IndexOutOfRange { index, value } => {
+                            writeln!(
+                                adapter,
+                                "index `{index}` is out of range (in \
+                                `{value}`)"
+                            )?;
+                        }
+                        Effect::NoMatchingArm { value } => {
+                            writeln!(
+                                adapter,
+                                "no arm matches value `{value}`"
+                            )?;
+                        }
+                        Effect::NumberOutOfRange { value, target } => {
+                            writeln!(
+                                adapter,
+                                "`{value}` can't be represented as `{target}`"
+                            )?;
+                        }
                         Effect::ProvidedFunctionNotFound => {
                             writeln!(adapter, "provided function not found")?;
                         }
@@ -135,6 +197,9 @@ fn render_runtime_state<A: TerminalOutputAdapter>(
                                 `{actual}`)"
                             )?;
                         }
+                        Effect::UnresolvedConflict => {
+                            writeln!(adapter, "unresolved merge conflict")?;
+                        }
                     }
 
                     Ok(())
@@ -154,6 +219,43 @@ fn render_runtime_state<A: TerminalOutputAdapter>(
     Ok(())
 }
 
+fn render_versions<A: TerminalOutputAdapter>(
+    adapter: &mut A,
+    context: &RenderContext,
+) -> anyhow::Result<()> {
+    let codebase = context.codebase;
+
+    write!(adapter, "On branch `{}`", codebase.branch())?;
+
+    let other_branches = codebase
+        .branches()
+        .map(|(name, _)| name)
+        .filter(|&name| name != codebase.branch())
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>();
+    if !other_branches.is_empty() {
+        write!(adapter, " (other branches: {})", other_branches.join(", "))?;
+    }
+
+    writeln!(adapter)?;
+
+    let tags = codebase
+        .tags()
+        .map(|(name, root)| {
+            if &root == codebase.root().path.hash() {
+                format!("`{name}` (current)")
+            } else {
+                format!("`{name}`")
+            }
+        })
+        .collect::<Vec<_>>();
+    if !tags.is_empty() {
+        writeln!(adapter, "Tags: {}", tags.join(", "))?;
+    }
+
+    Ok(())
+}
+
 fn render_layout<A: TerminalOutputAdapter>(
     layout: &EditorLayout,
     adapter: &mut A,
@@ -161,7 +263,11 @@ fn render_layout<A: TerminalOutputAdapter>(
 ) -> anyhow::Result<()> {
     writeln!(adapter)?;
 
-    for line in layout.lines.iter() {
+    for (index, line) in layout.lines.iter().enumerate() {
+        if line.starts_section && index > 0 {
+            writeln!(adapter)?;
+        }
+
         render_possibly_active_line(line, adapter, context)?;
     }
 
@@ -237,28 +343,252 @@ fn render_node<A: TerminalOutputAdapter>(
 ) -> anyhow::Result<()> {
     let node = context.codebase.node_at(path).node;
 
-    if let Some(editor) = context.editor {
-        if &editor.cursor().path == path {
-            context.cursor =
-                Some(adapter.cursor().move_right(editor.cursor().index));
-        }
+    if let Some(editor) = context.editor
+        && &editor.cursor().path == path
+    {
+        context.cursor =
+            Some(adapter.cursor().move_right(editor.cursor().index));
     }
 
     let color = match node {
         SyntaxNode::Identifier { .. } => Some(Color::DarkMagenta),
-        SyntaxNode::Function { .. }
+        SyntaxNode::String { .. } => Some(Color::DarkGreen),
+        SyntaxNode::Arm { .. }
+        | SyntaxNode::Boolean { .. }
+        | SyntaxNode::BooleanPattern { .. }
+        | SyntaxNode::Definition { .. }
+        | SyntaxNode::FieldAccess { .. }
+        | SyntaxNode::FieldUpdate { .. }
+        | SyntaxNode::Float { .. }
+        | SyntaxNode::Function { .. }
+        | SyntaxNode::Integer64 { .. }
+        | SyntaxNode::Let { .. }
+        | SyntaxNode::List { .. }
+        | SyntaxNode::Match { .. }
         | SyntaxNode::Number { .. }
+        | SyntaxNode::NumberPattern { .. }
+        | SyntaxNode::Record { .. }
+        | SyntaxNode::RecordPattern { .. }
+        | SyntaxNode::TuplePattern { .. }
+        | SyntaxNode::Unsigned { .. }
+        | SyntaxNode::Wildcard
         | SyntaxNode::Tuple { .. } => Some(Color::DarkBlue),
         _ => None,
     };
 
-    if let Some(color) = color {
-        adapter.color(color, |adapter| {
+    let has_type_error = context
+        .types
+        .is_some_and(|types| types.error_at(path).is_some());
+
+    // Ill-typed nodes are underlined, without hiding their usual color.
+    let render = |adapter: &mut A| {
+        if let Some(color) = color {
+            adapter.color(color, |adapter| {
+                write!(adapter, "{node}")?;
+                Ok(())
+            })?;
+        } else {
             write!(adapter, "{node}")?;
-            Ok(())
-        })?;
+        }
+
+        Ok(())
+    };
+
+    if has_type_error {
+        adapter.attribute(Attribute::Underlined, render)?;
+    } else {
+        render(adapter)?;
+    }
+
+    Ok(())
+}
+
+fn render_diff_to_base<A: TerminalOutputAdapter>(
+    adapter: &mut A,
+    context: &RenderContext,
+) -> anyhow::Result<()> {
+    let Some(tag) = context.editor.and_then(|editor| editor.diff_base()) else {
+        return Ok(());
+    };
+    let codebase = context.codebase;
+
+    let Some((_, base)) = codebase.tags().find(|(name, _)| *name == tag) else {
+        return Ok(());
+    };
+    let diff =
+        Diff::between(base, *codebase.root().path.hash(), codebase.nodes());
+
+    writeln!(adapter, "Changes since `{tag}`:")?;
+    writeln!(adapter)?;
+
+    if diff.is_empty() {
+        writeln!(adapter, "    (none)")?;
     } else {
-        write!(adapter, "{node}")?;
+        render_diff(&diff, codebase.nodes(), adapter)?;
+    }
+
+    writeln!(adapter)?;
+
+    Ok(())
+}
+
+/// # Render a diff as an annotated tree
+///
+/// Lines that were added or removed are marked and colored. Subtrees that are
+/// identical in both versions are collapsed.
+fn render_diff<A: TerminalOutputAdapter>(
+    diff: &Diff,
+    nodes: &Nodes,
+    adapter: &mut A,
+) -> anyhow::Result<()> {
+    render_diff_node(&diff.root, 0, nodes, adapter)
+}
+
+fn render_diff_node<A: TerminalOutputAdapter>(
+    node: &DiffNode,
+    level_of_indentation: u32,
+    nodes: &Nodes,
+    adapter: &mut A,
+) -> anyhow::Result<()> {
+    match node {
+        DiffNode::Unchanged { new, .. } => match nodes.get(new.hash()) {
+            SyntaxNode::Add => {}
+            SyntaxNode::Body { children, .. } => {
+                if !children.is_empty() {
+                    render_diff_line(' ', "…", level_of_indentation, adapter)?;
+                }
+            }
+            node => {
+                let token = if node.children().hashes.is_empty() {
+                    node.to_token()
+                } else {
+                    format!("{node} …")
+                };
+
+                render_diff_line(' ', &token, level_of_indentation, adapter)?;
+            }
+        },
+        DiffNode::Changed { new, children, .. } => {
+            let node = nodes.get(new.hash());
+
+            let level_of_children = if let SyntaxNode::Body { .. } = node {
+                level_of_indentation
+            } else {
+                let token = node.to_token();
+                render_diff_line(' ', &token, level_of_indentation, adapter)?;
+
+                level_of_indentation + 1
+            };
+
+            for child in children {
+                render_diff_node(child, level_of_children, nodes, adapter)?;
+            }
+        }
+        DiffNode::Replaced { old, new } => {
+            for (marker, path) in [('-', old), ('+', new)] {
+                render_diff_subtree(
+                    marker,
+                    path.hash(),
+                    level_of_indentation,
+                    nodes,
+                    adapter,
+                )?;
+            }
+        }
+        DiffNode::Inserted { new: path }
+        | DiffNode::MovedHere { new: path, .. } => {
+            let marker = if let DiffNode::Inserted { .. } = node {
+                '+'
+            } else {
+                '>'
+            };
+            render_diff_subtree(
+                marker,
+                path.hash(),
+                level_of_indentation,
+                nodes,
+                adapter,
+            )?;
+        }
+        DiffNode::Removed { old: path }
+        | DiffNode::MovedAway { old: path, .. } => {
+            let marker = if let DiffNode::Removed { .. } = node {
+                '-'
+            } else {
+                '<'
+            };
+            render_diff_subtree(
+                marker,
+                path.hash(),
+                level_of_indentation,
+                nodes,
+                adapter,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn render_diff_subtree<A: TerminalOutputAdapter>(
+    marker: char,
+    hash: &NodeHash,
+    level_of_indentation: u32,
+    nodes: &Nodes,
+    adapter: &mut A,
+) -> anyhow::Result<()> {
+    let node = nodes.get(hash);
+
+    let level_of_children = match node {
+        SyntaxNode::Add => {
+            return Ok(());
+        }
+        SyntaxNode::Body { .. } => level_of_indentation,
+        node => {
+            let token = node.to_token();
+            render_diff_line(marker, &token, level_of_indentation, adapter)?;
+
+            level_of_indentation + 1
+        }
+    };
+
+    for child in node.children().hashes {
+        render_diff_subtree(marker, child, level_of_children, nodes, adapter)?;
+    }
+
+    Ok(())
+}
+
+fn render_diff_line<A: TerminalOutputAdapter>(
+    marker: char,
+    token: &str,
+    level_of_indentation: u32,
+    adapter: &mut A,
+) -> anyhow::Result<()> {
+    let color = match marker {
+        '+' => Some(Color::DarkGreen),
+        '-' => Some(Color::DarkRed),
+        '<' | '>' => Some(Color::DarkYellow),
+        _ => None,
+    };
+
+    let write_line = |adapter: &mut A| -> anyhow::Result<()> {
+        write!(adapter, "{marker}   ")?;
+
+        for _ in 0..level_of_indentation {
+            let spaces = EditorLine::NUMBER_OF_SPACES_PER_LEVEL_OF_INDENTATION;
+            write!(adapter, "{:1$}", "", spaces as usize)?;
+        }
+
+        writeln!(adapter, "{token}")?;
+
+        Ok(())
+    };
+
+    if let Some(color) = color {
+        adapter.color(color, write_line)?;
+    } else {
+        write_line(adapter)?;
     }
 
     Ok(())
@@ -267,12 +597,21 @@ fn render_node<A: TerminalOutputAdapter>(
 fn render_prompt<A: TerminalOutputAdapter>(
     adapter: &mut A,
     editor_input: &TerminalEditorInput,
+    command_error: Option<&str>,
     context: &mut RenderContext,
 ) -> anyhow::Result<()> {
+    if let Some(error) = command_error {
+        adapter.color(Color::Red, |adapter| {
+            writeln!(adapter, "Command failed: {error}")?;
+            Ok(())
+        })?;
+    }
+
     match editor_input.mode() {
         EditorMode::Edit => {
             writeln!(adapter, "Currently editing.")?;
             writeln!(adapter, "Press ESC to enter command mode.")?;
+            writeln!(adapter, "Press CTRL+Z to undo, CTRL+Y to redo.")?;
         }
         EditorMode::Command { buffer, cursor } => {
             write!(adapter, "Enter command > ")?;
@@ -287,6 +626,31 @@ fn render_prompt<A: TerminalOutputAdapter>(
     Ok(())
 }
 
+fn render_type<A: TerminalOutputAdapter>(
+    adapter: &mut A,
+    context: &RenderContext,
+) -> anyhow::Result<()> {
+    let (Some(editor), Some(types)) = (context.editor, context.types) else {
+        return Ok(());
+    };
+
+    let path = &editor.cursor().path;
+
+    if let Some(type_) = types.type_of(path) {
+        writeln!(adapter)?;
+        writeln!(adapter, "Type: {type_}")?;
+    }
+
+    if let Some(error) = types.error_at(path) {
+        adapter.color(Color::Red, |adapter| {
+            writeln!(adapter, "Type error: {error}")?;
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
 // This help system is rather rudimentary. Just a first draft. But it could
 // serve as the foundation of a more full-featured help browser, which both
 // serves as a kind of tutorial, but also provides reference material.
@@ -318,9 +682,47 @@ fn render_help<A: TerminalOutputAdapter>(
                 "This is the application of a function to an argument.",
             )?;
         }
+        SyntaxNode::Arm { .. } => {
+            writeln!(
+                adapter,
+                "This is an arm of a match. Its first child is a pattern, the \
+                children below that are the arm's body. The body is evaluated, \
+                if the pattern matches the value.",
+            )?;
+        }
         SyntaxNode::Binding { .. } => {
             writeln!(adapter, "A binding that assigns a name to a value.")?;
         }
+        SyntaxNode::Boolean { value } => {
+            writeln!(
+                adapter,
+                "This is a boolean literal that produces the value `{value}`.",
+            )?;
+        }
+        SyntaxNode::BooleanPattern { value } => {
+            writeln!(
+                adapter,
+                "This is a pattern that matches the boolean value `{value}`.",
+            )?;
+        }
+        SyntaxNode::Conflict { .. } => {
+            writeln!(
+                adapter,
+                "This is a merge conflict. Both merged branches changed the \
+                code here. Its first child is the version from this branch, \
+                the second one the version from the merged branch. Use the \
+                `pick ours` or `pick theirs` command to resolve it."
+            )?;
+        }
+        SyntaxNode::Definition { .. } => {
+            writeln!(
+                adapter,
+                "This is a definition, which assigns a name to its value. \
+                Other code can refer to top-level definitions by that name. \
+                The definition named `{}` is where the program starts.",
+                Codebase::MAIN,
+            )?;
+        }
         SyntaxNode::Empty => {
             writeln!(
                 adapter,
@@ -334,14 +736,81 @@ fn render_help<A: TerminalOutputAdapter>(
                 "Expressions node is not directly displayed in the editor."
             );
         }
+        SyntaxNode::FieldAccess { .. } => {
+            writeln!(
+                adapter,
+                "This accesses a field of a record. Its first child is the \
+                record, the second one the name of the field.",
+            )?;
+        }
+        SyntaxNode::FieldUpdate { .. } => {
+            writeln!(
+                adapter,
+                "This produces a copy of a record, with some of its fields \
+                updated. Its first child is the record. Each definition below \
+                that provides a new value for one of the record's fields.",
+            )?;
+        }
+        SyntaxNode::Float { value } => {
+            writeln!(
+                adapter,
+                "This is a floating-point literal that produces the number \
+                `{value}`.",
+            )?;
+        }
         SyntaxNode::Function { .. } => {
             writeln!(
                 adapter,
                 "This is a function literal that produces a function value.",
             )?;
         }
-        SyntaxNode::Identifier { .. } => {
+        SyntaxNode::Identifier { name } => {
             writeln!(adapter, "You are editing an identifier.",)?;
+
+            if let Some(function) = context
+                .host_functions
+                .and_then(|host_functions| host_functions.get(name))
+            {
+                writeln!(adapter)?;
+                writeln!(
+                    adapter,
+                    "`{name}` is provided by the host. It expects `{}` and \
+                    produces `{}`.",
+                    function.parameter, function.output,
+                )?;
+                writeln!(adapter, "{}", function.documentation)?;
+            }
+        }
+        SyntaxNode::Integer64 { value } => {
+            writeln!(
+                adapter,
+                "This is a literal that produces the signed, 64-bit integer \
+                value `{value}`.",
+            )?;
+        }
+        SyntaxNode::Let { .. } => {
+            writeln!(
+                adapter,
+                "This binds the value of its second child to the name in its \
+                first child. The expressions that follow it can refer to the \
+                value by that name.",
+            )?;
+        }
+        SyntaxNode::List { .. } => {
+            writeln!(
+                adapter,
+                "This is a list literal that produces a list value, which \
+                contains the list's children. Intrinsic functions like `push` \
+                and `get_item` work with lists.",
+            )?;
+        }
+        SyntaxNode::Match { .. } => {
+            writeln!(
+                adapter,
+                "This is a match. Its first child is the value that is \
+                matched. Each of the following children is an arm. The first \
+                arm whose pattern matches the value is chosen.",
+            )?;
         }
         SyntaxNode::Number { value } => {
             writeln!(
@@ -350,6 +819,27 @@ fn render_help<A: TerminalOutputAdapter>(
                 `{value}`.",
             )?;
         }
+        SyntaxNode::NumberPattern { value } => {
+            writeln!(
+                adapter,
+                "This is a pattern that matches the integer value `{value}`.",
+            )?;
+        }
+        SyntaxNode::Record { .. } => {
+            writeln!(
+                adapter,
+                "This is a record literal that produces a record value. Each \
+                definition among its children defines one of the record's \
+                fields.",
+            )?;
+        }
+        SyntaxNode::RecordPattern { .. } => {
+            writeln!(
+                adapter,
+                "This is a pattern that matches a record. Each of its children \
+                binds the field of the same name.",
+            )?;
+        }
         SyntaxNode::Recursion => {
             writeln!(
                 adapter,
@@ -365,6 +855,35 @@ fn render_help<A: TerminalOutputAdapter>(
                 contains the tuple's children.",
             )?;
         }
+        SyntaxNode::TuplePattern { .. } => {
+            writeln!(
+                adapter,
+                "This is a pattern that matches a tuple, if each of the \
+                tuple's values matches the respective child of the pattern.",
+            )?;
+        }
+        SyntaxNode::String { .. } => {
+            writeln!(
+                adapter,
+                "This is a string literal that produces a piece of text. Within \
+                the quotes, write `\\s` for a space, `\\n` for a line break, \
+                `\\\"` for a quote, and `\\\\` for a backslash.",
+            )?;
+        }
+        SyntaxNode::Unsigned { value } => {
+            writeln!(
+                adapter,
+                "This is a literal that produces the unsigned, 32-bit integer \
+                value `{value}`.",
+            )?;
+        }
+        SyntaxNode::Wildcard => {
+            writeln!(
+                adapter,
+                "This is a wildcard pattern, which matches any value without \
+                binding it to a name.",
+            )?;
+        }
     }
 
     Ok(())
@@ -374,5 +893,67 @@ struct RenderContext<'r> {
     codebase: &'r Codebase,
     editor: Option<&'r Editor>,
     evaluator: Option<&'r Evaluator>,
+    host_functions: Option<&'r HostFunctions>,
+    types: Option<&'r Types>,
     cursor: Option<Cursor>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        language::{
+            code::{Diff, Nodes},
+            text::import_into,
+        },
+        terminal::StringOutputAdapter,
+    };
+
+    use super::render_diff;
+
+    #[test]
+    fn render_diff_as_annotated_tree() {
+        let mut nodes = Nodes::default();
+        let old = import_into(
+            "
+            apply
+                f
+                tuple
+                    1
+                    2
+            a
+            ",
+            &mut nodes,
+        )
+        .unwrap();
+        let new = import_into(
+            "
+            apply
+                f
+                tuple
+                    1
+                    3
+            b
+            ",
+            &mut nodes,
+        )
+        .unwrap();
+
+        let mut adapter = StringOutputAdapter {
+            output: String::new(),
+        };
+        render_diff(&Diff::between(old, new, &nodes), &nodes, &mut adapter)
+            .unwrap();
+
+        let expected = [
+            "    apply",
+            "        f",
+            "        tuple",
+            "            1",
+            "-           2",
+            "+           3",
+            "-   a",
+            "+   b",
+        ];
+        assert_eq!(adapter.output.lines().collect::<Vec<_>>(), expected);
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     language::{
         code::Type,
         language::Language,
-        runtime::{Effect, RuntimeState, Value},
+        runtime::{Effect, HostFunction, RuntimeState, Value},
     },
 };
 
@@ -21,6 +21,16 @@ use super::Camera;
 pub trait Init {
     fn name(&self) -> Option<&str>;
 
+    /// # The functions that the host provides to Crosscut code
+    ///
+    /// These are registered with [`Language`] before [`Init::init`] is called.
+    /// The editor checks applications of these functions, and shows their
+    /// documentation. Applications of functions that aren't registered here
+    /// are reported as errors, even if the game handles them.
+    fn host_functions(&self) -> Vec<HostFunction> {
+        Vec::new()
+    }
+
     async fn init(
         &mut self,
         language: &mut Language,
@@ -48,6 +58,27 @@ impl Init for PureCrosscutGameInit {
         None
     }
 
+    fn host_functions(&self) -> Vec<HostFunction> {
+        vec![
+            HostFunction {
+                name: "color".to_string(),
+                parameter: Type::Integer,
+                output: Type::Tuple { values: Vec::new() },
+                documentation: "Sets the background color to a shade of gray, \
+                    from `0` (black) to `255` (white), and ends the frame."
+                    .to_string(),
+            },
+            HostFunction {
+                name: "sleep_ms".to_string(),
+                parameter: Type::Integer,
+                output: Type::Tuple { values: Vec::new() },
+                documentation: "Pauses the program for the provided number \
+                    of milliseconds."
+                    .to_string(),
+            },
+        ]
+    }
+
     async fn init(
         &mut self,
         _: &mut Language,
@@ -167,56 +198,67 @@ impl PureCrosscutGame {
                 RuntimeState::Effect { effect, .. } => {
                     match effect {
                         Effect::ApplyProvidedFunction { name, input } => {
-                            match name.as_str() {
-                                "color" => match input {
-                                    Value::Integer { value } => {
-                                        let value: f64 = value.into();
-                                        let value = value / 255.;
-
-                                        self.color = wgpu::Color {
-                                            r: value,
-                                            g: value,
-                                            b: value,
-                                            a: 1.,
-                                        };
-
-                                        self.state = State::EndOfFrame;
-                                        break;
-                                    }
-                                    value => {
-                                        language.trigger_effect(
-                                            Effect::UnexpectedInput {
-                                                expected: Type::Integer,
-                                                actual: value,
-                                            },
-                                        );
-                                    }
-                                },
-                                "sleep_ms" => match input {
-                                    Value::Integer { value } if value >= 0 => {
-                                        let value = value as u64;
-
-                                        self.state = State::WaitUntil {
-                                            instant: Instant::now()
-                                                + Duration::from_millis(value),
-                                        };
-                                        break;
-                                    }
-                                    value => {
-                                        language.trigger_effect(
-                                            Effect::UnexpectedInput {
-                                                expected: Type::Integer,
-                                                actual: value,
-                                            },
-                                        );
-                                    }
-                                },
-                                _ => {
+                            // The signatures of the functions we handle here
+                            // are the ones we registered in `host_functions`.
+                            let Some(expected) = language
+                                .host_functions()
+                                .get(&name)
+                                .map(|function| function.parameter.clone())
+                            else {
+                                language.trigger_effect(
+                                    Effect::ProvidedFunctionNotFound,
+                                );
+                                continue;
+                            };
+
+                            if input.type_() != expected {
+                                language.trigger_effect(
+                                    Effect::UnexpectedInput {
+                                        expected,
+                                        actual: input,
+                                    },
+                                );
+                                continue;
+                            }
+
+                            match (name.as_str(), input) {
+                                ("color", Value::Integer { value }) => {
+                                    let value: f64 = value.into();
+                                    let value = value / 255.;
+
+                                    self.color = wgpu::Color {
+                                        r: value,
+                                        g: value,
+                                        b: value,
+                                        a: 1.,
+                                    };
+
+                                    self.state = State::EndOfFrame;
+                                    break;
+                                }
+                                ("sleep_ms", Value::Integer { value })
+                                    if value >= 0 =>
+                                {
+                                    let value = value as u64;
+
+                                    self.state = State::WaitUntil {
+                                        instant: Instant::now()
+                                            + Duration::from_millis(value),
+                                    };
+                                    break;
+                                }
+                                (_, actual) => {
+                                    // The input has the registered type, but
+                                    // not a value we can handle, like a
+                                    // negative duration.
                                     language.trigger_effect(
-                                        Effect::ProvidedFunctionNotFound,
+                                        Effect::UnexpectedInput {
+                                            expected,
+                                            actual,
+                                        },
                                     );
                                 }
-                            };
+                            }
                             continue;
                         }
                         _ => {
@@ -226,13 +268,14 @@ impl PureCrosscutGame {
                     }
                 }
                 RuntimeState::Finished { output } => {
-                    if let Ok(body) = output.into_function_body() {
-                        // If the program returns a function, we call that.
+                    if let Ok((body, environment)) = output.into_function() {
+                        // The program starts by evaluating the `main`
+                        // definition. If that is a function, we call it.
                         //
-                        // Eventually, we would want something more stringent
-                        // here, like expect a `main` function, or a module in a
-                        // specific format. For now, this will do though.
-                        language.apply_function(body);
+                        // Code without a `main` definition is evaluated as a
+                        // whole, and if that returns a function, we call that
+                        // instead.
+                        language.apply_function(body, environment);
                         continue;
                     }
                 }
@@ -4,7 +4,7 @@ use pollster::FutureExt;
 use winit::{keyboard::KeyCode, window::Window};
 
 use crate::{
-    language::language::Language,
+    language::{code::Store, language::Language},
     terminal::{RawTerminalAdapter, TerminalOutputAdapter},
 };
 
@@ -16,11 +16,31 @@ use super::{
     },
 };
 
+/// # The directory that the code is stored in
+///
+/// This is relative to the current working directory.
+const PROJECT_DIR: &str = ".crosscut";
+
+/// # The number of edits after which garbage is collected
+const EDITS_PER_GARBAGE_COLLECTION: u32 = 256;
+
+/// # The number of change sets that are kept in the history
+///
+/// This determines how many edits can be undone.
+const RETAINED_CHANGE_SETS: usize = 1024;
+
 pub struct GameEngine<A> {
     game: Box<dyn Game>,
     language: Language,
+    store: Store,
+    edits_since_garbage_collection: u32,
     editor_input: TerminalEditorInput,
     editor_output: TerminalEditorOutput<A>,
+
+    /// # The error from the most recent command, if it failed
+    ///
+    /// This is shown to the user, until the next input.
+    command_error: Option<String>,
 }
 
 impl GameEngine<RawTerminalAdapter> {
@@ -46,7 +66,11 @@ where
         window: &Arc<Window>,
         adapter: A,
     ) -> anyhow::Result<Self> {
-        let mut language = Language::new();
+        let mut store = Store::new(PROJECT_DIR);
+        let codebase = store.load()?.unwrap_or_default();
+
+        let mut language = Language::with_codebase(codebase);
+        language.register_host_functions(init.host_functions());
 
         if let Some(name) = init.name() {
             window.set_title(name);
@@ -58,8 +82,11 @@ where
         Ok(Self {
             game,
             language,
+            store,
+            edits_since_garbage_collection: 0,
             editor_input: TerminalEditorInput::new(),
             editor_output: TerminalEditorOutput::new(adapter),
+            command_error: None,
         })
     }
 
@@ -67,12 +94,21 @@ where
         &mut self,
         input: TerminalInput,
     ) -> anyhow::Result<()> {
+        self.command_error = None;
+
         match self.editor_input.on_input(input) {
             Some(EditorInputOrCommand::Input { input }) => {
                 self.language.on_editor_input(input);
+                self.on_edit()?;
             }
             Some(EditorInputOrCommand::Command { command }) => {
-                self.language.on_editor_command(command)?;
+                // A command that fails, like switching to a branch that
+                // doesn't exist, is a mistake that the user can correct. No
+                // reason to shut down.
+                if let Err(err) = self.language.on_editor_command(command) {
+                    self.command_error = Some(err.to_string());
+                }
+                self.on_edit()?;
             }
             None => {}
         }
@@ -98,9 +134,24 @@ where
         Ok(())
     }
 
+    fn on_edit(&mut self) -> anyhow::Result<()> {
+        self.store.save(self.language.codebase())?;
+
+        self.edits_since_garbage_collection += 1;
+        if self.edits_since_garbage_collection >= EDITS_PER_GARBAGE_COLLECTION {
+            self.language.collect_garbage(RETAINED_CHANGE_SETS);
+            self.edits_since_garbage_collection = 0;
+        }
+
+        Ok(())
+    }
+
     fn render_editor(&mut self) -> anyhow::Result<()> {
-        self.editor_output
-            .render(&self.language, &self.editor_input)?;
+        self.editor_output.render(
+            &self.language,
+            &self.editor_input,
+            self.command_error.as_deref(),
+        )?;
 
         Ok(())
     }
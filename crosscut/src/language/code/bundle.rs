@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Diagnostic, ExpectedNode, NodeHash, Nodes, SyntaxNode, check_integrity,
+};
+
+/// # A self-contained subtree of the syntax tree
+///
+/// Contains a node and all of its descendants, keyed by their [`NodeHash`].
+/// Bundles can be written to a file, and imported into another codebase from
+/// there. That makes it possible to share code, like a function, between
+/// projects.
+///
+/// Bundles are verified when importing them. See [`Bundle::import_into`].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Bundle {
+    version: u32,
+    root: NodeHash,
+    nodes: BTreeMap<NodeHash, SyntaxNode>,
+}
+
+impl Bundle {
+    /// # The version of the format that bundles are written in
+    ///
+    /// This must be incremented on every incompatible change to the format.
+    pub const VERSION: u32 = 1;
+
+    /// # Bundle a node with all of its descendants
+    pub fn new(root: &NodeHash, nodes: &Nodes) -> Self {
+        let mut bundled = BTreeMap::new();
+        let mut to_visit = vec![*root];
+
+        while let Some(hash) = to_visit.pop() {
+            if bundled.contains_key(&hash) {
+                continue;
+            }
+
+            let node = nodes.get(&hash);
+            to_visit.extend(node.children().hashes);
+            bundled.insert(hash, node.clone());
+        }
+
+        Self {
+            version: Self::VERSION,
+            root: *root,
+            nodes: bundled,
+        }
+    }
+
+    /// # Read a bundle from a file
+    ///
+    /// The bundle is not verified at this point. That only happens when it's
+    /// imported.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, BundleError> {
+        let file = File::open(path)?;
+        let bundle: Self = serde_json::from_reader(BufReader::new(file))?;
+
+        if bundle.version != Self::VERSION {
+            return Err(BundleError::UnsupportedVersion {
+                version: bundle.version,
+            });
+        }
+
+        Ok(bundle)
+    }
+
+    /// # Write the bundle to a file
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), BundleError> {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// # The hash of the bundled node
+    pub fn root(&self) -> &NodeHash {
+        &self.root
+    }
+
+    /// # Import the bundled nodes
+    ///
+    /// The bundled code is checked for integrity first. Among other things,
+    /// this means every node is hashed anew and compared to the hash it was
+    /// bundled under. This guarantees that the imported code is exactly what
+    /// was bundled, as a bundle whose nodes were modified since is rejected.
+    /// See [`check_integrity`].
+    ///
+    /// The bundled node must be an expression. Returns its hash, which can
+    /// then be inserted into the syntax tree.
+    pub fn import_into(
+        &self,
+        nodes: &mut Nodes,
+    ) -> Result<NodeHash, BundleError> {
+        let diagnostics =
+            check_integrity([(self.root, ExpectedNode::Expression)], |hash| {
+                self.nodes.get(hash)
+            });
+        if !diagnostics.is_empty() {
+            return Err(BundleError::Integrity { diagnostics });
+        }
+
+        // Only insert the nodes that were checked. A bundle might contain
+        // others, if somebody added them.
+        let mut to_insert = vec![self.root];
+        while let Some(hash) = to_insert.pop() {
+            let Some(node) = self.nodes.get(&hash) else {
+                unreachable!("Integrity check makes sure no node is missing.");
+            };
+
+            to_insert.extend(node.children().hashes);
+            nodes.insert(node.clone());
+        }
+
+        Ok(self.root)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error(transparent)]
+    Io {
+        #[from]
+        err: io::Error,
+    },
+
+    #[error(transparent)]
+    Format {
+        #[from]
+        err: serde_json::Error,
+    },
+
+    #[error("Bundle has unsupported format version {version}")]
+    UnsupportedVersion { version: u32 },
+
+    #[error("Bundled code is malformed: {diagnostics:#?}")]
+    Integrity { diagnostics: Vec<Diagnostic> },
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{
+        code::{Diagnostic, NodeHash, Nodes, SyntaxNode},
+        text::import_into,
+    };
+
+    use super::{Bundle, BundleError};
+
+    #[test]
+    fn import_bundle_into_other_nodes() {
+        let mut nodes = Nodes::default();
+        let root = function(&mut nodes);
+
+        let bundle = Bundle::new(&root, &nodes);
+        let json = serde_json::to_string(&bundle).unwrap();
+        let bundle: Bundle = serde_json::from_str(&json).unwrap();
+
+        let mut other_nodes = Nodes::default();
+        let imported = bundle.import_into(&mut other_nodes).unwrap();
+
+        assert_eq!(imported, root);
+        assert_eq!(other_nodes.get(&imported), nodes.get(&root));
+    }
+
+    #[test]
+    fn reject_nodes_whose_hashes_do_not_match() {
+        // Every modified node is reported, not just the first one found.
+
+        let mut nodes = Nodes::default();
+        let root = function(&mut nodes);
+
+        let mut bundle = Bundle::new(&root, &nodes);
+        let mut tampered = Vec::new();
+        for (hash, node) in &mut bundle.nodes {
+            let replacement = match node {
+                SyntaxNode::Number { .. } => SyntaxNode::Number { value: 2 },
+                SyntaxNode::Identifier { name } if name == "+" => {
+                    SyntaxNode::Identifier {
+                        name: "-".to_string(),
+                    }
+                }
+                _ => continue,
+            };
+
+            *node = replacement;
+            tampered.push(*hash);
+        }
+        assert_eq!(tampered.len(), 2);
+
+        let Err(BundleError::Integrity { diagnostics }) =
+            bundle.import_into(&mut Nodes::default())
+        else {
+            panic!("Expected modified nodes to be detected.");
+        };
+        let mut detected = diagnostics
+            .iter()
+            .map(|diagnostic| match diagnostic {
+                Diagnostic::HashMismatch { expected, .. } => *expected,
+                diagnostic => panic!("Unexpected diagnostic: {diagnostic:?}"),
+            })
+            .collect::<Vec<_>>();
+        detected.sort();
+        tampered.sort();
+        assert_eq!(detected, tampered);
+    }
+
+    #[test]
+    fn reject_bundle_with_missing_node() {
+        let mut nodes = Nodes::default();
+        let root = function(&mut nodes);
+
+        let mut bundle = Bundle::new(&root, &nodes);
+        bundle.nodes.remove(&root);
+
+        let Err(BundleError::Integrity { diagnostics }) =
+            bundle.import_into(&mut Nodes::default())
+        else {
+            panic!("Expected missing node to be detected.");
+        };
+        assert_eq!(diagnostics, [Diagnostic::MissingNode { hash: root }]);
+    }
+
+    fn function(nodes: &mut Nodes) -> NodeHash {
+        let body = import_into(
+            "
+            fn
+                x
+                apply
+                    +
+                    tuple
+                        x
+                        1
+            ",
+            nodes,
+        )
+        .unwrap();
+
+        let SyntaxNode::Body { children, .. } = nodes.get(&body) else {
+            unreachable!("Imported code is always a body.");
+        };
+
+        children[0]
+    }
+}
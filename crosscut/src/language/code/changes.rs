@@ -5,6 +5,19 @@ use super::{NodeHash, NodePath, Nodes};
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Changes {
     change_sets: Vec<ChangeSet>,
+
+    /// # Indices of the change sets that can be undone
+    ///
+    /// The change set that was made most recently comes last. Undoing a change
+    /// set doesn't remove it from the history. Instead, a new change set is
+    /// made that reverts it, and its index is moved to the redo stack.
+    undo_stack: Vec<usize>,
+
+    /// # Indices of the change sets that can be redone
+    ///
+    /// These are the change sets that were made to undo something. Redoing
+    /// means reverting them.
+    redo_stack: Vec<usize>,
 }
 
 impl Changes {
@@ -14,6 +27,7 @@ impl Changes {
         nodes: &'r mut Nodes,
     ) -> NewChangeSet<'r> {
         self.change_sets.push(ChangeSet {
+            root_before_change,
             replacements_by_replaced: BTreeMap::new(),
         });
 
@@ -56,6 +70,79 @@ impl Changes {
 
         latest_known
     }
+
+    /// # Drop all but the most recent change sets
+    ///
+    /// Dropped change sets can no longer be undone, and the latest versions of
+    /// nodes they replaced can no longer be found.
+    pub fn limit_history(&mut self, max_change_sets: usize) {
+        let num_to_drop =
+            self.change_sets.len().saturating_sub(max_change_sets);
+        self.change_sets.drain(..num_to_drop);
+
+        for stack in [&mut self.undo_stack, &mut self.redo_stack] {
+            stack.retain(|&index| index >= num_to_drop);
+
+            for index in stack {
+                *index -= num_to_drop;
+            }
+        }
+    }
+
+    /// # The roots of all syntax trees that the history refers to
+    pub fn roots(&self) -> impl Iterator<Item = NodeHash> + '_ {
+        self.change_sets.iter().flat_map(|change_set| {
+            [
+                change_set.root_before_change,
+                change_set.root_after_change(),
+            ]
+            .into_iter()
+            .chain(
+                change_set.replacements_by_replaced.iter().flat_map(
+                    |(replaced, replacement)| {
+                        [*replaced.root_hash(), *replacement.root_hash()]
+                    },
+                ),
+            )
+        })
+    }
+
+    /// # Mark the most recent change set as an edit that can be undone
+    ///
+    /// Making any edit means that whatever was undone before, can no longer be
+    /// redone.
+    pub fn record_edit(&mut self) {
+        if let Some(index) = self.change_sets.len().checked_sub(1) {
+            self.undo_stack.push(index);
+            self.redo_stack.clear();
+        }
+    }
+
+    pub fn change_set_to_undo(&self) -> Option<&ChangeSet> {
+        self.undo_stack
+            .last()
+            .map(|&index| &self.change_sets[index])
+    }
+
+    pub fn change_set_to_redo(&self) -> Option<&ChangeSet> {
+        self.redo_stack
+            .last()
+            .map(|&index| &self.change_sets[index])
+    }
+
+    /// # Record that the most recent change set undid the last edit
+    pub fn record_undo(&mut self) {
+        if self.undo_stack.pop().is_some() {
+            self.redo_stack.push(self.change_sets.len() - 1);
+        }
+    }
+
+    /// # Record that the most recent change set redid the last undone edit
+    pub fn record_redo(&mut self) {
+        if self.redo_stack.pop().is_some() {
+            self.undo_stack.push(self.change_sets.len() - 1);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -120,15 +207,34 @@ impl NewChangeSet<'_> {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ChangeSet {
+    root_before_change: NodeHash,
     replacements_by_replaced: BTreeMap<NodePath, NodePath>,
 }
 
 impl ChangeSet {
+    pub fn root_before_change(&self) -> NodeHash {
+        self.root_before_change
+    }
+
+    pub fn root_after_change(&self) -> NodeHash {
+        let root = NodePath::for_root(self.root_before_change);
+
+        self.was_replaced(&root)
+            .map(|path| *path.hash())
+            .unwrap_or(self.root_before_change)
+    }
+
+    pub fn replacements(
+        &self,
+    ) -> impl Iterator<Item = (&NodePath, &NodePath)> + '_ {
+        self.replacements_by_replaced.iter()
+    }
+
     pub fn was_replaced(&self, replaced: &NodePath) -> Option<&NodePath> {
         self.replacements_by_replaced.get(replaced)
     }
 
-    fn latest_version_of<'r>(
+    pub(super) fn latest_version_of<'r>(
         &'r self,
         path: &'r NodePath,
     ) -> Result<&'r NodePath, CircularDependency> {
@@ -150,7 +256,7 @@ impl ChangeSet {
     }
 }
 
-struct CircularDependency;
+pub(super) struct CircularDependency;
 
 #[cfg(test)]
 mod tests {
@@ -1,5 +1,16 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    mem,
+};
+
+use itertools::Itertools;
+
 use super::{
-    Body, Changes, LocatedNode, NewChangeSet, NodeHash, NodePath, Nodes,
+    Body, ChangeSet, Changes, Diagnostic, ExpectedNode, LocatedNode,
+    NewChangeSet, NodeHash, NodeId, NodeIds, NodePath, Nodes, SyntaxNode,
+    check_integrity, merge,
+    node_ids::StoredNodeIds,
+    observers::{CodebaseEvent, ObserverId, Observers},
 };
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -7,43 +18,267 @@ pub struct Codebase {
     root: Root,
     nodes: Nodes,
     changes: Changes,
+    ids: NodeIds,
+
+    /// # The name of the branch that `root`, `changes`, and `ids` belong to
+    branch: String,
+
+    /// # All branches, except the current one
+    other_branches: BTreeMap<String, Branch>,
+
+    tags: BTreeMap<String, NodeHash>,
+
+    /// # The most recent common ancestor of each pair of related branches
+    ///
+    /// Keyed by the names of both branches, in sorted order.
+    merge_bases: BTreeMap<(String, String), NodeHash>,
+
+    observers: Observers,
 }
 
 impl Codebase {
+    /// # The name of the branch that a new codebase starts out on
+    pub const DEFAULT_BRANCH: &str = "main";
+
+    /// # The name of the definition that is the program's entry point
+    ///
+    /// See [`Codebase::entry_point`].
+    pub const MAIN: &str = "main";
+
     pub fn new() -> Self {
         let mut nodes = Nodes::default();
 
         let root = {
             let node = Body::default().into_syntax_node(&mut nodes);
-            let hash = nodes.insert(node);
-            Root { hash }
+            nodes.insert(node)
+        };
+
+        Self::from_root(root, nodes)
+    }
+
+    /// # Construct a codebase from previously stored nodes
+    ///
+    /// Expects `root` to refer to a node in `nodes`. The history of changes
+    /// starts out empty.
+    pub fn from_root(root: NodeHash, nodes: Nodes) -> Self {
+        let branch = Self::DEFAULT_BRANCH.to_string();
+        let branches = BTreeMap::from([(branch.clone(), root)]);
+
+        Self::from_branches(
+            branch,
+            branches,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            nodes,
+        )
+    }
+
+    /// # Construct a codebase with multiple branches from stored nodes
+    ///
+    /// Expects all roots in `branches`, `tags`, and `merge_bases` to refer to
+    /// nodes in `nodes`. The history of changes starts out empty on all
+    /// branches.
+    ///
+    /// `merge_bases` are keyed by the names of both branches, in sorted order.
+    /// See [`Codebase::merge_branch`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics, if `branch`, which becomes the current branch, is not among
+    /// `branches`.
+    pub fn from_branches(
+        branch: String,
+        branches: BTreeMap<String, NodeHash>,
+        tags: BTreeMap<String, NodeHash>,
+        merge_bases: BTreeMap<(String, String), NodeHash>,
+        nodes: Nodes,
+    ) -> Self {
+        let mut other_branches = branches
+            .into_iter()
+            .map(|(name, root)| (name, Branch::new(root, &nodes)))
+            .collect::<BTreeMap<_, _>>();
+
+        for root in tags.values().chain(merge_bases.values()) {
+            // Make sure the tag or merge base actually refers to a node.
+            let _ = nodes.get(root);
+        }
+
+        let Some(Branch { root, changes, ids }) =
+            other_branches.remove(&branch)
+        else {
+            panic!("Current branch `{branch}` is not among the branches.");
         };
 
         Self {
             root,
             nodes,
-            changes: Changes::default(),
+            changes,
+            ids,
+            branch,
+            other_branches,
+            tags,
+            merge_bases,
+            observers: Observers::default(),
         }
     }
 
+    /// # Register an observer that is notified of every change to the code
+    ///
+    /// See [`CodebaseEvent`] for details on when observers are notified.
+    /// Observers are called in the order in which they were registered.
+    pub fn subscribe(
+        &mut self,
+        observer: impl FnMut(&CodebaseEvent) + 'static,
+    ) -> ObserverId {
+        self.observers.insert(observer)
+    }
+
+    /// # Remove an observer that was registered before
+    ///
+    /// Returns `false`, if no such observer is registered.
+    pub fn unsubscribe(&mut self, id: ObserverId) -> bool {
+        self.observers.remove(id)
+    }
+
+    /// # Replace the code on the current branch with empty code
+    ///
+    /// The history of changes on the current branch starts out empty again.
+    /// Other branches, tags, and registered observers are kept.
+    pub fn clear(&mut self) {
+        let root_before = self.root.hash;
+
+        let root = {
+            let node = Body::default().into_syntax_node(&mut self.nodes);
+            self.nodes.insert(node)
+        };
+
+        self.root = Root { hash: root };
+        self.changes = Changes::default();
+        self.ids = NodeIds::new(root, &self.nodes);
+
+        self.observers.notify(&CodebaseEvent {
+            root_before,
+            root_after: self.root.hash,
+            change_set: None,
+            nodes: &self.nodes,
+        });
+    }
+
     pub fn nodes(&self) -> &Nodes {
         &self.nodes
     }
 
-    pub fn root(&self) -> LocatedNode {
+    pub fn root(&self) -> LocatedNode<'_> {
         LocatedNode {
             node: self.nodes.get(&self.root.hash),
             path: self.root.path(),
         }
     }
 
-    pub fn node_at(&self, path: &NodePath) -> LocatedNode {
+    /// # All top-level definitions, with their names
+    ///
+    /// Top-level definitions are those that are children of the root body.
+    /// They are returned in the order they appear in.
+    pub fn definitions(&self) -> impl Iterator<Item = (&str, LocatedNode<'_>)> {
+        let definitions = self
+            .root()
+            .children(&self.nodes)
+            .filter_map(|definition| {
+                let SyntaxNode::Definition { name, .. } = definition.node
+                else {
+                    return None;
+                };
+                let SyntaxNode::Binding { name } = self.nodes.get(name) else {
+                    return None;
+                };
+
+                Some((name.as_str(), definition))
+            })
+            .collect::<Vec<_>>();
+
+        definitions.into_iter()
+    }
+
+    /// # Find the value of the top-level definition with the provided name
+    ///
+    /// If there are multiple definitions with that name, the first one wins.
+    pub fn definition(&self, name: &str) -> Option<NodePath> {
+        let (_, definition) = self
+            .definitions()
+            .find(|(definition, _)| *definition == name)?;
+        let [_, value] = definition.children(&self.nodes).collect_array()?;
+
+        Some(value.path)
+    }
+
+    /// # The expression that evaluation of the program starts with
+    ///
+    /// This is the value of the top-level definition named [`Codebase::MAIN`],
+    /// if there is one. Otherwise, the whole root body is evaluated.
+    pub fn entry_point(&self) -> NodePath {
+        self.definition(Self::MAIN)
+            .unwrap_or_else(|| self.root().path)
+    }
+
+    pub fn node_at(&self, path: &NodePath) -> LocatedNode<'_> {
         LocatedNode {
             node: self.nodes.get(path.hash()),
             path: path.clone(),
         }
     }
 
+    /// # Find the stable ID of the node at the provided path
+    ///
+    /// Returns `None`, if the path doesn't point into the current syntax tree.
+    pub fn id_of(&self, path: &NodePath) -> Option<NodeId> {
+        if path.root_hash() != &self.root.hash {
+            return None;
+        }
+
+        Some(self.ids.id_of(path))
+    }
+
+    /// # Find the current path of the node with the provided ID
+    ///
+    /// Returns `None`, if the node is no longer part of the syntax tree.
+    pub fn path_of(&self, id: NodeId) -> Option<NodePath> {
+        self.ids.path_of(id, self.root.hash, &self.nodes)
+    }
+
+    /// # The IDs of the nodes on all branches, ready to be stored
+    pub(super) fn stored_ids(&self) -> BTreeMap<String, StoredNodeIds> {
+        self.other_branches
+            .iter()
+            .map(|(name, branch)| (name.clone(), branch.ids.to_stored()))
+            .chain([(self.branch.clone(), self.ids.to_stored())])
+            .collect()
+    }
+
+    /// # Restore the IDs of the nodes on all branches
+    ///
+    /// Keeps the current IDs of a branch, if the stored ones don't match its
+    /// syntax tree. Stored IDs of unknown branches are ignored.
+    pub(super) fn restore_ids(
+        &mut self,
+        stored: BTreeMap<String, StoredNodeIds>,
+    ) {
+        for (name, stored) in stored {
+            let (root, ids) = if name == self.branch {
+                (self.root.hash, &mut self.ids)
+            } else if let Some(branch) = self.other_branches.get_mut(&name) {
+                (branch.root.hash, &mut branch.ids)
+            } else {
+                continue;
+            };
+
+            if let Some(restored) =
+                NodeIds::from_stored(stored, root, &self.nodes)
+            {
+                *ids = restored;
+            }
+        }
+    }
+
     pub fn latest_version_of<'r>(&'r self, path: &'r NodePath) -> &'r NodePath {
         self.changes.latest_version_of(path)
     }
@@ -51,6 +286,338 @@ impl Codebase {
     pub fn make_change<R>(
         &mut self,
         f: impl FnOnce(&mut NewChangeSet) -> R,
+    ) -> R {
+        let root_before_change = self.root.hash;
+        let value = self.make_change_without_recording_edit(f);
+
+        if self.root.hash != root_before_change {
+            self.changes.record_edit();
+        }
+
+        value
+    }
+
+    /// # Undo the most recent edit that hasn't been undone yet
+    ///
+    /// Undoing an edit doesn't remove it from the history. It makes a new
+    /// change that replaces every node the edit replaced with its previous
+    /// version. This means [`Codebase::latest_version_of`] keeps working.
+    ///
+    /// Returns `false`, if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(change_set) = self.changes.change_set_to_undo().cloned()
+        else {
+            return false;
+        };
+
+        self.revert(&change_set);
+        self.changes.record_undo();
+
+        true
+    }
+
+    /// # Redo the most recent edit that was undone
+    ///
+    /// Returns `false`, if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(change_set) = self.changes.change_set_to_redo().cloned()
+        else {
+            return false;
+        };
+
+        self.revert(&change_set);
+        self.changes.record_redo();
+
+        true
+    }
+
+    /// # The name of the current branch
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// # All branches, including the current one, with their roots
+    pub fn branches(&self) -> impl Iterator<Item = (&str, NodeHash)> {
+        let mut branches = self
+            .other_branches
+            .iter()
+            .map(|(name, branch)| (name.as_str(), branch.root.hash))
+            .chain([(self.branch.as_str(), self.root.hash)])
+            .collect::<Vec<_>>();
+        branches.sort();
+
+        branches.into_iter()
+    }
+
+    /// # All tags, with the roots they refer to
+    pub fn tags(&self) -> impl Iterator<Item = (&str, NodeHash)> {
+        self.tags.iter().map(|(name, root)| (name.as_str(), *root))
+    }
+
+    /// # The common ancestors of all pairs of related branches
+    ///
+    /// See [`Codebase::merge_branch`].
+    pub fn merge_bases(&self) -> impl Iterator<Item = (&str, &str, NodeHash)> {
+        self.merge_bases
+            .iter()
+            .map(|((a, b), root)| (a.as_str(), b.as_str(), *root))
+    }
+
+    /// # Check all versions of the code for structural problems
+    ///
+    /// Checks the syntax trees of all branches, tags, and merge bases. Code
+    /// that was created through the codebase is always well-formed, so this is
+    /// mostly useful for code that was modified from outside. See
+    /// [`check_integrity`].
+    pub fn check_integrity(&self) -> Vec<Diagnostic> {
+        let roots = self
+            .branches()
+            .map(|(_, root)| root)
+            .chain(self.tags().map(|(_, root)| root))
+            .chain(self.merge_bases().map(|(_, _, root)| root))
+            .map(|root| (root, ExpectedNode::Body))
+            .collect::<Vec<_>>();
+
+        check_integrity(roots, |hash| {
+            self.nodes.contains(hash).then(|| self.nodes.get(hash))
+        })
+    }
+
+    /// # All conflicts in the current code, in the order they appear in
+    pub fn conflicts(&self) -> Vec<NodePath> {
+        let mut conflicts = Vec::new();
+        collect_conflicts(self.root(), self, &mut conflicts);
+        return conflicts;
+
+        fn collect_conflicts(
+            located_node: LocatedNode,
+            codebase: &Codebase,
+            conflicts: &mut Vec<NodePath>,
+        ) {
+            if let SyntaxNode::Conflict { .. } = located_node.node {
+                conflicts.push(located_node.path.clone());
+            }
+
+            for child in located_node.children(codebase.nodes()) {
+                collect_conflicts(child, codebase, conflicts);
+            }
+        }
+    }
+
+    /// # Give the current version of the code a name
+    ///
+    /// The tag keeps referring to this version, regardless of what happens to
+    /// the code afterwards.
+    pub fn tag(&mut self, name: &str) -> Result<(), VersionError> {
+        if self.tags.contains_key(name) {
+            return Err(VersionError::TagAlreadyExists {
+                name: name.to_string(),
+            });
+        }
+
+        self.tags.insert(name.to_string(), self.root.hash);
+
+        Ok(())
+    }
+
+    /// # Change the code on the current branch to a tagged version
+    ///
+    /// This is a normal change, which can be undone.
+    pub fn checkout(&mut self, tag: &str) -> Result<(), VersionError> {
+        let Some(root) = self.tags.get(tag).copied() else {
+            return Err(VersionError::UnknownTag {
+                name: tag.to_string(),
+            });
+        };
+
+        let root_before_change = self.root.path();
+        self.make_change(|change_set| {
+            change_set.replace(&root_before_change, &NodePath::for_root(root));
+        });
+
+        Ok(())
+    }
+
+    /// # Create a new branch, starting at the current version of the code
+    ///
+    /// The current branch stays the same. Use [`Codebase::switch_branch`] to
+    /// switch to the new one.
+    pub fn create_branch(&mut self, name: &str) -> Result<(), VersionError> {
+        if name == self.branch || self.other_branches.contains_key(name) {
+            return Err(VersionError::BranchAlreadyExists {
+                name: name.to_string(),
+            });
+        }
+
+        self.other_branches.insert(
+            name.to_string(),
+            Branch {
+                root: self.root.clone(),
+                changes: Changes::default(),
+                ids: self.ids.clone(),
+            },
+        );
+        self.merge_bases
+            .insert(merge_base_key(&self.branch, name), self.root.hash);
+
+        Ok(())
+    }
+
+    /// # Switch to another branch
+    ///
+    /// Each branch has its own root and history of changes. Switching to a
+    /// branch makes those current, while the previously current branch keeps
+    /// its own.
+    ///
+    /// The history of the branch that is switched to records that its root
+    /// replaced the previous one. That isn't an edit that can be undone, but
+    /// it lets [`Codebase::latest_version_of`] find the new root, so running
+    /// code can be updated.
+    pub fn switch_branch(&mut self, name: &str) -> Result<(), VersionError> {
+        if name == self.branch {
+            return Ok(());
+        }
+
+        let Some(branch) = self.other_branches.remove(name) else {
+            return Err(VersionError::UnknownBranch {
+                name: name.to_string(),
+            });
+        };
+
+        let previous = Branch {
+            root: mem::replace(&mut self.root, branch.root),
+            changes: mem::replace(&mut self.changes, branch.changes),
+            ids: mem::replace(&mut self.ids, branch.ids),
+        };
+        let previous_name = mem::replace(&mut self.branch, name.to_string());
+
+        self.changes
+            .new_change_set(previous.root.hash, &mut self.nodes)
+            .replace(&previous.root.path(), &self.root.path());
+
+        self.observers.notify(&CodebaseEvent {
+            root_before: previous.root.hash,
+            root_after: self.root.hash,
+            change_set: None,
+            nodes: &self.nodes,
+        });
+
+        self.other_branches.insert(previous_name, previous);
+
+        Ok(())
+    }
+
+    /// # Merge another version of the code into the current code
+    ///
+    /// `base` must be the most recent version that both the current code and
+    /// `theirs` descend from. Where both changed the same node in different
+    /// ways, the merged code contains a [`SyntaxNode::Conflict`]. Use
+    /// [`Codebase::conflicts`] to find them.
+    ///
+    /// This is a normal change, which can be undone.
+    pub fn merge(&mut self, base: NodeHash, theirs: NodeHash) {
+        let root_before_change = self.root.path();
+        self.make_change(|change_set| {
+            let merged = merge(
+                base,
+                *root_before_change.hash(),
+                theirs,
+                change_set.nodes,
+            );
+            change_set
+                .replace(&root_before_change, &NodePath::for_root(merged));
+        });
+    }
+
+    /// # Merge another branch into the current one
+    ///
+    /// The common ancestor of both branches is the version that one of them was
+    /// created from, or the version of the merged branch that was last merged
+    /// between them. Branches that weren't created from one another, don't
+    /// have a common ancestor and can't be merged.
+    ///
+    /// See [`Codebase::merge`].
+    pub fn merge_branch(&mut self, name: &str) -> Result<(), VersionError> {
+        if name == self.branch {
+            return Ok(());
+        }
+
+        let Some(theirs) =
+            self.other_branches.get(name).map(|branch| branch.root.hash)
+        else {
+            return Err(VersionError::UnknownBranch {
+                name: name.to_string(),
+            });
+        };
+
+        let key = merge_base_key(&self.branch, name);
+        let Some(base) = self.merge_bases.get(&key).copied() else {
+            return Err(VersionError::NoMergeBase {
+                name: name.to_string(),
+            });
+        };
+
+        self.merge(base, theirs);
+
+        // Everything up to this version of the merged branch is now part of
+        // the current one.
+        self.merge_bases.insert(key, theirs);
+
+        Ok(())
+    }
+
+    /// # Remove nodes that are no longer needed
+    ///
+    /// Keeps the `retained_change_sets` most recent change sets, and all nodes
+    /// that are reachable from the current root, from the roots that the
+    /// retained change sets refer to, and from the provided `live_roots`.
+    ///
+    /// The same applies to all other branches. Tagged versions of the code are
+    /// always kept.
+    ///
+    /// The caller must provide as `live_roots` the roots of all syntax trees
+    /// that are still referenced from outside of the codebase. For example by
+    /// the evaluator, which might still run an old version of the code.
+    pub fn collect_garbage(
+        &mut self,
+        retained_change_sets: usize,
+        live_roots: impl IntoIterator<Item = NodeHash>,
+    ) {
+        self.changes.limit_history(retained_change_sets);
+        for branch in self.other_branches.values_mut() {
+            branch.changes.limit_history(retained_change_sets);
+        }
+
+        let roots = [self.root.hash]
+            .into_iter()
+            .chain(self.changes.roots())
+            .chain(self.other_branches.values().flat_map(|branch| {
+                [branch.root.hash].into_iter().chain(branch.changes.roots())
+            }))
+            .chain(self.tags.values().copied())
+            .chain(self.merge_bases.values().copied())
+            .chain(live_roots)
+            .collect::<BTreeSet<_>>();
+
+        self.ids.retain_removed(&roots);
+        for branch in self.other_branches.values_mut() {
+            branch.ids.retain_removed(&roots);
+        }
+
+        self.nodes.retain_reachable_from(roots);
+    }
+
+    fn revert(&mut self, change_set: &ChangeSet) {
+        self.make_change_without_recording_edit(|new_change_set| {
+            for (replaced, replacement) in change_set.replacements() {
+                new_change_set.replace(replacement, replaced);
+            }
+        });
+    }
+
+    fn make_change_without_recording_edit<R>(
+        &mut self,
+        f: impl FnOnce(&mut NewChangeSet) -> R,
     ) -> R {
         let mut new_change_set =
             self.changes.new_change_set(self.root.hash, &mut self.nodes);
@@ -61,6 +628,15 @@ impl Codebase {
 
         if let Some(new_root) = root_was_replaced {
             self.root.hash = *new_root.hash();
+            self.ids
+                .update(new_change_set.change_set(), new_change_set.nodes);
+
+            self.observers.notify(&CodebaseEvent {
+                root_before: new_change_set.change_set().root_before_change(),
+                root_after: self.root.hash,
+                change_set: Some(new_change_set.change_set()),
+                nodes: new_change_set.nodes,
+            });
         }
 
         value
@@ -73,6 +649,50 @@ impl Default for Codebase {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum VersionError {
+    #[error("Branch `{name}` already exists.")]
+    BranchAlreadyExists { name: String },
+
+    #[error("Branch `{name}` has no common ancestor with the current one.")]
+    NoMergeBase { name: String },
+
+    #[error("Tag `{name}` already exists.")]
+    TagAlreadyExists { name: String },
+
+    #[error("There is no branch named `{name}`.")]
+    UnknownBranch { name: String },
+
+    #[error("There is no tag named `{name}`.")]
+    UnknownTag { name: String },
+}
+
+fn merge_base_key(a: &str, b: &str) -> (String, String) {
+    let [a, b] = [a, b].map(ToString::to_string);
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// # A branch other than the current one
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Branch {
+    root: Root,
+    changes: Changes,
+    ids: NodeIds,
+}
+
+impl Branch {
+    fn new(root: NodeHash, nodes: &Nodes) -> Self {
+        // Make sure the root actually refers to a node.
+        let _ = nodes.get(&root);
+
+        Self {
+            root: Root { hash: root },
+            changes: Changes::default(),
+            ids: NodeIds::new(root, nodes),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct Root {
     hash: NodeHash,
@@ -86,9 +706,13 @@ impl Root {
 
 #[cfg(test)]
 mod tests {
-    use crate::language::{code::NodePath, tests::infra::identifier};
+    use std::{cell::RefCell, rc::Rc};
 
-    use super::Codebase;
+    use crate::language::{
+        code::NodePath, compiler::Compiler, tests::infra::identifier,
+    };
+
+    use super::{Codebase, VersionError};
 
     #[test]
     fn replace_root_node() {
@@ -107,4 +731,205 @@ mod tests {
 
         assert_eq!(codebase.root().path, root);
     }
+
+    #[test]
+    fn collect_garbage() {
+        // Collecting garbage removes nodes that are no longer reachable, but
+        // keeps what is required to undo retained changes.
+
+        let mut codebase = Codebase::new();
+
+        for name in ["a", "b", "c"] {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            compiler.replace(&root, name);
+        }
+
+        let num_nodes_before = codebase.nodes().len();
+        codebase.collect_garbage(1, []);
+        assert!(codebase.nodes().len() < num_nodes_before);
+
+        assert!(codebase.undo());
+        assert_eq!(codebase.root().node, &identifier("b"));
+
+        // The older change was not retained and can't be undone.
+        assert!(!codebase.undo());
+    }
+
+    #[test]
+    fn undo_and_redo_move_root_between_versions() {
+        // Undoing a change restores the previous root. Redoing it restores the
+        // root that the change created.
+
+        let mut codebase = Codebase::new();
+        let before = codebase.root().path;
+
+        let after = codebase.make_change(|change_set| {
+            let a =
+                NodePath::for_root(change_set.nodes.insert(identifier("a")));
+            change_set.replace(&change_set.root_before_change(), &a);
+
+            a
+        });
+
+        assert!(codebase.undo());
+        assert_eq!(codebase.root().path, before);
+        assert_eq!(codebase.latest_version_of(&after), &before);
+
+        assert!(codebase.redo());
+        assert_eq!(codebase.root().path, after);
+        assert_eq!(codebase.latest_version_of(&before), &after);
+
+        assert!(!codebase.redo());
+    }
+
+    #[test]
+    fn branches_have_independent_roots() {
+        // Changing the code on one branch leaves the other branches alone.
+
+        let mut codebase = Codebase::new();
+        let initial = codebase.root().path;
+
+        codebase.create_branch("experiment").unwrap();
+        codebase.switch_branch("experiment").unwrap();
+        {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            compiler.replace(&root, "a");
+        }
+        let experiment = codebase.root().path;
+
+        codebase.switch_branch(Codebase::DEFAULT_BRANCH).unwrap();
+        assert_eq!(codebase.root().path, initial);
+        assert!(!codebase.undo());
+
+        codebase.switch_branch("experiment").unwrap();
+        assert_eq!(codebase.root().path, experiment);
+        assert!(codebase.undo());
+        assert_eq!(codebase.root().path, initial);
+    }
+
+    #[test]
+    fn reject_invalid_branch_and_tag_names() {
+        let mut codebase = Codebase::new();
+
+        codebase.tag("a").unwrap();
+        assert_eq!(
+            codebase.tag("a"),
+            Err(VersionError::TagAlreadyExists {
+                name: String::from("a"),
+            }),
+        );
+        assert_eq!(
+            codebase.checkout("b"),
+            Err(VersionError::UnknownTag {
+                name: String::from("b"),
+            }),
+        );
+
+        assert_eq!(
+            codebase.create_branch(Codebase::DEFAULT_BRANCH),
+            Err(VersionError::BranchAlreadyExists {
+                name: String::from(Codebase::DEFAULT_BRANCH),
+            }),
+        );
+        assert_eq!(
+            codebase.switch_branch("b"),
+            Err(VersionError::UnknownBranch {
+                name: String::from("b"),
+            }),
+        );
+    }
+
+    #[test]
+    fn collect_garbage_keeps_tags_and_branches() {
+        // Tagged versions and other branches are kept, even if no history is
+        // retained.
+
+        let mut codebase = Codebase::new();
+
+        for name in ["a", "b"] {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            compiler.replace(&root, name);
+
+            if name == "a" {
+                codebase.tag("a").unwrap();
+                codebase.create_branch("branch").unwrap();
+            }
+        }
+
+        codebase.collect_garbage(0, []);
+
+        codebase.checkout("a").unwrap();
+        assert_eq!(codebase.root().node, &identifier("a"));
+
+        codebase.switch_branch("branch").unwrap();
+        assert_eq!(codebase.root().node, &identifier("a"));
+    }
+
+    #[test]
+    fn clear_keeps_tags_and_branches() {
+        // Clearing only affects the code on the current branch.
+
+        let mut codebase = Codebase::new();
+        {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            compiler.replace(&root, "a");
+        }
+        codebase.tag("a").unwrap();
+        codebase.create_branch("branch").unwrap();
+
+        codebase.clear();
+        assert_eq!(codebase.root().path, Codebase::new().root().path);
+        assert!(!codebase.undo());
+
+        codebase.checkout("a").unwrap();
+        assert_eq!(codebase.root().node, &identifier("a"));
+
+        codebase.switch_branch("branch").unwrap();
+        assert_eq!(codebase.root().node, &identifier("a"));
+    }
+
+    #[test]
+    fn notify_observers_of_changes() {
+        // Observers are notified of every change to the root, whether it's
+        // made by an edit, by undoing one, or by switching branches.
+
+        let mut codebase = Codebase::new();
+        let root_before_edit = codebase.root().path;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let id = codebase.subscribe({
+            let events = events.clone();
+            move |event| {
+                events.borrow_mut().push((
+                    event.root_before,
+                    event.root_after,
+                    event.change_set.is_some(),
+                ));
+            }
+        });
+
+        let root_after_edit =
+            Compiler::new(&mut codebase).replace(&root_before_edit, "a");
+        codebase.create_branch("branch").unwrap();
+        codebase.undo();
+        codebase.switch_branch("branch").unwrap();
+
+        assert!(codebase.unsubscribe(id));
+        codebase.switch_branch("main").unwrap();
+
+        let [before, after] =
+            [&root_before_edit, &root_after_edit].map(|path| *path.hash());
+        assert_eq!(
+            *events.borrow(),
+            [
+                (before, after, true),
+                (after, before, true),
+                (before, after, false),
+            ],
+        );
+    }
 }
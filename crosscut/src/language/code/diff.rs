@@ -0,0 +1,509 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use super::{LocatedNode, NodeHash, NodePath, Nodes, SyntaxNode};
+
+/// # A structural diff between two versions of the syntax tree
+///
+/// The diff mirrors the structure of both versions, as a tree of [`DiffNode`]s.
+/// Subtrees that are identical in both versions are detected by comparing
+/// their hashes, and not looked into any further.
+///
+/// Use [`Diff::changes`], to get a flat list of what changed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diff {
+    pub root: DiffNode,
+}
+
+impl Diff {
+    /// # Compute the diff between two versions of the syntax tree
+    ///
+    /// Both roots must refer to nodes in `nodes`.
+    pub fn between(old: NodeHash, new: NodeHash, nodes: &Nodes) -> Self {
+        let old = NodePath::for_root(old);
+        let new = NodePath::for_root(new);
+
+        let mut root = diff_nodes(old, new, nodes);
+
+        // A subtree that was removed in one place and inserted in another, was
+        // actually moved.
+        let mut removed = BTreeMap::new();
+        let mut inserted = BTreeMap::new();
+        collect_removed_and_inserted(&root, &mut removed, &mut inserted);
+
+        let mut moves = Vec::new();
+        for (hash, mut old_paths) in removed {
+            let Some(new_paths) = inserted.get_mut(&hash) else {
+                continue;
+            };
+
+            while let (Some(old), Some(new)) =
+                (old_paths.pop_front(), new_paths.pop_front())
+            {
+                moves.push((old, new));
+            }
+        }
+
+        for (old, new) in moves {
+            mark_as_moved(&mut root, &old, &new);
+        }
+
+        Self { root }
+    }
+
+    /// # Indicate whether both versions are identical
+    pub fn is_empty(&self) -> bool {
+        matches!(self.root, DiffNode::Unchanged { .. })
+    }
+
+    /// # All changes, in the order in which they appear in the syntax tree
+    pub fn changes(&self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        collect_changes(&self.root, &mut changes);
+        changes
+    }
+}
+
+/// # A node in a [`Diff`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DiffNode {
+    /// # The subtree is identical in both versions
+    Unchanged { old: NodePath, new: NodePath },
+
+    /// # The node is the same kind of node, but some of its children changed
+    Changed {
+        old: NodePath,
+        new: NodePath,
+        children: Vec<DiffNode>,
+    },
+
+    /// # The subtree was replaced with a different one
+    Replaced { old: NodePath, new: NodePath },
+
+    /// # The subtree only exists in the new version
+    Inserted { new: NodePath },
+
+    /// # The subtree only exists in the old version
+    Removed { old: NodePath },
+
+    /// # The subtree was moved away from here, to `new`
+    MovedAway { old: NodePath, new: NodePath },
+
+    /// # The subtree was moved here, from `old`
+    MovedHere { old: NodePath, new: NodePath },
+}
+
+/// # A change between two versions of the syntax tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    Inserted { new: NodePath },
+    Removed { old: NodePath },
+    Replaced { old: NodePath, new: NodePath },
+    Moved { old: NodePath, new: NodePath },
+}
+
+fn diff_nodes(old: NodePath, new: NodePath, nodes: &Nodes) -> DiffNode {
+    if old.hash() == new.hash() {
+        return DiffNode::Unchanged { old, new };
+    }
+
+    let old_node = nodes.get(old.hash());
+    let new_node = nodes.get(new.hash());
+
+    if !is_same_kind(old_node, new_node) {
+        return DiffNode::Replaced { old, new };
+    }
+
+    let old_children = LocatedNode {
+        node: old_node,
+        path: old.clone(),
+    }
+    .children(nodes)
+    .map(|child| child.path)
+    .collect::<Vec<_>>();
+    let new_children = LocatedNode {
+        node: new_node,
+        path: new.clone(),
+    }
+    .children(nodes)
+    .map(|child| child.path)
+    .collect::<Vec<_>>();
+
+    let children = if let SyntaxNode::Body { .. } = old_node {
+        diff_children(old_children, new_children, nodes)
+    } else {
+        // All other nodes that have children, have a fixed number of them. The
+        // child at each position has the same role in both versions, so
+        // that's what's compared.
+        old_children
+            .into_iter()
+            .zip(new_children)
+            .map(|(old, new)| diff_nodes(old, new, nodes))
+            .collect()
+    };
+
+    DiffNode::Changed { old, new, children }
+}
+
+/// # Indicate whether the nodes only differ in their children
+pub(super) fn is_same_kind(a: &SyntaxNode, b: &SyntaxNode) -> bool {
+    let has_children = !a.children().hashes.is_empty();
+    std::mem::discriminant(a) == std::mem::discriminant(b) && has_children
+}
+
+/// # Match up the children of two versions of a body
+///
+/// Children that are identical in both versions serve as anchors. Between two
+/// anchors, the remaining children are paired up in order, while any excess
+/// children were inserted or removed.
+fn diff_children(
+    old: Vec<NodePath>,
+    new: Vec<NodePath>,
+    nodes: &Nodes,
+) -> Vec<DiffNode> {
+    let anchors = longest_common_subsequence(
+        &old.iter().map(|path| *path.hash()).collect::<Vec<_>>(),
+        &new.iter().map(|path| *path.hash()).collect::<Vec<_>>(),
+    );
+
+    let mut old = old.into_iter().enumerate().peekable();
+    let mut new = new.into_iter().enumerate().peekable();
+
+    let mut children = Vec::new();
+
+    for (old_anchor, new_anchor) in anchors
+        .into_iter()
+        .map(Some)
+        .chain([None])
+        .map(|anchor| anchor.unzip())
+    {
+        let mut old_between = Vec::new();
+        while let Some((_, path)) =
+            old.next_if(|(i, _)| old_anchor.is_none_or(|anchor| *i < anchor))
+        {
+            old_between.push(path);
+        }
+
+        let mut new_between = Vec::new();
+        while let Some((_, path)) =
+            new.next_if(|(i, _)| new_anchor.is_none_or(|anchor| *i < anchor))
+        {
+            new_between.push(path);
+        }
+
+        let mut old_between = old_between.into_iter();
+        let mut new_between = new_between.into_iter();
+
+        loop {
+            match (old_between.next(), new_between.next()) {
+                (Some(old), Some(new)) => {
+                    children.push(diff_nodes(old, new, nodes));
+                }
+                (Some(old), None) => {
+                    children.push(DiffNode::Removed { old });
+                }
+                (None, Some(new)) => {
+                    children.push(DiffNode::Inserted { new });
+                }
+                (None, None) => {
+                    break;
+                }
+            }
+        }
+
+        if let (Some((_, old)), Some((_, new))) = (old.next(), new.next()) {
+            children.push(DiffNode::Unchanged { old, new });
+        }
+    }
+
+    children
+}
+
+/// # Find the indices of the longest sequence of identical children
+pub(super) fn longest_common_subsequence(
+    old: &[NodeHash],
+    new: &[NodeHash],
+) -> Vec<(usize, usize)> {
+    // `lengths[i][j]` is the length of the longest common subsequence of
+    // `old[i..]` and `new[j..]`.
+    let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    pairs
+}
+
+fn collect_removed_and_inserted(
+    node: &DiffNode,
+    removed: &mut BTreeMap<NodeHash, VecDeque<NodePath>>,
+    inserted: &mut BTreeMap<NodeHash, VecDeque<NodePath>>,
+) {
+    match node {
+        DiffNode::Removed { old } => {
+            removed
+                .entry(*old.hash())
+                .or_default()
+                .push_back(old.clone());
+        }
+        DiffNode::Inserted { new } => {
+            inserted
+                .entry(*new.hash())
+                .or_default()
+                .push_back(new.clone());
+        }
+        DiffNode::Changed { children, .. } => {
+            for child in children {
+                collect_removed_and_inserted(child, removed, inserted);
+            }
+        }
+        DiffNode::Unchanged { .. }
+        | DiffNode::Replaced { .. }
+        | DiffNode::MovedAway { .. }
+        | DiffNode::MovedHere { .. } => {}
+    }
+}
+
+fn mark_as_moved(node: &mut DiffNode, from: &NodePath, to: &NodePath) {
+    match node {
+        DiffNode::Removed { old } if old == from => {
+            *node = DiffNode::MovedAway {
+                old: from.clone(),
+                new: to.clone(),
+            };
+        }
+        DiffNode::Inserted { new } if new == to => {
+            *node = DiffNode::MovedHere {
+                old: from.clone(),
+                new: to.clone(),
+            };
+        }
+        DiffNode::Changed { children, .. } => {
+            for child in children {
+                mark_as_moved(child, from, to);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_changes(node: &DiffNode, changes: &mut Vec<Change>) {
+    match node {
+        DiffNode::Unchanged { .. } | DiffNode::MovedAway { .. } => {
+            // A move is recorded where the subtree ends up.
+        }
+        DiffNode::Changed { children, .. } => {
+            for child in children {
+                collect_changes(child, changes);
+            }
+        }
+        DiffNode::Replaced { old, new } => {
+            changes.push(Change::Replaced {
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+        DiffNode::Inserted { new } => {
+            changes.push(Change::Inserted { new: new.clone() });
+        }
+        DiffNode::Removed { old } => {
+            changes.push(Change::Removed { old: old.clone() });
+        }
+        DiffNode::MovedHere { old, new } => {
+            changes.push(Change::Moved {
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{
+        code::{NodeHash, Nodes},
+        text::import_into,
+    };
+
+    use super::{Change, Diff, DiffNode};
+
+    #[test]
+    fn identical_versions() {
+        let (old, new, nodes) = versions("apply", "apply");
+
+        let diff = Diff::between(old, new, &nodes);
+        assert!(diff.is_empty());
+        assert_eq!(diff.changes(), []);
+    }
+
+    #[test]
+    fn insert_remove_and_replace() {
+        let (old, new, nodes) = versions(
+            "
+            a
+            b
+            c
+            ",
+            "
+            a
+            x
+            c
+            d
+            ",
+        );
+
+        let changes = Diff::between(old, new, &nodes).changes();
+
+        let [
+            Change::Replaced { old: b, new: x },
+            Change::Inserted { new: d },
+        ] = &changes[..]
+        else {
+            panic!("Unexpected changes: {changes:#?}");
+        };
+        assert_eq!(nodes.get(b.hash()).to_token(), "b");
+        assert_eq!(nodes.get(x.hash()).to_token(), "x");
+        assert_eq!(nodes.get(d.hash()).to_token(), "d");
+
+        let changes = Diff::between(new, old, &nodes).changes();
+        assert!(matches!(
+            &changes[..],
+            [Change::Replaced { .. }, Change::Removed { .. }],
+        ));
+    }
+
+    #[test]
+    fn change_within_subtree() {
+        // If only part of a subtree changed, the diff points right at that
+        // part, and skips the rest.
+
+        let (old, new, nodes) = versions(
+            "
+            apply
+                f
+                tuple
+                    1
+                    2
+            ",
+            "
+            apply
+                f
+                tuple
+                    1
+                    3
+            ",
+        );
+
+        let diff = Diff::between(old, new, &nodes);
+
+        let changes = diff.changes();
+        let [Change::Replaced { old, new }] = &changes[..] else {
+            panic!("Unexpected changes: {changes:#?}");
+        };
+        assert_eq!(nodes.get(old.hash()).to_token(), "2");
+        assert_eq!(nodes.get(new.hash()).to_token(), "3");
+
+        let DiffNode::Changed { children, .. } = &diff.root else {
+            panic!("Expected root to be changed.");
+        };
+        let [
+            DiffNode::Changed { children, .. },
+            DiffNode::Unchanged { .. },
+        ] = &children[..]
+        else {
+            panic!("Expected `apply` to be changed.");
+        };
+        assert!(matches!(
+            &children[..],
+            [DiffNode::Unchanged { .. }, DiffNode::Changed { .. }],
+        ));
+    }
+
+    #[test]
+    fn children_of_fixed_arity_nodes_are_compared_by_position() {
+        // Only the children of a body can be inserted or removed. Other nodes
+        // always have the same number of children.
+
+        let (old, new, nodes) = versions(
+            "
+            apply
+                a
+                b
+            ",
+            "
+            apply
+                b
+                c
+            ",
+        );
+
+        let changes = Diff::between(old, new, &nodes).changes();
+
+        let [
+            Change::Replaced { old: a, new: b },
+            Change::Replaced { old: b_old, new: c },
+        ] = &changes[..]
+        else {
+            panic!("Unexpected changes: {changes:#?}");
+        };
+        assert_eq!(nodes.get(a.hash()).to_token(), "a");
+        assert_eq!(nodes.get(b.hash()).to_token(), "b");
+        assert_eq!(nodes.get(b_old.hash()).to_token(), "b");
+        assert_eq!(nodes.get(c.hash()).to_token(), "c");
+    }
+
+    #[test]
+    fn move_subtree() {
+        let (old, new, nodes) = versions(
+            "
+            apply
+                f
+                1
+            b
+            ",
+            "
+            b
+            apply
+                f
+                1
+            ",
+        );
+
+        let changes = Diff::between(old, new, &nodes).changes();
+
+        let [Change::Moved { old, new }] = &changes[..] else {
+            panic!("Unexpected changes: {changes:#?}");
+        };
+        assert_eq!(old.hash(), new.hash());
+    }
+
+    fn versions(old: &str, new: &str) -> (NodeHash, NodeHash, Nodes) {
+        let mut nodes = Nodes::default();
+
+        let old = import_into(old, &mut nodes).unwrap();
+        let new = import_into(new, &mut nodes).unwrap();
+
+        (old, new, nodes)
+    }
+}
@@ -0,0 +1,82 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// # A floating-point number that can be part of syntax nodes and values
+///
+/// ## Implementation Note
+///
+/// Syntax nodes and values need to be comparable and hashable, which `f64` by
+/// itself isn't. This wrapper compares numbers by their total order, and
+/// hashes them by their bits.
+///
+/// Numbers are stored as text, in the shortest form that still parses back
+/// into the exact same number. Storing them as JSON numbers instead, would not
+/// guarantee that.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Float {
+    value: f64,
+}
+
+impl Float {
+    pub fn new(value: f64) -> Self {
+        Self { value }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl Eq for Float {}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.total_cmp(&other.value)
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl udigest::Digestable for Float {
+    fn unambiguously_encode<B: udigest::Buffer>(
+        &self,
+        encoder: udigest::encoding::EncodeValue<B>,
+    ) {
+        self.value.to_bits().unambiguously_encode(encoder);
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Unlike `Display`, the `Debug` implementation of `f64` always marks
+        // the number as floating-point, and writes it in the shortest form
+        // that parses back into the same number.
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl From<Float> for String {
+    fn from(float: Float) -> Self {
+        float.to_string()
+    }
+}
+
+impl TryFrom<String> for Float {
+    type Error = <f64 as FromStr>::Err;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map(Self::new)
+    }
+}
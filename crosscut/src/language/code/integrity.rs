@@ -0,0 +1,447 @@
+use std::{collections::BTreeSet, fmt};
+
+use super::{ChildIndex, NodeHash, SyntaxNode};
+
+/// # Check syntax trees for structural problems
+///
+/// Walks the syntax tree from each of the provided roots, each of which is
+/// expected to be the provided kind of node. Looks up nodes using `get`, which
+/// returns the node that is stored under the provided hash, if any.
+///
+/// Reports every problem that it finds, instead of stopping at the first one.
+/// An empty list means that the syntax trees are well-formed.
+///
+/// Code that comes from outside of the editor, like stored code, must be
+/// checked before it's handed to a [`Codebase`]. Most code that works with
+/// syntax trees expects them to be well-formed, and panics otherwise.
+///
+/// [`Codebase`]: super::Codebase
+pub fn check_integrity<'r>(
+    roots: impl IntoIterator<Item = (NodeHash, ExpectedNode)>,
+    get: impl Fn(&NodeHash) -> Option<&'r SyntaxNode>,
+) -> Vec<Diagnostic> {
+    let mut check = Check {
+        get,
+        checked: BTreeSet::new(),
+        verified: BTreeSet::new(),
+        diagnostics: Vec::new(),
+    };
+
+    for (root, expected) in roots {
+        check.check_node(root, expected, None);
+    }
+
+    check.diagnostics
+}
+
+/// # The kind of node that is expected in a specific position
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ExpectedNode {
+    /// # A [`SyntaxNode::Add`]
+    Add,
+
+    /// # A [`SyntaxNode::Arm`]
+    Arm,
+
+    /// # A [`SyntaxNode::Body`] whose children are arms
+    Arms,
+
+    /// # A [`SyntaxNode::Binding`]
+    Binding,
+
+    /// # A [`SyntaxNode::Body`] whose children are bindings
+    Bindings,
+
+    /// # A [`SyntaxNode::Body`] whose children are expressions
+    Body,
+
+    /// # Any node that can be evaluated, except a body
+    Expression,
+
+    /// # Any pattern, like a binding or a wildcard
+    Pattern,
+
+    /// # A [`SyntaxNode::Body`] whose children are patterns
+    Patterns,
+}
+
+impl ExpectedNode {
+    /// # The kind of node expected as child of a body, with the provided parent
+    ///
+    /// Most bodies contain expressions, but some contain other kinds of nodes.
+    /// Those bodies are identified by their parent.
+    pub fn in_body_of(parent: Option<&SyntaxNode>) -> Self {
+        match parent {
+            Some(SyntaxNode::Match { .. }) => Self::Arm,
+            Some(SyntaxNode::RecordPattern { .. }) => Self::Binding,
+            Some(SyntaxNode::TuplePattern { .. }) => Self::Pattern,
+            _ => Self::Expression,
+        }
+    }
+}
+
+impl fmt::Display for ExpectedNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Add => write!(f, "node for adding children"),
+            Self::Arm => write!(f, "arm"),
+            Self::Arms => write!(f, "body of arms"),
+            Self::Binding => write!(f, "binding"),
+            Self::Bindings => write!(f, "body of bindings"),
+            Self::Body => write!(f, "body"),
+            Self::Expression => write!(f, "expression"),
+            Self::Pattern => write!(f, "pattern"),
+            Self::Patterns => write!(f, "body of patterns"),
+        }
+    }
+}
+
+/// # A structural problem in a syntax tree
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum Diagnostic {
+    #[error("Node `{hash}` is missing.")]
+    MissingNode { hash: NodeHash },
+
+    #[error("Node `{expected}` actually has hash `{actual}`.")]
+    HashMismatch {
+        expected: NodeHash,
+        actual: NodeHash,
+    },
+
+    #[error("Expected {expected} as root `{root}`, found `{node:?}`.")]
+    UnexpectedRoot {
+        root: NodeHash,
+        expected: ExpectedNode,
+        node: SyntaxNode,
+    },
+
+    #[error(
+        "Expected {expected} as child {index} of `{parent}`, found `{node:?}`."
+    )]
+    UnexpectedChild {
+        parent: NodeHash,
+        index: ChildIndex,
+        expected: ExpectedNode,
+        node: SyntaxNode,
+    },
+}
+
+struct Check<F> {
+    get: F,
+
+    /// # The nodes that have been checked, with the kind expected of them
+    ///
+    /// The same subtree can appear in many places. This makes sure that it's
+    /// only checked once, unless it's expected to be a different kind of node
+    /// somewhere.
+    checked: BTreeSet<(NodeHash, ExpectedNode)>,
+
+    /// # The nodes whose hashes have been verified, or found missing
+    verified: BTreeSet<NodeHash>,
+
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'r, F> Check<F>
+where
+    F: Fn(&NodeHash) -> Option<&'r SyntaxNode>,
+{
+    fn check_node(
+        &mut self,
+        hash: NodeHash,
+        expected: ExpectedNode,
+        parent: Option<(NodeHash, ChildIndex)>,
+    ) {
+        if !self.checked.insert((hash, expected)) {
+            return;
+        }
+
+        let Some(node) = (self.get)(&hash) else {
+            if self.verified.insert(hash) {
+                self.diagnostics.push(Diagnostic::MissingNode { hash });
+            }
+            return;
+        };
+
+        if self.verified.insert(hash)
+            && let Err(actual) = hash.verify(node)
+        {
+            self.diagnostics.push(Diagnostic::HashMismatch {
+                expected: hash,
+                actual,
+            });
+        }
+
+        let is_pattern = matches!(
+            node,
+            SyntaxNode::Binding { .. }
+                | SyntaxNode::BooleanPattern { .. }
+                | SyntaxNode::NumberPattern { .. }
+                | SyntaxNode::RecordPattern { .. }
+                | SyntaxNode::TuplePattern { .. }
+                | SyntaxNode::Wildcard
+        );
+
+        let is_expected = match expected {
+            ExpectedNode::Add => matches!(node, SyntaxNode::Add),
+            ExpectedNode::Arm => matches!(node, SyntaxNode::Arm { .. }),
+            ExpectedNode::Binding => matches!(node, SyntaxNode::Binding { .. }),
+            ExpectedNode::Arms
+            | ExpectedNode::Bindings
+            | ExpectedNode::Body
+            | ExpectedNode::Patterns => {
+                matches!(node, SyntaxNode::Body { .. })
+            }
+            ExpectedNode::Expression => {
+                !is_pattern
+                    && !matches!(
+                        node,
+                        SyntaxNode::Add
+                            | SyntaxNode::Arm { .. }
+                            | SyntaxNode::Body { .. }
+                    )
+            }
+            ExpectedNode::Pattern => is_pattern,
+        };
+
+        if !is_expected {
+            let node = node.clone();
+
+            self.diagnostics.push(match parent {
+                Some((parent, index)) => Diagnostic::UnexpectedChild {
+                    parent,
+                    index,
+                    expected,
+                    node,
+                },
+                None => Diagnostic::UnexpectedRoot {
+                    root: hash,
+                    expected,
+                    node,
+                },
+            });
+        }
+
+        // Whether the node itself was expected or not, its children are
+        // expected to match its kind.
+        let children = match node {
+            SyntaxNode::Add
+            | SyntaxNode::Binding { .. }
+            | SyntaxNode::Boolean { .. }
+            | SyntaxNode::BooleanPattern { .. }
+            | SyntaxNode::Empty
+            | SyntaxNode::Identifier { .. }
+            | SyntaxNode::Float { .. }
+            | SyntaxNode::Integer64 { .. }
+            | SyntaxNode::Number { .. }
+            | SyntaxNode::NumberPattern { .. }
+            | SyntaxNode::Recursion
+            | SyntaxNode::String { .. }
+            | SyntaxNode::Unsigned { .. }
+            | SyntaxNode::Wildcard => Vec::new(),
+
+            SyntaxNode::Apply {
+                expression,
+                argument,
+            } => vec![
+                (*expression, ExpectedNode::Expression),
+                (*argument, ExpectedNode::Expression),
+            ],
+
+            SyntaxNode::Arm { pattern, body } => vec![
+                (*pattern, ExpectedNode::Pattern),
+                (*body, ExpectedNode::Body),
+            ],
+
+            SyntaxNode::Body { children, add } => {
+                let expected = match expected {
+                    ExpectedNode::Arms => ExpectedNode::Arm,
+                    ExpectedNode::Bindings => ExpectedNode::Binding,
+                    ExpectedNode::Patterns => ExpectedNode::Pattern,
+                    _ => ExpectedNode::Expression,
+                };
+
+                children
+                    .iter()
+                    .map(|child| (*child, expected))
+                    .chain([(*add, ExpectedNode::Add)])
+                    .collect()
+            }
+
+            SyntaxNode::Conflict { ours, theirs } => [ours, theirs]
+                .into_iter()
+                .map(|side| {
+                    // The conflicting versions of a run of children within a
+                    // body are bodies themselves.
+                    let expected = if let Some(SyntaxNode::Body { .. }) =
+                        (self.get)(side)
+                    {
+                        ExpectedNode::Body
+                    } else {
+                        ExpectedNode::Expression
+                    };
+
+                    (*side, expected)
+                })
+                .collect(),
+
+            SyntaxNode::Definition { name, value } => vec![
+                (*name, ExpectedNode::Binding),
+                (*value, ExpectedNode::Expression),
+            ],
+
+            SyntaxNode::FieldAccess { record, field } => vec![
+                (*record, ExpectedNode::Expression),
+                (*field, ExpectedNode::Binding),
+            ],
+
+            SyntaxNode::FieldUpdate { record, fields } => vec![
+                (*record, ExpectedNode::Expression),
+                (*fields, ExpectedNode::Body),
+            ],
+
+            SyntaxNode::Function { parameter, body } => vec![
+                (*parameter, ExpectedNode::Binding),
+                (*body, ExpectedNode::Body),
+            ],
+
+            SyntaxNode::Let { binding, value } => vec![
+                (*binding, ExpectedNode::Binding),
+                (*value, ExpectedNode::Expression),
+            ],
+
+            SyntaxNode::Match { scrutinee, arms } => vec![
+                (*scrutinee, ExpectedNode::Expression),
+                (*arms, ExpectedNode::Arms),
+            ],
+
+            SyntaxNode::Record { fields } => {
+                vec![(*fields, ExpectedNode::Body)]
+            }
+
+            SyntaxNode::RecordPattern { fields } => {
+                vec![(*fields, ExpectedNode::Bindings)]
+            }
+
+            SyntaxNode::List { values } | SyntaxNode::Tuple { values } => {
+                vec![(*values, ExpectedNode::Body)]
+            }
+
+            SyntaxNode::TuplePattern { values } => {
+                vec![(*values, ExpectedNode::Patterns)]
+            }
+        };
+
+        for (index, (child, expected)) in children.into_iter().enumerate() {
+            self.check_node(
+                child,
+                expected,
+                Some((hash, ChildIndex { index })),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::language::code::{ChildIndex, Nodes, SyntaxNode};
+
+    use super::{Diagnostic, ExpectedNode, check_integrity};
+
+    #[test]
+    fn well_formed_code_has_no_diagnostics() {
+        let mut nodes = Nodes::default();
+        let root = crate::language::text::import_into(
+            "
+            fn
+                x
+                tuple
+                    x
+                    1
+            ",
+            &mut nodes,
+        )
+        .unwrap();
+
+        let diagnostics =
+            check_integrity([(root, ExpectedNode::Body)], |hash| {
+                nodes.contains(hash).then(|| nodes.get(hash))
+            });
+
+        assert_eq!(diagnostics, []);
+    }
+
+    #[test]
+    fn report_all_problems() {
+        // Nodes are checked the way they'd be loaded from outside: by the hash
+        // that they're stored under, which might not be their actual hash.
+
+        let mut nodes = Nodes::default();
+        let missing = nodes.insert(SyntaxNode::Recursion);
+        let not_a_binding = nodes.insert(SyntaxNode::Empty);
+        let add = nodes.insert(SyntaxNode::Add);
+        let body = nodes.insert(SyntaxNode::Body {
+            children: vec![missing],
+            add,
+        });
+        let function = nodes.insert(SyntaxNode::Function {
+            parameter: not_a_binding,
+            body,
+        });
+
+        let stored = BTreeMap::from([
+            (not_a_binding, SyntaxNode::Empty),
+            (add, SyntaxNode::Add),
+            (
+                body,
+                SyntaxNode::Body {
+                    children: vec![missing],
+                    add,
+                },
+            ),
+            // This node was modified after it was stored.
+            (
+                function,
+                SyntaxNode::Function {
+                    parameter: not_a_binding,
+                    body: add,
+                },
+            ),
+        ]);
+
+        let diagnostics =
+            check_integrity([(function, ExpectedNode::Expression)], |hash| {
+                stored.get(hash)
+            });
+
+        let [
+            Diagnostic::HashMismatch { expected, .. },
+            Diagnostic::UnexpectedChild {
+                parent: parameter_parent,
+                index: ChildIndex { index: 0 },
+                expected: ExpectedNode::Binding,
+                node: SyntaxNode::Empty,
+            },
+            Diagnostic::UnexpectedChild {
+                parent: body_parent,
+                index: ChildIndex { index: 1 },
+                expected: ExpectedNode::Body,
+                node: SyntaxNode::Add,
+            },
+        ] = &diagnostics[..]
+        else {
+            panic!("Unexpected diagnostics: {diagnostics:#?}");
+        };
+        assert_eq!(expected, &function);
+        assert_eq!(parameter_parent, &function);
+        assert_eq!(body_parent, &function);
+
+        let diagnostics =
+            check_integrity([(body, ExpectedNode::Body)], |hash| {
+                stored.get(hash)
+            });
+        assert_eq!(diagnostics, [Diagnostic::MissingNode { hash: missing }]);
+    }
+}
@@ -0,0 +1,452 @@
+use super::{
+    Body, NodeHash, Nodes, SyntaxNode, TypedNode,
+    diff::{is_same_kind, longest_common_subsequence},
+};
+
+/// # Merge two versions of the syntax tree that share a common ancestor
+///
+/// Changes that only one of the versions made are carried over into the
+/// result. If both versions changed the same node in different ways, the result
+/// contains a [`SyntaxNode::Conflict`] in its place.
+///
+/// All three roots must refer to bodies in `nodes`. The merged nodes are
+/// inserted there too. The merged root is a body as well.
+///
+/// ## Implementation Note
+///
+/// The children of bodies are merged, like the lines of a text file in a
+/// three-way merge: Children that neither version changed serve as anchors.
+/// Between two anchors, whatever only one of the versions changed is carried
+/// over. If both changed the same run of children, those are merged child by
+/// child, if both runs have the same length. Otherwise, the whole run becomes a
+/// conflict.
+///
+/// Conflicts can only take the place of expressions. If both versions changed
+/// a binding, the conflict takes the place of its parent instead. If there's no
+/// such parent below the root, the conflict becomes the only child of the
+/// root.
+pub fn merge(
+    base: NodeHash,
+    ours: NodeHash,
+    theirs: NodeHash,
+    nodes: &mut Nodes,
+) -> NodeHash {
+    merge_nodes(base, ours, theirs, nodes).unwrap_or_else(|NotAnExpression| {
+        let add = Body::from_hash(&ours, nodes).add;
+        let conflict = nodes.insert(SyntaxNode::Conflict { ours, theirs });
+
+        nodes.insert(SyntaxNode::Body {
+            children: vec![conflict],
+            add,
+        })
+    })
+}
+
+/// # One of the two versions in a [`SyntaxNode::Conflict`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictSide {
+    /// # The version on the branch that was merged into
+    Ours,
+
+    /// # The version on the branch that was merged
+    Theirs,
+}
+
+fn merge_nodes(
+    base: NodeHash,
+    ours: NodeHash,
+    theirs: NodeHash,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, NotAnExpression> {
+    if ours == theirs || base == theirs {
+        return Ok(ours);
+    }
+    if base == ours {
+        return Ok(theirs);
+    }
+
+    let [base_node, our_node, their_node] =
+        [base, ours, theirs].map(|hash| nodes.get(&hash).clone());
+
+    if is_same_kind(&base_node, &our_node)
+        && is_same_kind(&our_node, &their_node)
+    {
+        if let (
+            SyntaxNode::Body {
+                children: base_children,
+                ..
+            },
+            SyntaxNode::Body {
+                children: our_children,
+                add,
+            },
+            SyntaxNode::Body {
+                children: their_children,
+                ..
+            },
+        ) = (&base_node, &our_node, &their_node)
+        {
+            let children = merge_children(
+                base_children,
+                our_children,
+                their_children,
+                *add,
+                nodes,
+            )?;
+
+            return Ok(nodes.insert(SyntaxNode::Body {
+                children,
+                add: *add,
+            }));
+        }
+
+        // All other nodes that have children, have a fixed number of them.
+        let merged_children = base_node
+            .children()
+            .hashes
+            .into_iter()
+            .zip(our_node.children().hashes)
+            .zip(their_node.children().hashes)
+            .map(|((base, ours), theirs)| {
+                merge_nodes(*base, *ours, *theirs, nodes)
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        if let Ok(merged_children) = merged_children {
+            let mut merged = our_node;
+
+            for (child, merged_child) in merged
+                .children_mut()
+                .hashes
+                .into_iter()
+                .zip(merged_children)
+            {
+                *child = merged_child;
+            }
+
+            return Ok(nodes.insert(merged));
+        }
+    }
+
+    if let TypedNode::Expression { .. } =
+        TypedNode::from_syntax_node(our_node, nodes)
+    {
+        Ok(nodes.insert(SyntaxNode::Conflict { ours, theirs }))
+    } else {
+        Err(NotAnExpression)
+    }
+}
+
+fn merge_children(
+    base: &[NodeHash],
+    ours: &[NodeHash],
+    theirs: &[NodeHash],
+    add: NodeHash,
+    nodes: &mut Nodes,
+) -> Result<Vec<NodeHash>, NotAnExpression> {
+    let unchanged_in_ours = longest_common_subsequence(base, ours);
+    let unchanged_in_theirs = longest_common_subsequence(base, theirs);
+
+    let anchors = unchanged_in_ours.into_iter().filter_map(|(b, o)| {
+        unchanged_in_theirs
+            .iter()
+            .find(|(other_b, _)| *other_b == b)
+            .map(|(_, t)| (b, o, *t))
+    });
+
+    let mut merged = Vec::new();
+    let (mut b, mut o, mut t) = (0, 0, 0);
+
+    for anchor in anchors.map(Some).chain([None]) {
+        let (end_b, end_o, end_t) =
+            anchor.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        merge_run(
+            &base[b..end_b],
+            &ours[o..end_o],
+            &theirs[t..end_t],
+            add,
+            nodes,
+            &mut merged,
+        )?;
+
+        if anchor.is_some() {
+            merged.push(base[end_b]);
+            (b, o, t) = (end_b + 1, end_o + 1, end_t + 1);
+        }
+    }
+
+    Ok(merged)
+}
+
+fn merge_run(
+    base: &[NodeHash],
+    ours: &[NodeHash],
+    theirs: &[NodeHash],
+    add: NodeHash,
+    nodes: &mut Nodes,
+    merged: &mut Vec<NodeHash>,
+) -> Result<(), NotAnExpression> {
+    if ours == theirs || base == theirs {
+        merged.extend(ours);
+        return Ok(());
+    }
+    if base == ours {
+        merged.extend(theirs);
+        return Ok(());
+    }
+
+    if base.len() == ours.len() && ours.len() == theirs.len() {
+        let run = base
+            .iter()
+            .zip(ours)
+            .zip(theirs)
+            .map(|((base, ours), theirs)| {
+                merge_nodes(*base, *ours, *theirs, nodes)
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        if let Ok(run) = run {
+            merged.extend(run);
+            return Ok(());
+        }
+    }
+
+    // Not all bodies contain expressions. The arms of a match, for example,
+    // can't be put into a conflict.
+    let contains_only_expressions = ours.iter().chain(theirs).all(|hash| {
+        matches!(
+            TypedNode::from_hash(hash, nodes),
+            TypedNode::Expression { .. }
+        )
+    });
+    if !contains_only_expressions {
+        return Err(NotAnExpression);
+    }
+
+    let [ours, theirs] = [ours, theirs].map(|children| {
+        nodes.insert(SyntaxNode::Body {
+            children: children.to_vec(),
+            add,
+        })
+    });
+    merged.push(nodes.insert(SyntaxNode::Conflict { ours, theirs }));
+
+    Ok(())
+}
+
+/// # A conflict that can't be placed where it occurred
+struct NotAnExpression;
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{
+        code::{Body, NodeHash, Nodes, SyntaxNode},
+        text::import_into,
+    };
+
+    use super::merge;
+
+    #[test]
+    fn combine_changes_to_different_nodes() {
+        let (base, ours, theirs, mut nodes) = versions(
+            "
+            a
+            b
+            c
+            ",
+            "
+            x
+            b
+            c
+            ",
+            "
+            a
+            b
+            c
+            d
+            ",
+        );
+
+        let merged = merge(base, ours, theirs, &mut nodes);
+
+        let expected = import_into(
+            "
+            x
+            b
+            c
+            d
+            ",
+            &mut nodes,
+        )
+        .unwrap();
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn combine_changes_within_the_same_node() {
+        let (base, ours, theirs, mut nodes) = versions(
+            "
+            tuple
+                1
+                2
+            ",
+            "
+            tuple
+                3
+                2
+            ",
+            "
+            tuple
+                1
+                4
+            ",
+        );
+
+        let merged = merge(base, ours, theirs, &mut nodes);
+
+        let expected = import_into(
+            "
+            tuple
+                3
+                4
+            ",
+            &mut nodes,
+        )
+        .unwrap();
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn conflicting_changes_result_in_conflict() {
+        let (base, ours, theirs, mut nodes) = versions(
+            "
+            a
+            b
+            ",
+            "
+            x
+            b
+            ",
+            "
+            y
+            b
+            c
+            ",
+        );
+
+        let merged = merge(base, ours, theirs, &mut nodes);
+
+        let SyntaxNode::Body { children, .. } = nodes.get(&merged) else {
+            panic!("Expected body.");
+        };
+        let [conflict, b, c] = children[..] else {
+            panic!("Unexpected children: {children:#?}");
+        };
+
+        let expected = SyntaxNode::Conflict {
+            ours: identifier("x", &mut nodes),
+            theirs: identifier("y", &mut nodes),
+        };
+        assert_eq!(nodes.get(&conflict), &expected);
+        assert_eq!(b, identifier("b", &mut nodes));
+        assert_eq!(c, identifier("c", &mut nodes));
+    }
+
+    #[test]
+    fn conflicting_bindings_result_in_conflict_of_function() {
+        let (base, ours, theirs, mut nodes) = versions(
+            "
+            fn
+                a
+                a
+            ",
+            "
+            fn
+                x
+                a
+            ",
+            "
+            fn
+                y
+                a
+            ",
+        );
+
+        let merged = merge(base, ours, theirs, &mut nodes);
+
+        let SyntaxNode::Body { children, .. } = nodes.get(&merged) else {
+            panic!("Expected body.");
+        };
+        let [conflict] = children[..] else {
+            panic!("Unexpected children: {children:#?}");
+        };
+
+        let [ours, theirs] = [ours, theirs].map(|root| {
+            let SyntaxNode::Body { children, .. } = nodes.get(&root) else {
+                panic!("Expected body.");
+            };
+            children[0]
+        });
+        assert_eq!(
+            nodes.get(&conflict),
+            &SyntaxNode::Conflict { ours, theirs },
+        );
+    }
+
+    #[test]
+    fn conflict_at_root_is_placed_into_body() {
+        // If the conflict can't take the place of any node below the root, it
+        // becomes the only child of the root. The root must stay a body.
+
+        let mut nodes = Nodes::default();
+
+        // The text format doesn't allow arms outside of a match. So the
+        // versions have to be constructed directly.
+        let [base, ours, theirs] = [0, 1, 2].map(|value| {
+            let pattern = nodes.insert(SyntaxNode::NumberPattern { value });
+            let body = Body::empty().into_syntax_node(&mut nodes);
+            let body = nodes.insert(body);
+            let arm = nodes.insert(SyntaxNode::Arm { pattern, body });
+
+            let mut root = Body::empty().into_syntax_node(&mut nodes);
+            if let SyntaxNode::Body { children, .. } = &mut root {
+                children.push(arm);
+            }
+            nodes.insert(root)
+        });
+
+        let merged = merge(base, ours, theirs, &mut nodes);
+
+        let SyntaxNode::Body { children, .. } = nodes.get(&merged) else {
+            panic!("Expected body.");
+        };
+        let [conflict] = children[..] else {
+            panic!("Unexpected children: {children:#?}");
+        };
+
+        assert_eq!(
+            nodes.get(&conflict),
+            &SyntaxNode::Conflict { ours, theirs },
+        );
+    }
+
+    fn versions(
+        base: &str,
+        ours: &str,
+        theirs: &str,
+    ) -> (NodeHash, NodeHash, NodeHash, Nodes) {
+        let mut nodes = Nodes::default();
+
+        let [base, ours, theirs] = [base, ours, theirs]
+            .map(|code| import_into(code, &mut nodes).unwrap());
+
+        (base, ours, theirs, nodes)
+    }
+
+    fn identifier(name: &str, nodes: &mut Nodes) -> NodeHash {
+        nodes.insert(SyntaxNode::Identifier {
+            name: name.to_string(),
+        })
+    }
+}
@@ -1,18 +1,37 @@
+mod bundle;
 mod changes;
 mod codebase;
+mod diff;
+mod float;
+mod integrity;
+mod merge;
+mod node_ids;
 mod nodes_typed;
 mod nodes_uniform;
+mod observers;
+mod store;
 mod types;
 
 pub use self::{
-    changes::{Changes, NewChangeSet},
-    codebase::Codebase,
-    nodes_typed::{Apply, Body, Expression, Function, Tuple, TypedNode},
+    bundle::{Bundle, BundleError},
+    changes::{ChangeSet, Changes, NewChangeSet},
+    codebase::{Codebase, VersionError},
+    diff::{Change, Diff, DiffNode},
+    float::Float,
+    integrity::{Diagnostic, ExpectedNode, check_integrity},
+    merge::{ConflictSide, merge},
+    node_ids::{NodeId, NodeIds},
+    nodes_typed::{
+        Apply, Body, Definition, Expression, FieldAccess, FieldUpdate,
+        Function, Let, List, Match, Record, Tuple, TypedNode,
+    },
     nodes_uniform::{
         ChildIndex, LocatedNode, NodeAsUniform, NodeByHash, NodeHash, NodePath,
         Nodes, SyntaxNode,
     },
-    types::{Type, display_tuple},
+    observers::{CodebaseEvent, ObserverId},
+    store::Store,
+    types::{Type, display_record, display_tuple},
 };
 
 #[cfg(test)]
@@ -0,0 +1,479 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ChangeSet, ChildIndex, LocatedNode, NodeHash, NodePath, Nodes};
+
+/// # A stable identity of a node in the syntax tree
+///
+/// Unlike [`NodePath`], which refers to a specific version of a node, a
+/// [`NodeId`] refers to "this node", regardless of how it or the rest of the
+/// syntax tree were changed since. This makes it suitable for things that need
+/// to keep referring to a node across edits, like breakpoints, annotations, or
+/// bookmarks.
+///
+/// Use [`Codebase::id_of`] and [`Codebase::path_of`] to convert between the
+/// two.
+///
+/// [`Codebase::id_of`]: super::Codebase::id_of
+/// [`Codebase::path_of`]: super::Codebase::path_of
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Deserialize,
+    Serialize,
+)]
+#[serde(transparent)]
+pub struct NodeId {
+    value: u64,
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.value)
+    }
+}
+
+/// # Tracks the [`NodeId`]s of all nodes in the current syntax tree
+///
+/// Mirrors the structure of the syntax tree, but with IDs instead of hashes.
+/// Whenever the syntax tree changes, the IDs are carried over to the new
+/// version of each node, as recorded by the [`ChangeSet`]. Nodes that didn't
+/// change keep their ID. Nodes that didn't exist before get a new one.
+///
+/// ## Implementation Note
+///
+/// A node is considered to be the new version of an old one, if the change set
+/// records it as its replacement. Failing that, an unchanged node at the same
+/// position among its siblings, or anywhere among them, is considered the
+/// same node. This is good enough for the edits we can currently make, but
+/// wouldn't follow nodes that are moved to a different parent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeIds {
+    root: NodeId,
+    children_by_id: BTreeMap<NodeId, Vec<NodeId>>,
+    parents_by_id: BTreeMap<NodeId, (NodeId, ChildIndex)>,
+
+    /// # The IDs of nodes that were removed, by their last path
+    ///
+    /// If a change restores a removed node at exactly that path, like undoing
+    /// its removal does, it gets its old ID back. The IDs of the removed node's
+    /// descendants are kept in `children_by_id` for that purpose.
+    removed: BTreeMap<NodePath, NodeId>,
+
+    next_id: u64,
+}
+
+impl NodeIds {
+    pub fn new(root: NodeHash, nodes: &Nodes) -> Self {
+        let mut ids = Self {
+            root: NodeId { value: 0 },
+            children_by_id: BTreeMap::new(),
+            parents_by_id: BTreeMap::new(),
+            removed: BTreeMap::new(),
+            next_id: 0,
+        };
+
+        ids.root = ids.assign_new_ids(&NodePath::for_root(root), nodes);
+
+        ids
+    }
+
+    /// # Restore the IDs of the syntax tree with the provided root
+    ///
+    /// Returns `None`, if the stored IDs don't mirror the structure of that
+    /// syntax tree.
+    pub(super) fn from_stored(
+        stored: StoredNodeIds,
+        root: NodeHash,
+        nodes: &Nodes,
+    ) -> Option<Self> {
+        let mut ids = Self {
+            root: stored.root,
+            children_by_id: BTreeMap::new(),
+            parents_by_id: BTreeMap::new(),
+            removed: BTreeMap::new(),
+            next_id: stored.next_id,
+        };
+
+        ids.restore(
+            stored.root,
+            &NodePath::for_root(root),
+            &stored.children,
+            nodes,
+        )?;
+
+        Some(ids)
+    }
+
+    /// # Convert the IDs of the current syntax tree into their stored form
+    pub(super) fn to_stored(&self) -> StoredNodeIds {
+        let mut children = BTreeMap::new();
+        let mut ids = vec![self.root];
+
+        while let Some(id) = ids.pop() {
+            let child_ids =
+                self.children_by_id.get(&id).cloned().unwrap_or_default();
+            ids.extend(child_ids.iter().copied());
+            children.insert(id, child_ids);
+        }
+
+        StoredNodeIds {
+            root: self.root,
+            children,
+            next_id: self.next_id,
+        }
+    }
+
+    /// # Find the ID of the node at the provided path
+    ///
+    /// Expects `path` to point into the current syntax tree.
+    pub fn id_of(&self, path: &NodePath) -> NodeId {
+        let mut indices = Vec::new();
+        let mut current = path;
+
+        while let Some((parent, index)) = current.parent() {
+            indices.push(index);
+            current = parent;
+        }
+
+        let mut id = self.root;
+
+        for index in indices.into_iter().rev() {
+            let Some(child) = self
+                .children_by_id
+                .get(&id)
+                .and_then(|children| children.get(index.index))
+            else {
+                unreachable!(
+                    "IDs mirror the structure of the current syntax tree. \
+                    Every node in it must have one."
+                );
+            };
+
+            id = *child;
+        }
+
+        id
+    }
+
+    /// # Find the path of the node with the provided ID
+    ///
+    /// Returns `None`, if the node is not part of the current syntax tree.
+    pub fn path_of(
+        &self,
+        id: NodeId,
+        root: NodeHash,
+        nodes: &Nodes,
+    ) -> Option<NodePath> {
+        let mut indices = Vec::new();
+        let mut current = id;
+
+        while current != self.root {
+            let (parent, index) = self.parents_by_id.get(&current)?;
+
+            indices.push(*index);
+            current = *parent;
+        }
+
+        let mut path = NodePath::for_root(root);
+
+        for index in indices.into_iter().rev() {
+            let child = *nodes.get(path.hash()).children().hashes[index.index];
+            path = NodePath::new(child, Some((path, index)), nodes);
+        }
+
+        Some(path)
+    }
+
+    /// # Carry over the IDs to the syntax tree that a change set produced
+    pub fn update(&mut self, change_set: &ChangeSet, nodes: &Nodes) {
+        let old = NodePath::for_root(change_set.root_before_change());
+        let new = NodePath::for_root(change_set.root_after_change());
+
+        self.update_node(self.root, &old, &new, change_set, nodes);
+    }
+
+    /// # Forget removed nodes that can no longer be restored
+    ///
+    /// Only nodes that were removed from one of the provided roots are kept.
+    pub fn retain_removed(&mut self, roots: &BTreeSet<NodeHash>) {
+        let (retained, forgotten) = std::mem::take(&mut self.removed)
+            .into_iter()
+            .partition(|(path, _)| roots.contains(path.root_hash()));
+
+        self.removed = retained;
+
+        for (_, id) in forgotten {
+            self.forget(id);
+        }
+    }
+
+    fn update_node(
+        &mut self,
+        id: NodeId,
+        old: &NodePath,
+        new: &NodePath,
+        change_set: &ChangeSet,
+        nodes: &Nodes,
+    ) {
+        if old.hash() == new.hash() {
+            // The whole subtree is unchanged, and so are its IDs.
+            return;
+        }
+
+        let old_children = LocatedNode {
+            node: nodes.get(old.hash()),
+            path: old.clone(),
+        }
+        .children(nodes)
+        .zip(self.children_by_id.get(&id).cloned().unwrap_or_default())
+        .map(|(located, id)| Some((located.path, id)))
+        .collect::<Vec<_>>();
+        let mut unmatched = old_children;
+
+        let new_children = LocatedNode {
+            node: nodes.get(new.hash()),
+            path: new.clone(),
+        }
+        .children(nodes)
+        .collect::<Vec<_>>();
+
+        let mut child_ids = Vec::new();
+
+        for (index, new_child) in new_children.into_iter().enumerate() {
+            let new_child = new_child.path;
+            let index = ChildIndex { index };
+
+            let child_id = if let Some(old_child) =
+                find_old_version(&new_child, index, &mut unmatched, change_set)
+            {
+                let (old_child, child_id) = old_child;
+                self.update_node(
+                    child_id, &old_child, &new_child, change_set, nodes,
+                );
+                child_id
+            } else if let Some(child_id) = self.removed.remove(&new_child) {
+                child_id
+            } else {
+                self.assign_new_ids(&new_child, nodes)
+            };
+
+            self.parents_by_id.insert(child_id, (id, index));
+            child_ids.push(child_id);
+        }
+
+        for (path, child_id) in unmatched.into_iter().flatten() {
+            self.parents_by_id.remove(&child_id);
+            self.removed.insert(path, child_id);
+        }
+
+        self.children_by_id.insert(id, child_ids);
+    }
+
+    fn assign_new_ids(&mut self, path: &NodePath, nodes: &Nodes) -> NodeId {
+        let id = NodeId {
+            value: self.next_id,
+        };
+        self.next_id += 1;
+
+        let located = LocatedNode {
+            node: nodes.get(path.hash()),
+            path: path.clone(),
+        };
+
+        let mut child_ids = Vec::new();
+
+        for (index, child) in located.children(nodes).enumerate() {
+            let child_id = self.assign_new_ids(&child.path, nodes);
+
+            self.parents_by_id
+                .insert(child_id, (id, ChildIndex { index }));
+            child_ids.push(child_id);
+        }
+
+        self.children_by_id.insert(id, child_ids);
+
+        id
+    }
+
+    fn restore(
+        &mut self,
+        id: NodeId,
+        path: &NodePath,
+        stored: &BTreeMap<NodeId, Vec<NodeId>>,
+        nodes: &Nodes,
+    ) -> Option<()> {
+        if id.value >= self.next_id || self.children_by_id.contains_key(&id) {
+            // Either way, the same ID could end up with two nodes.
+            return None;
+        }
+
+        let child_ids = stored.get(&id)?;
+        let children = LocatedNode {
+            node: nodes.get(path.hash()),
+            path: path.clone(),
+        }
+        .children(nodes)
+        .collect::<Vec<_>>();
+
+        if child_ids.len() != children.len() {
+            return None;
+        }
+
+        self.children_by_id.insert(id, child_ids.clone());
+
+        for (index, (child, child_id)) in
+            children.into_iter().zip(child_ids).enumerate()
+        {
+            self.parents_by_id
+                .insert(*child_id, (id, ChildIndex { index }));
+            self.restore(*child_id, &child.path, stored, nodes)?;
+        }
+
+        Some(())
+    }
+
+    fn forget(&mut self, id: NodeId) {
+        self.parents_by_id.remove(&id);
+
+        for child in self.children_by_id.remove(&id).unwrap_or_default() {
+            self.forget(child);
+        }
+    }
+}
+
+/// # The [`NodeIds`] of a syntax tree, in the form they are stored in
+///
+/// Only the IDs of the nodes that are currently part of the syntax tree are
+/// stored. The IDs of removed nodes are only needed to undo their removal, and
+/// the history of changes is not stored.
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct StoredNodeIds {
+    root: NodeId,
+    children: BTreeMap<NodeId, Vec<NodeId>>,
+    next_id: u64,
+}
+
+fn find_old_version(
+    new: &NodePath,
+    index: ChildIndex,
+    unmatched: &mut [Option<(NodePath, NodeId)>],
+    change_set: &ChangeSet,
+) -> Option<(NodePath, NodeId)> {
+    let replaced = unmatched.iter().position(|old| {
+        old.as_ref().is_some_and(|(old, _)| {
+            change_set
+                .latest_version_of(old)
+                .is_ok_and(|latest| latest == new)
+        })
+    });
+    let unchanged_at_same_index = unmatched
+        .get(index.index)
+        .and_then(|old| old.as_ref())
+        .is_some_and(|(old, _)| old.hash() == new.hash())
+        .then_some(index.index);
+    let unchanged_anywhere = unmatched.iter().position(|old| {
+        old.as_ref()
+            .is_some_and(|(old, _)| old.hash() == new.hash())
+    });
+
+    let position = replaced
+        .or(unchanged_at_same_index)
+        .or(unchanged_anywhere)?;
+    unmatched[position].take()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{
+        code::{Codebase, NodePath, SyntaxNode},
+        compiler::Compiler,
+    };
+
+    #[test]
+    fn id_survives_edits() {
+        // A node keeps its ID, when it is replaced, and when other nodes in the
+        // syntax tree change.
+
+        let mut codebase = Codebase::new();
+
+        let mut compiler = Compiler::new(&mut codebase);
+        let root = compiler.codebase().root().path;
+        let a = compiler.insert_child(root, "a");
+        let id = compiler.codebase().id_of(&a).unwrap();
+
+        let root = compiler.codebase().root().path;
+        compiler.insert_child(root, "b");
+
+        let a = compiler.codebase().path_of(id).unwrap();
+        assert_eq!(
+            compiler.codebase().node_at(&a).node,
+            &SyntaxNode::Identifier {
+                name: String::from("a"),
+            },
+        );
+
+        let c = compiler.replace(&a, "c");
+        assert_eq!(compiler.codebase().id_of(&c), Some(id));
+    }
+
+    #[test]
+    fn new_nodes_get_new_ids() {
+        // Even identical nodes get different IDs, if they were added
+        // separately.
+
+        let mut codebase = Codebase::new();
+
+        let mut compiler = Compiler::new(&mut codebase);
+        let root = compiler.codebase().root().path;
+        let a = compiler.insert_child(root, "a");
+        let a = compiler.codebase().id_of(&a).unwrap();
+
+        let root = compiler.codebase().root().path;
+        let b = compiler.insert_child(root, "a");
+        let b = compiler.codebase().id_of(&b).unwrap();
+
+        assert_ne!(a, b);
+        assert!(compiler.codebase().path_of(a).is_some());
+        assert!(compiler.codebase().path_of(b).is_some());
+    }
+
+    #[test]
+    fn removed_node_gets_id_back_on_undo() {
+        // A removed node is no longer part of the syntax tree. But undoing its
+        // removal restores it, with the same ID.
+
+        let mut codebase = Codebase::new();
+
+        let mut compiler = Compiler::new(&mut codebase);
+        let root = compiler.codebase().root().path;
+        let a = compiler.insert_child(root, "a");
+        let id = compiler.codebase().id_of(&a).unwrap();
+
+        codebase.make_change(|change_set| {
+            let root = change_set.root_before_change();
+
+            let mut node = change_set.nodes.get(root.hash()).clone();
+            if let SyntaxNode::Body { children, .. } = &mut node {
+                children.clear();
+            }
+
+            let hash = change_set.nodes.insert(node);
+            change_set.replace(&root, &NodePath::for_root(hash));
+        });
+        assert_eq!(codebase.path_of(id), None);
+
+        codebase.undo();
+        assert_eq!(codebase.path_of(id), Some(a));
+    }
+}
@@ -53,11 +53,11 @@ impl Body<NodeByHash> {
         Self { children, add }
     }
 
-    pub fn children(&self) -> TypedChildren<Ref> {
+    pub fn children(&self) -> TypedChildren<Ref<'_>> {
         TypedChildren::new(&self.children, 0)
     }
 
-    pub fn children_mut(&mut self) -> TypedChildren<RefMut> {
+    pub fn children_mut(&mut self) -> TypedChildren<RefMut<'_>> {
         TypedChildren::new(&mut self.children, 0)
     }
 
@@ -0,0 +1,30 @@
+use crate::{
+    language::code::{NodeAsUniform, NodeHash, Nodes, SyntaxNode},
+    util::form::Form,
+};
+
+use super::Binding;
+
+#[derive(Debug)]
+pub struct Definition<T: Form> {
+    pub name: T::Form<Binding>,
+    pub value: T::Form<NodeHash>,
+}
+
+impl Definition<NodeAsUniform> {
+    pub fn empty() -> Self {
+        Self {
+            name: SyntaxNode::Binding {
+                name: "".to_string(),
+            },
+            value: SyntaxNode::Empty,
+        }
+    }
+
+    pub fn into_syntax_node(self, nodes: &mut Nodes) -> SyntaxNode {
+        let name = nodes.insert(self.name);
+        let value = nodes.insert(self.value);
+
+        SyntaxNode::Definition { name, value }
+    }
+}
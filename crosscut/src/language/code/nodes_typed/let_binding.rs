@@ -0,0 +1,41 @@
+use crate::{
+    language::code::{NodeAsUniform, NodeHash, Nodes, SyntaxNode},
+    util::form::{Form, Owned},
+};
+
+use super::{Binding, TypedChild};
+
+#[derive(Debug)]
+pub struct Let<T: Form> {
+    pub binding: T::Form<Binding>,
+    pub value: T::Form<NodeHash>,
+}
+
+impl Let<Owned> {
+    pub fn new(binding: &NodeHash, value: NodeHash, nodes: &Nodes) -> Self {
+        let binding = Binding::from_hash(binding, nodes);
+        Self { binding, value }
+    }
+
+    pub fn value(&self) -> TypedChild {
+        TypedChild::new(self.value, 1)
+    }
+}
+
+impl Let<NodeAsUniform> {
+    pub fn empty() -> Self {
+        Self {
+            binding: SyntaxNode::Binding {
+                name: "".to_string(),
+            },
+            value: SyntaxNode::Empty,
+        }
+    }
+
+    pub fn into_syntax_node(self, nodes: &mut Nodes) -> SyntaxNode {
+        let binding = nodes.insert(self.binding);
+        let value = nodes.insert(self.value);
+
+        SyntaxNode::Let { binding, value }
+    }
+}
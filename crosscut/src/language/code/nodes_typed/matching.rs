@@ -0,0 +1,43 @@
+use crate::{
+    language::code::{NodeByHash, Nodes, SyntaxNode},
+    util::form::{Form, Owned},
+};
+
+use super::{Body, TypedChild};
+
+#[derive(Debug)]
+pub struct Match<T: Form> {
+    pub scrutinee: T::Form<SyntaxNode>,
+    pub arms: T::Form<Body<Owned>>,
+}
+
+impl Match<Owned> {
+    pub fn into_syntax_node(self, nodes: &mut Nodes) -> SyntaxNode {
+        let scrutinee = nodes.insert(self.scrutinee);
+        let arms = {
+            let node = self.arms.into_syntax_node(nodes);
+            nodes.insert(node)
+        };
+
+        SyntaxNode::Match { scrutinee, arms }
+    }
+}
+
+impl Match<NodeByHash> {
+    pub fn scrutinee(&self) -> TypedChild {
+        TypedChild::new(self.scrutinee, 0)
+    }
+
+    pub fn arms(&self) -> TypedChild {
+        TypedChild::new(self.arms, 1)
+    }
+}
+
+impl Default for Match<Owned> {
+    fn default() -> Self {
+        Self {
+            scrutinee: SyntaxNode::Empty,
+            arms: Body::empty(),
+        }
+    }
+}
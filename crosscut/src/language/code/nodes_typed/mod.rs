@@ -2,7 +2,12 @@ mod apply;
 mod binding;
 mod body;
 mod children;
+mod definition;
 mod function;
+mod let_binding;
+mod list;
+mod matching;
+mod record;
 mod tuple;
 mod typed_node;
 
@@ -11,7 +16,12 @@ pub use self::{
     binding::Binding,
     body::Body,
     children::{TypedChild, TypedChildren},
+    definition::Definition,
     function::Function,
+    let_binding::Let,
+    list::List,
+    matching::Match,
+    record::{FieldAccess, FieldUpdate, Record},
     tuple::Tuple,
     typed_node::{Expression, TypedNode},
 };
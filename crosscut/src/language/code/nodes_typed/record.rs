@@ -0,0 +1,109 @@
+use crate::{
+    language::code::{NodeByHash, Nodes, SyntaxNode},
+    util::form::{Form, Owned},
+};
+
+use super::{Binding, Body, TypedChild};
+
+#[derive(Debug)]
+pub struct Record<T: Form> {
+    pub fields: T::Form<Body<Owned>>,
+}
+
+impl Record<Owned> {
+    pub fn empty() -> Self {
+        Self {
+            fields: Body::empty(),
+        }
+    }
+
+    pub fn into_syntax_node(self, nodes: &mut Nodes) -> SyntaxNode {
+        let fields = {
+            let node = self.fields.into_syntax_node(nodes);
+            nodes.insert(node)
+        };
+
+        SyntaxNode::Record { fields }
+    }
+}
+
+impl Record<NodeByHash> {
+    pub fn fields(&self) -> TypedChild {
+        TypedChild::new(self.fields, 0)
+    }
+}
+
+#[derive(Debug)]
+pub struct FieldAccess<T: Form> {
+    pub record: T::Form<SyntaxNode>,
+    pub field: T::Form<Binding>,
+}
+
+impl FieldAccess<Owned> {
+    pub fn into_syntax_node(self, nodes: &mut Nodes) -> SyntaxNode {
+        let record = nodes.insert(self.record);
+        let field = nodes.insert(SyntaxNode::Binding {
+            name: self.field.name,
+        });
+
+        SyntaxNode::FieldAccess { record, field }
+    }
+}
+
+impl FieldAccess<NodeByHash> {
+    pub fn record(&self) -> TypedChild {
+        TypedChild::new(self.record, 0)
+    }
+
+    pub fn field(&self, nodes: &Nodes) -> Binding {
+        Binding::from_hash(&self.field, nodes)
+    }
+}
+
+impl Default for FieldAccess<Owned> {
+    fn default() -> Self {
+        Self {
+            record: SyntaxNode::Empty,
+            field: Binding {
+                name: "".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FieldUpdate<T: Form> {
+    pub record: T::Form<SyntaxNode>,
+    pub fields: T::Form<Body<Owned>>,
+}
+
+impl FieldUpdate<Owned> {
+    pub fn into_syntax_node(self, nodes: &mut Nodes) -> SyntaxNode {
+        let record = nodes.insert(self.record);
+        let fields = {
+            let node = self.fields.into_syntax_node(nodes);
+            nodes.insert(node)
+        };
+
+        SyntaxNode::FieldUpdate { record, fields }
+    }
+}
+
+impl FieldUpdate<NodeByHash> {
+    pub fn record(&self) -> TypedChild {
+        TypedChild::new(self.record, 0)
+    }
+
+    pub fn fields(&self) -> TypedChild {
+        TypedChild::new(self.fields, 1)
+    }
+}
+
+impl Default for FieldUpdate<Owned> {
+    fn default() -> Self {
+        Self {
+            record: SyntaxNode::Empty,
+            fields: Body::empty(),
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
-    language::code::{NodeByHash, NodeHash, Nodes, SyntaxNode},
+    language::code::{Float, NodeByHash, NodeHash, Nodes, SyntaxNode},
     util::form::Owned,
 };
 
-use super::{Apply, Body, Function, Tuple};
+use super::{
+    Apply, Body, FieldAccess, FieldUpdate, Function, Let, List, Match, Record,
+    Tuple,
+};
 
 #[derive(Debug)]
 pub enum TypedNode {
@@ -32,15 +35,40 @@ impl TypedNode {
                     },
                 },
             },
-            SyntaxNode::Binding { .. } => Self::Pattern,
+            SyntaxNode::Arm { .. } => Self::Other,
+            SyntaxNode::Binding { .. }
+            | SyntaxNode::BooleanPattern { .. }
+            | SyntaxNode::NumberPattern { .. }
+            | SyntaxNode::RecordPattern { .. }
+            | SyntaxNode::TuplePattern { .. }
+            | SyntaxNode::Wildcard => Self::Pattern,
+            SyntaxNode::Boolean { value } => Self::Expression {
+                expression: Expression::Boolean { value },
+            },
             SyntaxNode::Body { children, add } => Self::Expression {
                 expression: Expression::Body {
                     body: Body { children, add },
                 },
             },
+            SyntaxNode::Conflict { .. } => Self::Expression {
+                expression: Expression::Conflict,
+            },
+            SyntaxNode::Definition { .. } => Self::Expression {
+                expression: Expression::Definition,
+            },
             SyntaxNode::Empty => Self::Expression {
                 expression: Expression::Empty,
             },
+            SyntaxNode::FieldAccess { record, field } => Self::Expression {
+                expression: Expression::FieldAccess {
+                    access: FieldAccess { record, field },
+                },
+            },
+            SyntaxNode::FieldUpdate { record, fields } => Self::Expression {
+                expression: Expression::FieldUpdate {
+                    update: FieldUpdate { record, fields },
+                },
+            },
             SyntaxNode::Function { parameter, body } => Self::Expression {
                 expression: Expression::Function {
                     function: Function::new(&parameter, body, nodes),
@@ -49,9 +77,41 @@ impl TypedNode {
             SyntaxNode::Identifier { name } => Self::Expression {
                 expression: Expression::Identifier { name },
             },
+            SyntaxNode::Let { binding, value } => Self::Expression {
+                expression: Expression::Let {
+                    let_: Let::new(&binding, value, nodes),
+                },
+            },
+            SyntaxNode::List { values } => Self::Expression {
+                expression: Expression::List {
+                    list: List { values },
+                },
+            },
+            SyntaxNode::Match { scrutinee, arms } => Self::Expression {
+                expression: Expression::Match {
+                    match_: Match { scrutinee, arms },
+                },
+            },
+            SyntaxNode::Float { value } => Self::Expression {
+                expression: Expression::Float { value },
+            },
+            SyntaxNode::Integer64 { value } => Self::Expression {
+                expression: Expression::Integer64 { value },
+            },
             SyntaxNode::Number { value } => Self::Expression {
                 expression: Expression::Number { value },
             },
+            SyntaxNode::String { value } => Self::Expression {
+                expression: Expression::String { value },
+            },
+            SyntaxNode::Unsigned { value } => Self::Expression {
+                expression: Expression::Unsigned { value },
+            },
+            SyntaxNode::Record { fields } => Self::Expression {
+                expression: Expression::Record {
+                    record: Record { fields },
+                },
+            },
             SyntaxNode::Recursion => Self::Expression {
                 expression: Expression::Recursion,
             },
@@ -68,10 +128,23 @@ impl TypedNode {
 pub enum Expression {
     Apply { apply: Apply<NodeByHash> },
     Body { body: Body<NodeByHash> },
+    Boolean { value: bool },
+    Conflict,
+    Definition,
     Empty,
+    FieldAccess { access: FieldAccess<NodeByHash> },
+    FieldUpdate { update: FieldUpdate<NodeByHash> },
+    Float { value: Float },
     Function { function: Function<Owned> },
     Identifier { name: String },
+    Integer64 { value: i64 },
+    Let { let_: Let<Owned> },
+    List { list: List<NodeByHash> },
+    Match { match_: Match<NodeByHash> },
     Number { value: i32 },
+    Record { record: Record<NodeByHash> },
     Recursion,
+    String { value: String },
     Tuple { tuple: Tuple<NodeByHash> },
+    Unsigned { value: u32 },
 }
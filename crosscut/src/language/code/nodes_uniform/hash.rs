@@ -1,6 +1,7 @@
 use std::fmt;
 
 use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
 use crate::util::form::Form;
 
@@ -38,6 +39,58 @@ impl NodeHash {
             inner: udigest::hash::<blake3::Hasher>(node).into(),
         }
     }
+
+    /// # Check whether this is the hash of the provided node
+    ///
+    /// Returns the actual hash of the node, if it's not. This is meant for
+    /// detecting nodes that were modified, when loading them from outside.
+    pub fn verify<T>(&self, node: &T) -> Result<(), Self>
+    where
+        T: udigest::Digestable,
+    {
+        let actual = Self::new(node);
+
+        if actual == *self { Ok(()) } else { Err(actual) }
+    }
+}
+
+impl Serialize for NodeHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// # Deserialize a hash from its string representation
+///
+/// ## Implementation Note
+///
+/// This creates a hash without the node that it refers to, which is exactly
+/// what [`NodeHash::new`] is designed to prevent. This is required for loading
+/// stored nodes though, and code that does that must make sure to never hand a
+/// hash to [`Nodes`] before the node it refers to has been inserted there.
+///
+/// [`Nodes`]: super::Nodes
+impl<'de> Deserialize<'de> for NodeHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(&string)
+            .map_err(de::Error::custom)?;
+        let Ok(inner) = bytes.try_into() else {
+            return Err(de::Error::custom(format!(
+                "`{string}` does not have the length of a node hash"
+            )));
+        };
+
+        Ok(Self { inner })
+    }
 }
 
 impl fmt::Debug for NodeHash {
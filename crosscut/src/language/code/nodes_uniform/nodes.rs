@@ -1,7 +1,13 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use super::{NodeHash, SyntaxNode};
 
+/// # The storage for all syntax nodes
+///
+/// Nodes are never modified once inserted. Changing the code means inserting
+/// new nodes, leaving the old ones in place. This means nodes that are no
+/// longer referenced accumulate over time, which is what
+/// [`Nodes::retain_reachable_from`] is for.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Nodes {
     inner: BTreeMap<NodeHash, SyntaxNode>,
@@ -11,17 +17,47 @@ impl Nodes {
     pub fn get(&self, hash: &NodeHash) -> &SyntaxNode {
         let Some(node) = self.inner.get(hash) else {
             unreachable!(
-                "This is an append-only data structure. All hashes that were \
-                ever created must be valid."
+                "Nodes are only removed, once they are no longer reachable. \
+                All hashes that are still referenced must be valid."
             );
         };
 
         node
     }
 
+    pub fn contains(&self, hash: &NodeHash) -> bool {
+        self.inner.contains_key(hash)
+    }
+
     pub fn insert(&mut self, node: SyntaxNode) -> NodeHash {
         let hash = NodeHash::new(&node);
         self.inner.insert(hash, node);
         hash
     }
+
+    /// # Remove all nodes that are not reachable from the provided roots
+    ///
+    /// It is the responsibility of the caller to provide the roots of _all_
+    /// syntax trees that are still referenced anywhere. Otherwise, the
+    /// guarantee that every hash is valid no longer holds.
+    pub fn retain_reachable_from(
+        &mut self,
+        roots: impl IntoIterator<Item = NodeHash>,
+    ) {
+        let mut reachable = BTreeSet::new();
+        let mut to_visit = roots.into_iter().collect::<Vec<_>>();
+
+        while let Some(hash) = to_visit.pop() {
+            if reachable.insert(hash) {
+                to_visit.extend(self.get(&hash).children().hashes);
+            }
+        }
+
+        self.inner.retain(|hash, _| reachable.contains(hash));
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.inner.len()
+    }
 }
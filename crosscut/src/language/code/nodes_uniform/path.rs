@@ -111,6 +111,17 @@ impl NodePath {
             .map(|(path, index)| (path.deref(), *index))
     }
 
+    /// # The hash of the root of the syntax tree that this path points into
+    pub fn root_hash(&self) -> &NodeHash {
+        let mut path = self;
+
+        while let Some((parent, _)) = &path.parent {
+            path = parent;
+        }
+
+        &path.hash
+    }
+
     pub fn is_ancestor_of(&self, possible_descendant: &NodePath) -> bool {
         let mut maybe_parent = possible_descendant.parent.as_ref();
 
@@ -1,7 +1,9 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    language::code::NodeHash,
+    language::code::{Float, NodeHash},
     util::form::{Form, Ref, RefMut},
 };
 
@@ -29,7 +31,23 @@ use super::Children;
 /// Experience has shown that, pending further insights that might this more
 /// tenable, a typed representation is not desirable as a base layer, for these
 /// reasons.
-#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, udigest::Digestable)]
+///
+/// ## Attention: Renaming Variants or Fields Breaks Stored Code
+///
+/// The names of this enum's variants and their fields are part of the input to
+/// [`NodeHash`], and of the format that nodes are stored in. Adding variants is
+/// fine, but renaming existing ones makes stored code unreadable.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Deserialize,
+    Serialize,
+    udigest::Digestable,
+)]
 pub enum SyntaxNode {
     /// # A node used for adding a child to its parent
     ///
@@ -77,6 +95,33 @@ pub enum SyntaxNode {
         argument: NodeHash,
     },
 
+    /// # An arm of a [`SyntaxNode::Match`]
+    Arm {
+        /// # The pattern that a value must match, for this arm to be chosen
+        ///
+        /// This is expected to be a pattern, like a [`SyntaxNode::Binding`]
+        /// or a [`SyntaxNode::Wildcard`].
+        pattern: NodeHash,
+
+        /// # The body that is evaluated, if this arm is chosen
+        ///
+        /// This is expected to be a [`SyntaxNode::Body`]. The names that the
+        /// pattern binds are visible within it.
+        body: NodeHash,
+    },
+
+    /// # A boolean literal
+    Boolean {
+        /// # The value this literal evaluates to
+        value: bool,
+    },
+
+    /// # A pattern that matches a specific boolean value
+    BooleanPattern {
+        /// # The value that this pattern matches
+        value: bool,
+    },
+
     /// # Assigns a name to a value
     Binding {
         /// # The name that this binding assigns to the value
@@ -101,6 +146,43 @@ pub enum SyntaxNode {
         add: NodeHash,
     },
 
+    /// # A conflict between two versions of the same code
+    ///
+    /// Conflicts are created when merging, if both versions that are merged
+    /// changed the same node in different ways. The conflict takes the place of
+    /// that node, until it is resolved by picking one of the two versions.
+    ///
+    /// If both versions changed the same run of children within a body, the
+    /// two versions are bodies that contain the respective children. Picking
+    /// one of them replaces the conflict with those children.
+    Conflict {
+        /// # The version of the node on the branch that was merged into
+        ours: NodeHash,
+
+        /// # The version of the node on the branch that was merged
+        theirs: NodeHash,
+    },
+
+    /// # A named definition
+    ///
+    /// Definitions that are children of the root body make up the top level of
+    /// the program. They can refer to each other by name, and the one named
+    /// [`Codebase::MAIN`] is the program's entry point.
+    ///
+    /// Evaluating a definition has no effect. Its value is only evaluated,
+    /// where the definition is referred to by name.
+    ///
+    /// [`Codebase::MAIN`]: crate::language::code::Codebase::MAIN
+    Definition {
+        /// # The name of the definition
+        ///
+        /// This is expected to be a [`SyntaxNode::Binding`].
+        name: NodeHash,
+
+        /// # The value of the definition
+        value: NodeHash,
+    },
+
     /// # An empty node
     ///
     /// Empty nodes are placeholders, while the user is editing the code. They
@@ -129,6 +211,41 @@ pub enum SyntaxNode {
     /// practical solution.
     Empty,
 
+    /// # Access to a field of a record
+    ///
+    /// Evaluates to the value of the field.
+    FieldAccess {
+        /// # The record whose field is accessed
+        record: NodeHash,
+
+        /// # The name of the field
+        ///
+        /// This is expected to be a [`SyntaxNode::Binding`].
+        field: NodeHash,
+    },
+
+    /// # A functional update of the fields of a record
+    ///
+    /// Evaluates to a copy of the record, in which the updated fields have
+    /// their new values. The original record is not modified.
+    FieldUpdate {
+        /// # The record whose fields are updated
+        record: NodeHash,
+
+        /// # The new values of the updated fields
+        ///
+        /// This is expected to be a [`SyntaxNode::Body`], with a
+        /// [`SyntaxNode::Definition`] for each updated field. Each of them
+        /// must refer to a field that the record already has.
+        fields: NodeHash,
+    },
+
+    /// # A floating-point number literal
+    Float {
+        /// # The value of the number this literal evaluates to
+        value: Float,
+    },
+
     /// # A function literal
     Function {
         /// # The parameter of the function
@@ -148,17 +265,102 @@ pub enum SyntaxNode {
         name: String,
     },
 
+    /// # A literal of a signed, 64-bit integer
+    Integer64 {
+        /// # The value of the number this literal evaluates to
+        value: i64,
+    },
+
+    /// # Binds the value of an expression to a name
+    ///
+    /// The name is visible to the siblings that follow the node within the
+    /// same body. Evaluating the node itself results in the empty tuple.
+    Let {
+        /// # The binding that assigns a name to the value
+        ///
+        /// This is expected to be a [`SyntaxNode::Binding`].
+        binding: NodeHash,
+
+        /// # The expression whose value is bound to the name
+        value: NodeHash,
+    },
+
+    /// # A list literal
+    ///
+    /// A literal that evaluates to a list. Unlike tuples, lists can grow and
+    /// shrink, as intrinsic functions create modified copies of them.
+    List {
+        /// # The items of the list
+        ///
+        /// This is expected to be a [`SyntaxNode::Body`].
+        values: NodeHash,
+    },
+
+    /// # Chooses what to evaluate, depending on the shape of a value
+    ///
+    /// Evaluates the body of the first arm whose pattern matches the value. If
+    /// no arm matches, that is an error.
+    Match {
+        /// # The expression whose value is matched against the arms
+        scrutinee: NodeHash,
+
+        /// # The arms of the match
+        ///
+        /// This is expected to be a [`SyntaxNode::Body`], whose children are
+        /// all [`SyntaxNode::Arm`]s.
+        arms: NodeHash,
+    },
+
     /// # A number literal
+    ///
+    /// Without a suffix that selects a different type, number literals
+    /// evaluate to signed, 32-bit integers. Literals of other number types are
+    /// represented by [`SyntaxNode::Float`], [`SyntaxNode::Integer64`], and
+    /// [`SyntaxNode::Unsigned`].
+    ///
+    /// Literals can be written in various formats, but all of them result in
+    /// the same node. Only the value is kept.
     Number {
         /// # The value of the number this literal evaluates to
+        value: i32,
+    },
+
+    /// # A pattern that matches a specific number
+    ///
+    /// ## Implementation Note
+    ///
+    /// Only signed, 32-bit integers can be matched so far.
+    NumberPattern {
+        /// # The value of the number that this pattern matches
+        value: i32,
+    },
+
+    /// # A record literal
+    ///
+    /// A literal that evaluates to a composite data type with named fields, a
+    /// record.
+    Record {
+        /// # The fields of the record
         ///
-        /// ## Implementation Note
+        /// This is expected to be a [`SyntaxNode::Body`]. Each
+        /// [`SyntaxNode::Definition`] in there defines a field. Any other
+        /// children are ignored.
+        fields: NodeHash,
+    },
+
+    /// # A pattern that matches a record and binds its fields
+    ///
+    /// ## Implementation Note
+    ///
+    /// Each field is bound to a name that is the same as its own. Matching the
+    /// value of a field against another pattern is not supported yet.
+    RecordPattern {
+        /// # The fields that are bound
         ///
-        /// At this point, number literals always evaluate to signed, 32-bit
-        /// integers, so that's the type of this field. In the future, once we
-        /// support more number types, and more ways of specifying literals
-        /// except as decimal numbers, this needs to become more sophisticated.
-        value: i32,
+        /// This is expected to be a [`SyntaxNode::Body`], whose children are
+        /// all [`SyntaxNode::Binding`]s, each named after a field. The record
+        /// may have more fields than that.
+        fields: NodeHash,
     },
 
     /// # The recursive application of the current function
@@ -174,17 +376,42 @@ pub enum SyntaxNode {
     /// ## Implementation Note
     ///
     /// Tuples only exist in the language as a placeholder. I (@hannobraun)
-    /// expect to replace them with record types at some point.
+    /// expect to replace them with records (see [`SyntaxNode::Record`]) at
+    /// some point.
     Tuple {
         /// # The values of the tuple
         ///
         /// This is expected to be a [`SyntaxNode::Body`].
         values: NodeHash,
     },
+
+    /// # A string literal
+    String {
+        /// # The text this literal evaluates to
+        value: String,
+    },
+
+    /// # A pattern that matches a tuple and its values
+    TuplePattern {
+        /// # The patterns that the values of the tuple must match
+        ///
+        /// This is expected to be a [`SyntaxNode::Body`], whose children are
+        /// all patterns. The tuple must have exactly as many values.
+        values: NodeHash,
+    },
+
+    /// # A literal of an unsigned, 32-bit integer
+    Unsigned {
+        /// # The value of the number this literal evaluates to
+        value: u32,
+    },
+
+    /// # A pattern that matches any value, without binding it
+    Wildcard,
 }
 
 impl SyntaxNode {
-    pub fn children(&self) -> Children<Ref> {
+    pub fn children(&self) -> Children<Ref<'_>> {
         let mut hashes = Vec::new();
 
         match self {
@@ -193,24 +420,66 @@ impl SyntaxNode {
                 argument,
             } => hashes.extend([expression, argument]),
 
+            Self::Arm { pattern, body } => {
+                hashes.extend([pattern, body]);
+            }
+
             Self::Add
             | Self::Binding { .. }
+            | Self::Boolean { value: _ }
+            | Self::BooleanPattern { value: _ }
             | Self::Empty
             | Self::Identifier { .. }
+            | Self::Float { value: _ }
+            | Self::Integer64 { value: _ }
             | Self::Number { value: _ }
-            | Self::Recursion => {}
+            | Self::NumberPattern { value: _ }
+            | Self::Recursion
+            | Self::String { value: _ }
+            | Self::Unsigned { value: _ }
+            | Self::Wildcard => {}
 
             Self::Body { children, add } => {
                 hashes.extend(children);
                 hashes.push(add);
             }
 
+            Self::Conflict { ours, theirs } => {
+                hashes.extend([ours, theirs]);
+            }
+
+            Self::Definition { name, value } => {
+                hashes.extend([name, value]);
+            }
+
+            Self::FieldAccess { record, field } => {
+                hashes.extend([record, field]);
+            }
+
+            Self::FieldUpdate { record, fields } => {
+                hashes.extend([record, fields]);
+            }
+
             Self::Function { parameter, body } => {
                 hashes.push(parameter);
                 hashes.push(body);
             }
 
-            Self::Tuple { values } => {
+            Self::Let { binding, value } => {
+                hashes.extend([binding, value]);
+            }
+
+            Self::Match { scrutinee, arms } => {
+                hashes.extend([scrutinee, arms]);
+            }
+
+            Self::Record { fields } | Self::RecordPattern { fields } => {
+                hashes.push(fields);
+            }
+
+            Self::List { values }
+            | Self::Tuple { values }
+            | Self::TuplePattern { values } => {
                 hashes.push(values);
             }
         }
@@ -218,7 +487,7 @@ impl SyntaxNode {
         Children { hashes }
     }
 
-    pub fn children_mut(&mut self) -> Children<RefMut> {
+    pub fn children_mut(&mut self) -> Children<RefMut<'_>> {
         let mut hashes = Vec::new();
 
         match self {
@@ -227,24 +496,66 @@ impl SyntaxNode {
                 argument,
             } => hashes.extend([expression, argument]),
 
+            Self::Arm { pattern, body } => {
+                hashes.extend([pattern, body]);
+            }
+
             Self::Add
             | Self::Binding { .. }
+            | Self::Boolean { value: _ }
+            | Self::BooleanPattern { value: _ }
             | Self::Empty
             | Self::Identifier { .. }
+            | Self::Float { value: _ }
+            | Self::Integer64 { value: _ }
             | Self::Number { value: _ }
-            | Self::Recursion => {}
+            | Self::NumberPattern { value: _ }
+            | Self::Recursion
+            | Self::String { value: _ }
+            | Self::Unsigned { value: _ }
+            | Self::Wildcard => {}
 
             Self::Body { children, add } => {
                 hashes.extend(children);
                 hashes.push(add);
             }
 
+            Self::Conflict { ours, theirs } => {
+                hashes.extend([ours, theirs]);
+            }
+
+            Self::Definition { name, value } => {
+                hashes.extend([name, value]);
+            }
+
+            Self::FieldAccess { record, field } => {
+                hashes.extend([record, field]);
+            }
+
+            Self::FieldUpdate { record, fields } => {
+                hashes.extend([record, fields]);
+            }
+
             Self::Function { parameter, body } => {
                 hashes.push(parameter);
                 hashes.push(body);
             }
 
-            Self::Tuple { values } => {
+            Self::Let { binding, value } => {
+                hashes.extend([binding, value]);
+            }
+
+            Self::Match { scrutinee, arms } => {
+                hashes.extend([scrutinee, arms]);
+            }
+
+            Self::Record { fields } | Self::RecordPattern { fields } => {
+                hashes.push(fields);
+            }
+
+            Self::List { values }
+            | Self::Tuple { values }
+            | Self::TuplePattern { values } => {
                 hashes.push(values);
             }
         }
@@ -266,34 +577,100 @@ impl fmt::Display for SyntaxNode {
             SyntaxNode::Apply { .. } => {
                 write!(f, "apply")
             }
+            SyntaxNode::Arm { .. } => {
+                write!(f, "arm")
+            }
             SyntaxNode::Binding { name } => {
                 write!(f, "{name}")
             }
+            SyntaxNode::Boolean { value }
+            | SyntaxNode::BooleanPattern { value } => {
+                write!(f, "{value}")
+            }
             SyntaxNode::Body { .. } => {
                 unreachable!(
                     "Body node is not directly displayed in the editor."
                 );
             }
+            SyntaxNode::Conflict { .. } => {
+                write!(f, "conflict")
+            }
+            SyntaxNode::Definition { .. } => {
+                write!(f, "def")
+            }
             SyntaxNode::Empty => {
                 write!(f, "")
             }
+            SyntaxNode::FieldAccess { .. } => {
+                write!(f, "get")
+            }
+            SyntaxNode::FieldUpdate { .. } => {
+                write!(f, "with")
+            }
             SyntaxNode::Function { .. } => {
                 write!(f, "fn")
             }
             SyntaxNode::Identifier { name, .. } => {
                 write!(f, "{name}")
             }
-            SyntaxNode::Number { value } => {
+            SyntaxNode::Let { .. } => {
+                write!(f, "let")
+            }
+            SyntaxNode::List { .. } => {
+                write!(f, "list")
+            }
+            SyntaxNode::Match { .. } => {
+                write!(f, "match")
+            }
+            SyntaxNode::Float { value } => {
                 write!(f, "{value}")
             }
+            SyntaxNode::Integer64 { value } => {
+                write!(f, "{value}i64")
+            }
+            SyntaxNode::Number { value }
+            | SyntaxNode::NumberPattern { value } => {
+                write!(f, "{value}")
+            }
+            SyntaxNode::String { value } => write_string_literal(value, f),
+            SyntaxNode::Unsigned { value } => {
+                write!(f, "{value}u32")
+            }
+            SyntaxNode::Record { .. } | SyntaxNode::RecordPattern { .. } => {
+                write!(f, "record")
+            }
             SyntaxNode::Recursion => {
                 write!(f, "self")
             }
-            SyntaxNode::Tuple { .. } => {
+            SyntaxNode::Tuple { .. } | SyntaxNode::TuplePattern { .. } => {
                 write!(f, "tuple")
             }
+            SyntaxNode::Wildcard => {
+                write!(f, "_")
+            }
+        }
+    }
+}
+
+/// # Write a string literal, escaping the characters that require it
+///
+/// This is the inverse of how the compiler resolves string literals.
+fn write_string_literal(value: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for ch in value.chars() {
+        match ch {
+            ' ' => write!(f, "\\s")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            ch => write!(f, "{ch}")?,
         }
     }
+
+    write!(f, "\"")
 }
 
 pub struct NodeAsUniform;
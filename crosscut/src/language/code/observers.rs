@@ -0,0 +1,111 @@
+use std::{collections::BTreeMap, fmt};
+
+use super::{ChangeSet, NodeHash, Nodes};
+
+/// # An event that observers of a [`Codebase`] are notified of
+///
+/// Observers are notified whenever the root of the current syntax tree changes,
+/// which is the case for every change that actually changed something. This
+/// includes undoing and redoing changes, as well as switching branches.
+///
+/// Use [`Codebase::subscribe`] to register an observer.
+///
+/// [`Codebase`]: super::Codebase
+/// [`Codebase::subscribe`]: super::Codebase::subscribe
+#[derive(Debug)]
+pub struct CodebaseEvent<'r> {
+    /// # The root of the syntax tree before the change
+    ///
+    /// If the change cleared the codebase, this root is no longer available
+    /// in `nodes`.
+    pub root_before: NodeHash,
+
+    /// # The root of the syntax tree after the change
+    pub root_after: NodeHash,
+
+    /// # The change set that made the change, if any
+    ///
+    /// Switching branches and clearing the codebase swap out the whole syntax
+    /// tree, without replacing any individual nodes. There's no change set in
+    /// that case.
+    pub change_set: Option<&'r ChangeSet>,
+
+    /// # The nodes that both roots refer to
+    pub nodes: &'r Nodes,
+}
+
+/// # Identifies an observer that has been registered with a [`Codebase`]
+///
+/// [`Codebase`]: super::Codebase
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ObserverId {
+    value: u64,
+}
+
+/// # The observers that have been registered with a [`Codebase`]
+///
+/// ## Implementation Note
+///
+/// Observers are not part of the code, so they don't affect the comparison of
+/// two codebases. Neither are they carried over, if a codebase is cloned. An
+/// observer was registered with a specific codebase, and a clone is a
+/// different one.
+///
+/// [`Codebase`]: super::Codebase
+#[derive(Default)]
+pub(super) struct Observers {
+    observers: BTreeMap<ObserverId, Box<Observer>>,
+    next_id: u64,
+}
+
+type Observer = dyn FnMut(&CodebaseEvent);
+
+impl Observers {
+    pub fn insert(
+        &mut self,
+        observer: impl FnMut(&CodebaseEvent) + 'static,
+    ) -> ObserverId {
+        let id = ObserverId {
+            value: self.next_id,
+        };
+        self.next_id += 1;
+
+        self.observers.insert(id, Box::new(observer));
+
+        id
+    }
+
+    pub fn remove(&mut self, id: ObserverId) -> bool {
+        self.observers.remove(&id).is_some()
+    }
+
+    pub fn notify(&mut self, event: &CodebaseEvent) {
+        if event.root_before == event.root_after {
+            return;
+        }
+
+        for observer in self.observers.values_mut() {
+            observer(event);
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.observers.keys()).finish()
+    }
+}
+
+impl Eq for Observers {}
+
+impl PartialEq for Observers {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,516 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Codebase, Diagnostic, ExpectedNode, NodeHash, Nodes, SyntaxNode,
+    check_integrity, node_ids::StoredNodeIds,
+};
+
+/// # Stores a [`Codebase`] in a project directory
+///
+/// The store is content-addressed. Every node is written to its own file,
+/// named after its [`NodeHash`], into the `nodes` subdirectory. Since a node's
+/// hash depends on its contents, a node file never changes once written.
+///
+/// In addition to the nodes, a small `head.json` file records the current root
+/// of the syntax tree, as well as the version of the format. It also records
+/// the current branch, the roots of all branches, all tags, and the common
+/// ancestors of related branches. Last, it records the [`NodeId`]s of the
+/// nodes on each branch, so they stay the same across sessions.
+///
+/// ## Implementation Note
+///
+/// The history of changes is not stored. A loaded codebase starts out without
+/// any history.
+///
+/// [`NodeId`]: super::NodeId
+#[derive(Debug)]
+pub struct Store {
+    path: PathBuf,
+
+    /// # The nodes that are known to be stored already
+    ///
+    /// Since a node can only be stored after its children have been stored,
+    /// this means that the whole subtree of each of these nodes is stored.
+    stored: BTreeSet<NodeHash>,
+}
+
+impl Store {
+    /// # The version of the format that this store writes
+    ///
+    /// This must be incremented on every incompatible change to the format.
+    pub const VERSION: u32 = 1;
+
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            stored: BTreeSet::new(),
+        }
+    }
+
+    /// # Load the codebase from the store
+    ///
+    /// Returns `None`, if nothing has been stored yet. The loaded code is checked
+    /// for integrity, and rejected with a list of all problems, if it's
+    /// malformed. See [`check_integrity`].
+    pub fn load(&mut self) -> Result<Option<Codebase>, StoreError> {
+        let head = match File::open(self.head_path()) {
+            Ok(file) => {
+                serde_json::from_reader::<_, Head>(BufReader::new(file))?
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(err.into());
+            }
+        };
+
+        if head.version != Self::VERSION {
+            return Err(StoreError::UnsupportedVersion {
+                version: head.version,
+            });
+        }
+
+        let mut branches = head.branches;
+        branches.insert(head.branch.clone(), head.root);
+
+        let merge_bases = head
+            .merge_bases
+            .into_iter()
+            .map(
+                |MergeBase {
+                     branches: [a, b],
+                     root,
+                 }| ((a, b), root),
+            )
+            .collect::<BTreeMap<_, _>>();
+
+        let roots = branches
+            .values()
+            .chain(head.tags.values())
+            .chain(merge_bases.values())
+            .map(|root| (*root, ExpectedNode::Body))
+            .collect::<Vec<_>>();
+
+        let mut loaded = BTreeMap::new();
+        for (root, _) in &roots {
+            self.read_node(root, &mut loaded)?;
+        }
+
+        let diagnostics = check_integrity(roots, |hash| loaded.get(hash));
+        if !diagnostics.is_empty() {
+            return Err(StoreError::Integrity { diagnostics });
+        }
+
+        let mut nodes = Nodes::default();
+        for (hash, node) in loaded {
+            nodes.insert(node);
+            self.stored.insert(hash);
+        }
+
+        let mut codebase = Codebase::from_branches(
+            head.branch,
+            branches,
+            head.tags,
+            merge_bases,
+            nodes,
+        );
+        codebase.restore_ids(head.ids);
+
+        Ok(Some(codebase))
+    }
+
+    /// # Write the codebase to the store
+    ///
+    /// Only writes nodes that are not already stored. This makes it cheap
+    /// enough to call after every change.
+    pub fn save(&mut self, codebase: &Codebase) -> Result<(), StoreError> {
+        let root = *codebase.root().path.hash();
+
+        let branches = codebase
+            .branches()
+            .map(|(name, root)| (name.to_string(), root))
+            .collect::<BTreeMap<_, _>>();
+        let tags = codebase
+            .tags()
+            .map(|(name, root)| (name.to_string(), root))
+            .collect::<BTreeMap<_, _>>();
+        let merge_bases = codebase
+            .merge_bases()
+            .map(|(a, b, root)| MergeBase {
+                branches: [a, b].map(ToString::to_string),
+                root,
+            })
+            .collect::<Vec<_>>();
+
+        fs::create_dir_all(self.nodes_path())?;
+        for root in branches
+            .values()
+            .chain(tags.values())
+            .chain(merge_bases.iter().map(|merge_base| &merge_base.root))
+        {
+            self.save_node(root, codebase.nodes())?;
+        }
+
+        // Write the head to a temporary file first, then move it into place.
+        // That way, an interrupted write can't leave us with a corrupted head.
+        let head = Head {
+            version: Self::VERSION,
+            root,
+            branch: codebase.branch().to_string(),
+            branches,
+            tags,
+            merge_bases,
+            ids: codebase.stored_ids(),
+        };
+        let tmp = self.path.join("head.json.tmp");
+        write_json(&tmp, &head)?;
+        fs::rename(tmp, self.head_path())?;
+
+        Ok(())
+    }
+
+    fn read_node(
+        &self,
+        hash: &NodeHash,
+        loaded: &mut BTreeMap<NodeHash, SyntaxNode>,
+    ) -> Result<(), StoreError> {
+        if loaded.contains_key(hash) {
+            return Ok(());
+        }
+
+        let file = match File::open(self.node_path(hash)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                // Missing nodes are reported by the integrity check.
+                return Ok(());
+            }
+            Err(err) => {
+                return Err(err.into());
+            }
+        };
+        let node: SyntaxNode = serde_json::from_reader(BufReader::new(file))?;
+
+        let children = node
+            .children()
+            .hashes
+            .into_iter()
+            .copied()
+            .collect::<Vec<_>>();
+        loaded.insert(*hash, node);
+
+        for child in children {
+            self.read_node(&child, loaded)?;
+        }
+
+        Ok(())
+    }
+
+    fn save_node(
+        &mut self,
+        hash: &NodeHash,
+        nodes: &Nodes,
+    ) -> Result<(), StoreError> {
+        if self.stored.contains(hash) {
+            return Ok(());
+        }
+
+        let node = nodes.get(hash);
+
+        for child in node.children().hashes {
+            self.save_node(child, nodes)?;
+        }
+
+        let path = self.node_path(hash);
+        if !path.exists() {
+            write_json(&path, node)?;
+        }
+
+        self.stored.insert(*hash);
+
+        Ok(())
+    }
+
+    fn head_path(&self) -> PathBuf {
+        self.path.join("head.json")
+    }
+
+    fn nodes_path(&self) -> PathBuf {
+        self.path.join("nodes")
+    }
+
+    fn node_path(&self, hash: &NodeHash) -> PathBuf {
+        self.nodes_path().join(format!("{hash}.json"))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Io {
+        #[from]
+        err: io::Error,
+    },
+
+    #[error(transparent)]
+    Format {
+        #[from]
+        err: serde_json::Error,
+    },
+
+    #[error("Stored code has unsupported format version {version}")]
+    UnsupportedVersion { version: u32 },
+
+    #[error("Stored code is malformed: {diagnostics:#?}")]
+    Integrity { diagnostics: Vec<Diagnostic> },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Head {
+    version: u32,
+
+    /// # The root of the current branch
+    root: NodeHash,
+
+    // The following fields were added after the first version of the format.
+    // Defaults make sure that heads written before that can still be read.
+    #[serde(default = "default_branch")]
+    branch: String,
+    #[serde(default)]
+    branches: BTreeMap<String, NodeHash>,
+    #[serde(default)]
+    tags: BTreeMap<String, NodeHash>,
+    #[serde(default)]
+    merge_bases: Vec<MergeBase>,
+
+    /// # The IDs of the nodes on each branch, by name of the branch
+    ///
+    /// If these don't match the syntax tree of a branch, they are assigned
+    /// anew instead.
+    #[serde(default)]
+    ids: BTreeMap<String, StoredNodeIds>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct MergeBase {
+    branches: [String; 2],
+    root: NodeHash,
+}
+
+fn default_branch() -> String {
+    Codebase::DEFAULT_BRANCH.to_string()
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<(), StoreError> {
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, value)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::language::{
+        code::{Codebase, Diagnostic, SyntaxNode},
+        compiler::Compiler,
+    };
+
+    use super::{Store, StoreError};
+
+    #[test]
+    fn load_returns_nothing_if_nothing_was_stored() {
+        let path = test_dir("load_returns_nothing_if_nothing_was_stored");
+
+        let mut store = Store::new(path);
+        assert!(matches!(store.load(), Ok(None)));
+    }
+
+    #[test]
+    fn load_what_was_saved() {
+        // A codebase that was saved can be loaded again, resulting in the same
+        // syntax tree.
+
+        let path = test_dir("load_what_was_saved");
+
+        let mut codebase = Codebase::new();
+        {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            compiler.insert_child(root, "apply");
+        }
+
+        Store::new(&path).save(&codebase).unwrap();
+        let loaded = Store::new(&path).load().unwrap().unwrap();
+
+        assert_eq!(loaded.root().path, codebase.root().path);
+        assert_eq!(loaded.root().node, codebase.root().node);
+    }
+
+    #[test]
+    fn load_branches_and_tags() {
+        // Branches and tags are stored along with the code. As are the common
+        // ancestors of branches, which are needed to merge them.
+
+        let path = test_dir("load_branches_and_tags");
+
+        let mut codebase = Codebase::new();
+        codebase.tag("empty").unwrap();
+        codebase.create_branch("experiment").unwrap();
+        codebase.switch_branch("experiment").unwrap();
+        {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            compiler.insert_child(root, "apply");
+        }
+
+        Store::new(&path).save(&codebase).unwrap();
+        let loaded = Store::new(&path).load().unwrap().unwrap();
+
+        assert_eq!(loaded.branch(), "experiment");
+        assert_eq!(
+            loaded.branches().collect::<Vec<_>>(),
+            codebase.branches().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            loaded.tags().collect::<Vec<_>>(),
+            codebase.tags().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            loaded.merge_bases().collect::<Vec<_>>(),
+            codebase.merge_bases().collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn load_node_ids() {
+        // Nodes keep their IDs across sessions. That includes IDs that were
+        // assigned after the codebase was created, as well as the ID that is
+        // going to be assigned next.
+
+        let path = test_dir("load_node_ids");
+
+        let mut codebase = Codebase::new();
+        let (a, b) = {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            let a = compiler.insert_child(root, "a");
+            let root = compiler.codebase().root().path;
+            let b = compiler.insert_child(root, "b");
+            let b = compiler.replace(&b, "c");
+
+            (a, b)
+        };
+
+        Store::new(&path).save(&codebase).unwrap();
+        let mut loaded = Store::new(&path).load().unwrap().unwrap();
+
+        for path in [a, b] {
+            assert_eq!(loaded.id_of(&path), codebase.id_of(&path));
+        }
+
+        let mut compiler = Compiler::new(&mut codebase);
+        let root = compiler.codebase().root().path;
+        let d = compiler.insert_child(root, "d");
+
+        let mut compiler = Compiler::new(&mut loaded);
+        let root = compiler.codebase().root().path;
+        compiler.insert_child(root, "d");
+
+        assert_eq!(loaded.id_of(&d), codebase.id_of(&d));
+    }
+
+    #[test]
+    fn load_node_ids_of_all_branches() {
+        // Nodes keep their IDs across sessions, no matter which branch they
+        // are on.
+
+        let path = test_dir("load_node_ids_of_all_branches");
+
+        let mut codebase = Codebase::new();
+        let main = codebase.branch().to_string();
+        codebase.create_branch("feature").unwrap();
+        codebase.switch_branch("feature").unwrap();
+        let (a, b) = {
+            let mut compiler = Compiler::new(&mut codebase);
+            let root = compiler.codebase().root().path;
+            let a = compiler.insert_child(root, "a");
+            let root = compiler.codebase().root().path;
+            let b = compiler.insert_child(root, "b");
+            let b = compiler.replace(&b, "c");
+
+            (a, b)
+        };
+        codebase.switch_branch(&main).unwrap();
+
+        Store::new(&path).save(&codebase).unwrap();
+        let mut loaded = Store::new(&path).load().unwrap().unwrap();
+
+        codebase.switch_branch("feature").unwrap();
+        loaded.switch_branch("feature").unwrap();
+
+        for path in [a, b] {
+            assert_eq!(loaded.id_of(&path), codebase.id_of(&path));
+        }
+
+        let mut compiler = Compiler::new(&mut codebase);
+        let root = compiler.codebase().root().path;
+        let d = compiler.insert_child(root, "d");
+
+        let mut compiler = Compiler::new(&mut loaded);
+        let root = compiler.codebase().root().path;
+        compiler.insert_child(root, "d");
+
+        assert_eq!(loaded.id_of(&d), codebase.id_of(&d));
+    }
+
+    #[test]
+    fn reject_node_whose_hash_does_not_match() {
+        // The hash of a node is checked when loading it. If somebody messed
+        // with a node file, that should be detected.
+
+        let path = test_dir("reject_node_whose_hash_does_not_match");
+
+        let codebase = Codebase::new();
+        Store::new(&path).save(&codebase).unwrap();
+
+        let SyntaxNode::Body { add, .. } = codebase.root().node else {
+            unreachable!("Root of a new codebase is an empty body.");
+        };
+        fs::write(
+            path.join("nodes").join(format!("{add}.json")),
+            serde_json::to_string(&SyntaxNode::Empty).unwrap(),
+        )
+        .unwrap();
+
+        let Err(StoreError::Integrity { diagnostics }) =
+            Store::new(&path).load()
+        else {
+            panic!("Expected modified node to be detected.");
+        };
+        assert!(diagnostics.iter().any(|diagnostic| matches!(
+            diagnostic,
+            Diagnostic::HashMismatch { expected, .. } if expected == add
+        )));
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join("crosscut-tests")
+            .join(format!("{name}-{}", std::process::id()));
+
+        // Ignoring the result, as the directory probably doesn't exist.
+        let _ = fs::remove_dir_all(&path);
+
+        path
+    }
+}
@@ -2,23 +2,73 @@ use std::fmt::{self};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Type {
+    Boolean,
+    Float,
     Function,
     Integer,
-    Tuple { values: Vec<Type> },
+    Integer64,
+    List,
+
+    /// # Any of the provided types
+    ///
+    /// No value has this type. It describes the expected input of functions
+    /// that accept values of several types.
+    OneOf {
+        types: Vec<Type>,
+    },
+
+    Record {
+        fields: Vec<(String, Type)>,
+    },
+    String,
+    Tuple {
+        values: Vec<Type>,
+    },
+    Unsigned,
 }
 
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Self::Boolean => {
+                write!(f, "Boolean")?;
+            }
+            Self::Float => {
+                write!(f, "Float")?;
+            }
             Self::Function => {
                 write!(f, "Function")?;
             }
             Self::Integer => {
                 write!(f, "Integer")?;
             }
+            Self::Integer64 => {
+                write!(f, "Integer64")?;
+            }
+            Self::List => {
+                write!(f, "List")?;
+            }
+            Self::OneOf { types } => {
+                for (i, type_) in types.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " or ")?;
+                    }
+
+                    write!(f, "{type_}")?;
+                }
+            }
+            Self::Record { fields } => {
+                display_record(fields, f)?;
+            }
+            Self::String => {
+                write!(f, "String")?;
+            }
             Self::Tuple { values } => {
                 display_tuple(values, f)?;
             }
+            Self::Unsigned => {
+                write!(f, "Unsigned")?;
+            }
         }
 
         Ok(())
@@ -48,3 +98,30 @@ where
 
     Ok(())
 }
+
+pub fn display_record<V>(
+    fields: &[(String, V)],
+    f: &mut fmt::Formatter,
+) -> fmt::Result
+where
+    V: fmt::Display,
+{
+    if fields.is_empty() {
+        write!(f, "{{}}")?;
+        return Ok(());
+    }
+
+    write!(f, "{{ ")?;
+
+    for (i, (name, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{name}: {value}")?;
+    }
+
+    write!(f, " }}")?;
+
+    Ok(())
+}
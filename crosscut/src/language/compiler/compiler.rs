@@ -1,6 +1,10 @@
-use crate::language::code::{Body, Codebase, NodePath, SyntaxNode, TypedNode};
+use crate::language::code::{
+    Body, Bundle, BundleError, ChildIndex, Codebase, ConflictSide,
+    ExpectedNode, NewChangeSet, NodeHash, NodePath, Nodes, SyntaxNode,
+    TypedNode,
+};
 
-use super::{expression, replace::replace_node_and_update_parents};
+use super::{expression, pattern, replace::replace_node_and_update_parents};
 
 pub struct Compiler<'r> {
     codebase: &'r mut Codebase,
@@ -21,42 +25,49 @@ impl<'r> Compiler<'r> {
         child_token: &str,
     ) -> NodePath {
         self.codebase.make_change(|change_set| {
-            // Compiling the child as an expression is correct for all the
-            // parents that we check for above.
-            let child = expression::compile(child_token, change_set.nodes);
+            let expected = ExpectedNode::in_body_of(parent.parent().map(
+                |(body_parent, _)| change_set.nodes.get(body_parent.hash()),
+            ));
 
-            let (parent_path, index) = {
-                let node = change_set.nodes.get(parent.hash()).clone();
+            match expected {
+                ExpectedNode::Arm => {
+                    // An arm has no token of its own that could be edited. So
+                    // we take the token as the pattern of the new arm, and
+                    // that's where we return the path to.
+                    let pattern =
+                        pattern::compile(child_token, change_set.nodes);
+                    let body = {
+                        let node =
+                            Body::empty().into_syntax_node(change_set.nodes);
+                        change_set.nodes.insert(node)
+                    };
+                    let arm = change_set
+                        .nodes
+                        .insert(SyntaxNode::Arm { pattern, body });
 
-                let (node, index) = match &node {
-                    SyntaxNode::Body { children, add } => {
-                        let mut expressions = Body {
-                            children: children.clone(),
-                            add: *add,
-                        };
-
-                        let index = expressions.children_mut().add(child);
-                        let node = expressions.into_syntax_node();
-
-                        (node, index)
-                    }
-                    node => {
-                        panic!(
-                            "Can't add child to this node:\n\
-                            {node:#?}"
-                        );
-                    }
-                };
-
-                let hash = change_set.nodes.insert(node);
-
-                let path =
-                    replace_node_and_update_parents(parent, hash, change_set);
-
-                (path, index)
-            };
-
-            NodePath::new(child, Some((parent_path, index)), change_set.nodes)
+                    let arm = insert_child_node(parent, arm, change_set);
+                    NodePath::new(
+                        pattern,
+                        Some((arm, ChildIndex { index: 0 })),
+                        change_set.nodes,
+                    )
+                }
+                ExpectedNode::Binding => {
+                    let child = change_set.nodes.insert(SyntaxNode::Binding {
+                        name: child_token.to_string(),
+                    });
+                    insert_child_node(parent, child, change_set)
+                }
+                ExpectedNode::Pattern => {
+                    let child = pattern::compile(child_token, change_set.nodes);
+                    insert_child_node(parent, child, change_set)
+                }
+                _ => {
+                    let child =
+                        expression::compile(child_token, change_set.nodes);
+                    insert_child_node(parent, child, change_set)
+                }
+            }
         })
     }
 
@@ -76,9 +87,13 @@ impl<'r> Compiler<'r> {
                     expression::compile(replacement_token, change_set.nodes)
                 }
                 TypedNode::Pattern => {
-                    change_set.nodes.insert(SyntaxNode::Binding {
-                        name: replacement_token.to_string(),
-                    })
+                    if accepts_any_pattern(to_replace, change_set.nodes) {
+                        pattern::compile(replacement_token, change_set.nodes)
+                    } else {
+                        change_set.nodes.insert(SyntaxNode::Binding {
+                            name: replacement_token.to_string(),
+                        })
+                    }
                 }
                 TypedNode::Other => {
                     panic!(
@@ -95,4 +110,150 @@ impl<'r> Compiler<'r> {
             )
         })
     }
+
+    /// # Import a bundle into the code
+    ///
+    /// If `at` points to a node for adding children, the bundled node is added
+    /// as a child there. Otherwise, it replaces the node at `at`, which must be
+    /// an expression.
+    ///
+    /// Returns the path of the imported node.
+    pub fn import_bundle(
+        &mut self,
+        bundle: &Bundle,
+        at: &NodePath,
+    ) -> Result<NodePath, BundleError> {
+        self.codebase.make_change(|change_set| {
+            let root = bundle.import_into(change_set.nodes)?;
+
+            if let SyntaxNode::Add = change_set.nodes.get(at.hash()) {
+                let Some((parent, _)) = at.parent() else {
+                    unreachable!(
+                        "A node for adding children always has a parent."
+                    );
+                };
+
+                Ok(insert_child_node(parent.clone(), root, change_set))
+            } else {
+                Ok(replace_node_and_update_parents(
+                    at.clone(),
+                    root,
+                    change_set,
+                ))
+            }
+        })
+    }
+
+    /// # Resolve a conflict by picking one of its versions
+    ///
+    /// If the picked version is a run of children within a body, those
+    /// children take the place of the conflict within its parent body.
+    /// Otherwise, the picked version replaces the conflict.
+    ///
+    /// Returns the path of the node that took the place of the conflict, or of
+    /// the parent body in the former case.
+    pub fn resolve_conflict(
+        &mut self,
+        conflict: &NodePath,
+        side: ConflictSide,
+    ) -> NodePath {
+        self.codebase.make_change(|change_set| {
+            let SyntaxNode::Conflict { ours, theirs } =
+                change_set.nodes.get(conflict.hash()).clone()
+            else {
+                panic!("Expected conflict at `{conflict:?}`.");
+            };
+
+            let picked = match side {
+                ConflictSide::Ours => ours,
+                ConflictSide::Theirs => theirs,
+            };
+
+            if let SyntaxNode::Body {
+                children: picked_children,
+                ..
+            } = change_set.nodes.get(&picked).clone()
+                && let Some((parent, index)) = conflict.parent()
+                && let SyntaxNode::Body { mut children, add } =
+                    change_set.nodes.get(parent.hash()).clone()
+            {
+                children.splice(index.index..=index.index, picked_children);
+                let parent_replacement =
+                    change_set.nodes.insert(SyntaxNode::Body { children, add });
+
+                return replace_node_and_update_parents(
+                    parent.clone(),
+                    parent_replacement,
+                    change_set,
+                );
+            }
+
+            replace_node_and_update_parents(
+                conflict.clone(),
+                picked,
+                change_set,
+            )
+        })
+    }
+}
+
+/// # Indicate whether any pattern can take the place of the node at `path`
+///
+/// Otherwise, only a binding can.
+fn accepts_any_pattern(path: &NodePath, nodes: &Nodes) -> bool {
+    let Some((parent, _)) = path.parent() else {
+        return false;
+    };
+
+    match nodes.get(parent.hash()) {
+        SyntaxNode::Arm { .. } => true,
+        SyntaxNode::Body { .. } => {
+            let expected = ExpectedNode::in_body_of(
+                parent
+                    .parent()
+                    .map(|(body_parent, _)| nodes.get(body_parent.hash())),
+            );
+
+            expected == ExpectedNode::Pattern
+        }
+        _ => false,
+    }
+}
+
+fn insert_child_node(
+    parent: NodePath,
+    child: NodeHash,
+    change_set: &mut NewChangeSet,
+) -> NodePath {
+    let (parent_path, index) = {
+        let node = change_set.nodes.get(parent.hash()).clone();
+
+        let (node, index) = match &node {
+            SyntaxNode::Body { children, add } => {
+                let mut expressions = Body {
+                    children: children.clone(),
+                    add: *add,
+                };
+
+                let index = expressions.children_mut().add(child);
+                let node = expressions.into_syntax_node();
+
+                (node, index)
+            }
+            node => {
+                panic!(
+                    "Can't add child to this node:\n\
+                    {node:#?}"
+                );
+            }
+        };
+
+        let hash = change_set.nodes.insert(node);
+
+        let path = replace_node_and_update_parents(parent, hash, change_set);
+
+        (path, index)
+    };
+
+    NodePath::new(child, Some((parent_path, index)), change_set.nodes)
 }
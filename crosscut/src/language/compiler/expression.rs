@@ -1,7 +1,10 @@
 use crate::language::code::{
-    Apply, Function, NodeHash, Nodes, SyntaxNode, Tuple,
+    Apply, Definition, FieldAccess, FieldUpdate, Function, Let, List, Match,
+    NodeHash, Nodes, Record, SyntaxNode, Tuple,
 };
 
+use super::{number, string};
+
 pub fn compile(token: &str, nodes: &mut Nodes) -> NodeHash {
     let node = if token.is_empty() {
         SyntaxNode::Empty
@@ -18,20 +21,42 @@ pub fn compile(token: &str, nodes: &mut Nodes) -> NodeHash {
     nodes.insert(node)
 }
 
+/// # Indicate whether compiling the token results in an identifier
+///
+/// This is the case, if the token is neither empty, nor a keyword or literal.
+pub fn is_identifier(token: &str) -> bool {
+    let mut nodes = Nodes::default();
+
+    !token.is_empty()
+        && resolve_keyword(token, &mut nodes).is_none()
+        && resolve_literal(token, &mut nodes).is_none()
+}
+
 fn resolve_keyword(name: &str, nodes: &mut Nodes) -> Option<SyntaxNode> {
     match name {
         "apply" => Some(Apply::default().into_syntax_node(nodes)),
+        "def" => Some(Definition::empty().into_syntax_node(nodes)),
+        "get" => Some(FieldAccess::default().into_syntax_node(nodes)),
+        "let" => Some(Let::empty().into_syntax_node(nodes)),
+        "match" => Some(Match::default().into_syntax_node(nodes)),
         "self" => Some(SyntaxNode::Recursion),
+        "with" => Some(FieldUpdate::default().into_syntax_node(nodes)),
         _ => None,
     }
 }
 
 fn resolve_literal(name: &str, nodes: &mut Nodes) -> Option<SyntaxNode> {
-    if let Ok(value) = name.parse() {
-        Some(SyntaxNode::Number { value })
+    if let Some(node) = number::resolve(name) {
+        Some(node)
+    } else if let Some(node) = string::resolve(name) {
+        Some(node)
     } else {
         match name {
+            "false" => Some(SyntaxNode::Boolean { value: false }),
             "fn" => Some(Function::empty(nodes).into_syntax_node(nodes)),
+            "list" => Some(List::empty().into_syntax_node(nodes)),
+            "record" => Some(Record::empty().into_syntax_node(nodes)),
+            "true" => Some(SyntaxNode::Boolean { value: true }),
             "tuple" => Some(Tuple::empty().into_syntax_node(nodes)),
             _ => None,
         }
@@ -1,8 +1,15 @@
 mod compiler;
 mod expression;
+mod number;
 mod replace;
+mod string;
 
-pub use self::compiler::Compiler;
+pub mod pattern;
+
+pub use self::{
+    compiler::Compiler,
+    expression::{compile, is_identifier},
+};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,90 @@
+//! # Compile tokens into number literals
+
+use crate::language::code::{Float, SyntaxNode};
+
+/// # Resolve a token into a number literal, if it is one
+///
+/// Number literals start with a digit, optionally preceded by a minus sign.
+/// Beyond that, the following formats are supported:
+///
+/// - Decimal integers, like `127`.
+/// - Hexadecimal, binary, and octal integers, like `0x7f`, `0b101`, or `0o17`.
+/// - Floating-point numbers, which have a decimal point or an exponent, like
+///   `0.5` or `1e-3`.
+/// - Underscores between digits, which are ignored, like in `1_000_000`.
+/// - A suffix that selects the type of the number: `i32`, `i64`, `u32`, or
+///   `f64`. Without a suffix, integers are signed and 32 bits wide.
+///
+/// Tokens that look like number literals, but whose value doesn't fit into
+/// the selected type, are not number literals.
+pub fn resolve(token: &str) -> Option<SyntaxNode> {
+    let (sign, unsigned) = match token.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", token),
+    };
+
+    // Without this restriction, tokens like `_` or `inf` would be parsed as
+    // numbers.
+    if !unsigned.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    let unsigned = unsigned.replace('_', "");
+
+    let (radix, digits) = [("0x", 16), ("0b", 2), ("0o", 8)]
+        .into_iter()
+        .find_map(|(prefix, radix)| {
+            unsigned.strip_prefix(prefix).map(|digits| (radix, digits))
+        })
+        .unwrap_or((10, &unsigned));
+
+    // `f` is a hexadecimal digit, so the `f64` suffix is only available for
+    // decimal numbers.
+    let suffixes = if radix == 10 {
+        ["i32", "i64", "u32", "f64"].as_slice()
+    } else {
+        ["i32", "i64", "u32"].as_slice()
+    };
+    let (digits, suffix) = suffixes
+        .iter()
+        .find_map(|suffix| {
+            digits
+                .strip_suffix(suffix)
+                .map(|digits| (digits, Some(*suffix)))
+        })
+        .unwrap_or((digits, None));
+
+    // This rules out signs after the prefix, which the parsing functions below
+    // would accept otherwise.
+    if !digits.starts_with(|ch: char| ch.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    let text = format!("{sign}{digits}");
+
+    let is_float = radix == 10 && digits.contains(['.', 'e', 'E']);
+
+    match suffix {
+        Some("f64") => resolve_float(&text),
+        None if is_float => resolve_float(&text),
+        _ if is_float => None,
+        Some("i64") => i64::from_str_radix(&text, radix)
+            .ok()
+            .map(|value| SyntaxNode::Integer64 { value }),
+        Some("u32") => u32::from_str_radix(&text, radix)
+            .ok()
+            .map(|value| SyntaxNode::Unsigned { value }),
+        _ => i32::from_str_radix(&text, radix)
+            .ok()
+            .map(|value| SyntaxNode::Number { value }),
+    }
+}
+
+fn resolve_float(text: &str) -> Option<SyntaxNode> {
+    let value = text.parse::<f64>().ok()?;
+
+    // A literal that is too large to be represented, is not a valid literal.
+    value.is_finite().then(|| SyntaxNode::Float {
+        value: Float::new(value),
+    })
+}
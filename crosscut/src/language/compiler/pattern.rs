@@ -0,0 +1,46 @@
+//! # Compile tokens into patterns
+
+use crate::language::code::{Body, NodeHash, Nodes, SyntaxNode};
+
+use super::number;
+
+/// # Compile a token into a pattern
+///
+/// This is used where any pattern is possible, like in the arm of a match. In
+/// other places, like the parameter of a function, only bindings are.
+pub fn compile(token: &str, nodes: &mut Nodes) -> NodeHash {
+    let node = if token == "_" {
+        SyntaxNode::Wildcard
+    } else if let Some(SyntaxNode::Number { value }) = number::resolve(token) {
+        SyntaxNode::NumberPattern { value }
+    } else {
+        match token {
+            "false" => SyntaxNode::BooleanPattern { value: false },
+            "record" => SyntaxNode::RecordPattern {
+                fields: empty_body(nodes),
+            },
+            "true" => SyntaxNode::BooleanPattern { value: true },
+            "tuple" => SyntaxNode::TuplePattern {
+                values: empty_body(nodes),
+            },
+            _ => SyntaxNode::Binding {
+                name: token.to_string(),
+            },
+        }
+    };
+
+    nodes.insert(node)
+}
+
+/// # Indicate whether compiling the token as a pattern results in a binding
+pub fn is_binding(token: &str) -> bool {
+    let mut nodes = Nodes::default();
+    let hash = compile(token, &mut nodes);
+
+    matches!(nodes.get(&hash), SyntaxNode::Binding { .. })
+}
+
+fn empty_body(nodes: &mut Nodes) -> NodeHash {
+    let node = Body::empty().into_syntax_node(nodes);
+    nodes.insert(node)
+}
@@ -0,0 +1,52 @@
+//! # Compile tokens into string literals
+
+use crate::language::code::SyntaxNode;
+
+/// # Resolve a token into a string literal, if it is one
+///
+/// String literals are enclosed in double quotes. Within them, a backslash
+/// starts one of the following escape sequences:
+///
+/// - `\s` for a space
+/// - `\n`, `\r`, and `\t` for a line feed, carriage return, and tab
+/// - `\"` and `\\` for a double quote and a backslash
+///
+/// Tokens that start and end with a double quote, but contain an unknown
+/// escape sequence or an unescaped double quote, are not string literals.
+///
+/// ## Implementation Note
+///
+/// Tokens can't contain whitespace, as the editor uses it to submit them, and
+/// the text format to separate them. This is why spaces need to be escaped.
+/// It's not very convenient, but keeps string literals in line with every
+/// other kind of token.
+pub fn resolve(token: &str) -> Option<SyntaxNode> {
+    let contents = token.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut value = String::new();
+    let mut chars = contents.chars();
+
+    while let Some(ch) = chars.next() {
+        let ch = match ch {
+            '\\' => match chars.next()? {
+                's' => ' ',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '"' => '"',
+                '\\' => '\\',
+                _ => {
+                    return None;
+                }
+            },
+            '"' => {
+                return None;
+            }
+            ch => ch,
+        };
+
+        value.push(ch);
+    }
+
+    Some(SyntaxNode::String { value })
+}
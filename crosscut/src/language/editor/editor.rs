@@ -1,7 +1,10 @@
 use std::{fmt, fs::File};
 
 use crate::language::{
-    code::{Codebase, LocatedNode, NodePath, SyntaxNode},
+    code::{
+        Bundle, Codebase, ConflictSide, LocatedNode, NodePath, SyntaxNode,
+        TypedNode, VersionError,
+    },
     compiler::Compiler,
     runtime::Evaluator,
 };
@@ -14,6 +17,9 @@ use super::{
 pub struct Editor {
     input: EditorInputBuffer,
     cursor: Cursor,
+
+    /// # The tag that the current code is compared to, if any
+    diff_base: Option<String>,
 }
 
 impl Editor {
@@ -38,6 +44,7 @@ impl Editor {
         let mut editor = Self {
             input: EditorInputBuffer::empty(),
             cursor: cursor.clone(),
+            diff_base: None,
         };
 
         editor.navigate_to(cursor, codebase);
@@ -49,12 +56,16 @@ impl Editor {
         &self.cursor
     }
 
+    /// # The tag that the current code should be compared to, if any
+    pub fn diff_base(&self) -> Option<&str> {
+        self.diff_base.as_deref()
+    }
+
     pub fn find(&mut self, code: &str, codebase: &Codebase) {
         let path = find_from_node(codebase.root(), code, codebase);
 
         if let Some(path) = path {
-            self.cursor.path = path;
-            self.cursor.index = 0;
+            self.navigate_to(path, codebase);
         }
 
         fn find_from_node(
@@ -84,6 +95,18 @@ impl Editor {
         codebase: &mut Codebase,
         evaluator: &mut Evaluator,
     ) {
+        match input {
+            EditorInput::Undo => {
+                self.undo(codebase, evaluator);
+                return;
+            }
+            EditorInput::Redo => {
+                self.redo(codebase, evaluator);
+                return;
+            }
+            _ => {}
+        }
+
         let layout = EditorLayout::new(codebase.root(), codebase);
         let mut compiler = Compiler::new(codebase);
 
@@ -142,6 +165,9 @@ impl Editor {
                 self.cursor.path = compiler
                     .insert_child(parent.clone(), self.input.contents());
             }
+        } else if let SyntaxNode::Arm { .. } = current_node.node {
+            // An arm only groups its pattern and its body. It has no token of
+            // its own that could be replaced.
         } else if compiler
             .codebase()
             .nodes()
@@ -169,11 +195,53 @@ impl Editor {
         evaluator: &mut Evaluator,
     ) -> anyhow::Result<()> {
         match command {
+            EditorCommand::Branch { name } => {
+                // The new branch starts out with the current code, so there's
+                // no need to update the cursor or the evaluator.
+                let result = codebase
+                    .create_branch(&name)
+                    .and_then(|()| codebase.switch_branch(&name));
+                self.on_version_command(result, codebase)?;
+            }
+            EditorCommand::Checkout { tag } => {
+                let result = codebase.checkout(&tag);
+                if self.on_version_command(result, codebase)? {
+                    evaluator.update(codebase);
+                }
+            }
+            EditorCommand::Conflict => {
+                let layout = EditorLayout::new(codebase.root(), codebase);
+                let line_of = |path: &NodePath| {
+                    layout.lines.iter().position(|line| &line.node.path == path)
+                };
+
+                let cursor = line_of(&self.cursor.path);
+                let conflicts = codebase.conflicts();
+
+                if let Some(conflict) = conflicts
+                    .iter()
+                    .find(|conflict| line_of(conflict) > cursor)
+                    .or(conflicts.first())
+                {
+                    self.navigate_to(conflict.clone(), codebase);
+                }
+            }
             EditorCommand::Clear => {
-                *codebase = Codebase::new();
+                codebase.clear();
                 *self = Self::new(codebase.root().path, codebase);
                 evaluator.reset(codebase);
             }
+            EditorCommand::Diff { tag } => {
+                if let Some(tag) = &tag
+                    && codebase.tags().all(|(name, _)| name != tag)
+                {
+                    return Err(
+                        VersionError::UnknownTag { name: tag.clone() }.into()
+                    );
+                }
+
+                self.diff_base = tag;
+            }
             EditorCommand::Dump => {
                 for (name, data) in [
                     ("codebase", codebase as &dyn fmt::Debug),
@@ -187,14 +255,180 @@ impl Editor {
                     write!(file, "{data:#?}")?;
                 }
             }
+            EditorCommand::Export { path } => {
+                let located_node = codebase.node_at(&self.cursor.path);
+                let TypedNode::Expression { .. } = TypedNode::from_syntax_node(
+                    located_node.node.clone(),
+                    codebase.nodes(),
+                ) else {
+                    // Only expressions can be imported again.
+                    return Err(CommandError::NothingToExport.into());
+                };
+
+                Bundle::new(located_node.path.hash(), codebase.nodes())
+                    .write(path)?;
+            }
+            EditorCommand::Import { path } => {
+                let node = codebase.node_at(&self.cursor.path).node;
+                let can_import_here = match node {
+                    SyntaxNode::Add => true,
+                    node => matches!(
+                        TypedNode::from_syntax_node(
+                            node.clone(),
+                            codebase.nodes()
+                        ),
+                        TypedNode::Expression { .. },
+                    ),
+                };
+                if !can_import_here {
+                    return Err(CommandError::CantImportHere.into());
+                }
+
+                let bundle = Bundle::read(path)?;
+                let path = Compiler::new(codebase)
+                    .import_bundle(&bundle, &self.cursor.path)?;
+
+                self.navigate_to(path, codebase);
+                evaluator.update(codebase);
+            }
+            EditorCommand::Merge { branch } => {
+                let result = codebase.merge_branch(&branch);
+                if self.on_version_command(result, codebase)? {
+                    if let Some(conflict) = codebase.conflicts().first() {
+                        self.navigate_to(conflict.clone(), codebase);
+                    }
+
+                    evaluator.update(codebase);
+                }
+            }
+            EditorCommand::Pick { side } => {
+                let mut conflict = Some(&self.cursor.path);
+
+                while let Some(path) = conflict {
+                    if let SyntaxNode::Conflict { .. } =
+                        codebase.node_at(path).node
+                    {
+                        break;
+                    }
+
+                    conflict = path.parent().map(|(parent, _)| parent);
+                }
+
+                let Some(conflict) = conflict.cloned() else {
+                    // The cursor is not within a conflict, so there's nothing
+                    // to pick from.
+                    return Ok(());
+                };
+
+                self.cursor.path =
+                    Compiler::new(codebase).resolve_conflict(&conflict, side);
+                self.on_history_change(codebase, evaluator);
+            }
+            EditorCommand::Redo => {
+                self.redo(codebase, evaluator);
+            }
             EditorCommand::Reset => {
                 evaluator.reset(codebase);
             }
+            EditorCommand::Switch { branch } => {
+                let result = codebase.switch_branch(&branch);
+                if self.on_version_command(result, codebase)? {
+                    // Like after checking out a tag, the running program
+                    // continues with the code of the branch.
+                    evaluator.update(codebase);
+                }
+            }
+            EditorCommand::Tag { name } => {
+                let result = codebase.tag(&name);
+                self.on_version_command(result, codebase)?;
+            }
+            EditorCommand::Undo => {
+                self.undo(codebase, evaluator);
+            }
         }
 
         Ok(())
     }
 
+    pub fn undo(&mut self, codebase: &mut Codebase, evaluator: &mut Evaluator) {
+        if codebase.undo() {
+            self.on_history_change(codebase, evaluator);
+        }
+    }
+
+    pub fn redo(&mut self, codebase: &mut Codebase, evaluator: &mut Evaluator) {
+        if codebase.redo() {
+            self.on_history_change(codebase, evaluator);
+        }
+    }
+
+    /// # Handle the result of a command that concerns versions of the code
+    ///
+    /// Returns `true`, if the command changed the current code. Returns an
+    /// error, if the command referred to a branch or tag that doesn't exist,
+    /// or tried to create one that does.
+    fn on_version_command(
+        &mut self,
+        result: Result<(), VersionError>,
+        codebase: &Codebase,
+    ) -> Result<bool, VersionError> {
+        result?;
+
+        if self.cursor.path.root_hash() == codebase.root().path.hash() {
+            return Ok(false);
+        }
+
+        self.move_cursor_into_current_code(codebase);
+
+        Ok(true)
+    }
+
+    fn on_history_change(
+        &mut self,
+        codebase: &Codebase,
+        evaluator: &mut Evaluator,
+    ) {
+        self.move_cursor_into_current_code(codebase);
+        evaluator.update(codebase);
+    }
+
+    fn move_cursor_into_current_code(&mut self, codebase: &Codebase) {
+        let root = codebase.root().path;
+
+        // The node that the cursor is on might not exist in the current
+        // version of the code. If it was added by the change that got undone,
+        // for example. In that case, the closest ancestor that still exists is
+        // the best place to put the cursor.
+        let mut candidate = Some(self.cursor.path.clone());
+        let mut cursor = None;
+
+        while let Some(path) = candidate {
+            let latest = codebase.latest_version_of(&path);
+
+            if latest == &root || root.is_ancestor_of(latest) {
+                cursor = Some(latest.clone());
+                break;
+            }
+
+            candidate = path.parent().map(|(parent, _)| parent.clone());
+        }
+
+        let mut cursor = cursor.unwrap_or(root);
+
+        // Bodies are not shown in the editor. The node for adding children is,
+        // and if the cursor ended up on the body, it's the most sensible place
+        // to put it.
+        let located_node = codebase.node_at(&cursor);
+        if let SyntaxNode::Body { .. } = located_node.node
+            && let Some(add) = located_node.children(codebase.nodes()).last()
+        {
+            cursor = add.path;
+        }
+
+        self.navigate_to(cursor, codebase);
+        self.input.move_cursor_to_end(&mut self.cursor.index);
+    }
+
     fn navigate_to(&mut self, cursor: impl Into<Cursor>, codebase: &Codebase) {
         let cursor = cursor.into();
 
@@ -240,7 +474,77 @@ impl From<NodePath> for Cursor {
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum EditorCommand {
+    /// # Create a branch at the current version of the code and switch to it
+    Branch {
+        name: String,
+    },
+
+    /// # Change the code on the current branch to a tagged version
+    Checkout {
+        tag: String,
+    },
+
     Clear,
+
+    /// # Move the cursor to the next conflict
+    Conflict,
+
+    /// # Compare the current code to a tagged version, or stop comparing
+    Diff {
+        tag: Option<String>,
+    },
+
     Dump,
+
+    /// # Write the code at the cursor to a bundle file
+    ///
+    /// The cursor must be on an expression.
+    Export {
+        path: String,
+    },
+
+    /// # Read code from a bundle file and insert it at the cursor
+    ///
+    /// The cursor must be on an expression, which the imported code replaces,
+    /// or on a node for adding children.
+    Import {
+        path: String,
+    },
+
+    /// # Merge another branch into the current one
+    Merge {
+        branch: String,
+    },
+
+    /// # Resolve the conflict at the cursor by picking one of its versions
+    Pick {
+        side: ConflictSide,
+    },
+
+    Redo,
     Reset,
+
+    /// # Switch to another branch
+    Switch {
+        branch: String,
+    },
+
+    /// # Give the current version of the code a name
+    Tag {
+        name: String,
+    },
+
+    Undo,
+}
+
+/// # An editor command that can't be executed where the cursor is
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum CommandError {
+    #[error("There is no expression at the cursor to export.")]
+    NothingToExport,
+
+    #[error(
+        "Code can only be imported at an expression, or where one is added."
+    )]
+    CantImportHere,
 }
@@ -8,4 +8,6 @@ pub enum EditorInput {
     RemoveLeft { whole_node: bool },
     RemoveRight { whole_node: bool },
     Submit,
+    Undo,
+    Redo,
 }
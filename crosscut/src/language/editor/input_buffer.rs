@@ -64,12 +64,19 @@ impl EditorInputBuffer {
             EditorInput::Submit => {
                 return Some(NodeAction::Submit);
             }
+            EditorInput::Undo | EditorInput::Redo => {
+                // These don't affect the contents of the buffer. If they
+                // concern the code, the editor handles them.
+            }
         }
 
         None
     }
 
-    fn insert(&mut self, ch: char, cursor: &mut usize) {
+    /// # Insert a character at the cursor
+    ///
+    /// Unlike [`EditorInputBuffer::update`], this accepts whitespace.
+    pub fn insert(&mut self, ch: char, cursor: &mut usize) {
         self.buffer.insert(*cursor, ch);
         self.move_cursor_right(cursor);
     }
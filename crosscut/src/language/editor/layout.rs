@@ -15,9 +15,17 @@ impl EditorLayout {
             .map(|node| {
                 let level_of_indentation = node.distance_from_root;
 
+                // Each top-level definition is shown as its own section.
+                let starts_section = level_of_indentation == 0
+                    && matches!(
+                        codebase.node_at(&node.path).node,
+                        SyntaxNode::Definition { .. }
+                    );
+
                 EditorLine {
                     node,
                     level_of_indentation,
+                    starts_section,
                 }
             })
             .collect();
@@ -54,6 +62,11 @@ impl EditorLayout {
 pub struct EditorLine {
     pub node: NodeInLayout,
     pub level_of_indentation: u32,
+
+    /// # Indicate whether the line starts a new section
+    ///
+    /// Sections are visually separated from whatever comes before them.
+    pub starts_section: bool,
 }
 
 impl EditorLine {
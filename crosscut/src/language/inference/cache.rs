@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::language::code::{ChildIndex, Codebase, NodeHash, Nodes};
+
+use super::{InferredType, TypeError};
+
+/// # The results of inferring subtrees, for reuse after the code has changed
+///
+/// Syntax nodes are content-addressed, so two subtrees with the same hash have
+/// the same content. But the types within a subtree also depend on the names
+/// it refers to, without binding them itself. A result is reused, if the
+/// types of those names are the same too.
+#[derive(Debug, Default)]
+pub struct Cache {
+    /// # The names that each subtree refers to, without binding them itself
+    ///
+    /// These only depend on the content of the subtree.
+    pub free_names: BTreeMap<NodeHash, FreeNames>,
+
+    pub results: BTreeMap<CacheKey, CachedResult>,
+}
+
+impl Cache {
+    /// # Remove all results for subtrees that are not part of the code
+    ///
+    /// Without this, the cache would keep growing with every edit.
+    pub fn retain(&mut self, codebase: &Codebase) {
+        let mut hashes = BTreeSet::new();
+        collect_hashes(
+            codebase.root().path.hash(),
+            codebase.nodes(),
+            &mut hashes,
+        );
+
+        self.free_names.retain(|hash, _| hashes.contains(hash));
+        self.results.retain(|key, _| hashes.contains(&key.hash));
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FreeNames {
+    pub names: BTreeSet<String>,
+
+    /// # Indicate whether the subtree refers to an enclosing function
+    pub recursion: bool,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct CacheKey {
+    pub hash: NodeHash,
+
+    /// # What the free names of the subtree refer to
+    ///
+    /// Has one entry for each of the free names, in order. If the subtree
+    /// refers to an enclosing function, that comes last, with an empty name.
+    ///
+    /// The variables in these types are numbered in the order they appear in,
+    /// across all entries. The same numbers are used in [`CachedResult`].
+    pub environment: Vec<(String, Outside)>,
+}
+
+/// # What a name refers to, from the perspective of a subtree
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Outside {
+    /// # A local name that is bound outside of the subtree
+    Local { type_: InferredType },
+
+    /// # A top-level definition, with its generalized type
+    Definition { type_: InferredType },
+
+    /// # A provided function
+    Provided,
+
+    /// # The function that encloses the subtree, if any
+    Recursion { type_: Option<InferredType> },
+}
+
+#[derive(Clone, Debug)]
+pub struct CachedResult {
+    /// # The types of the environment, after the subtree has been inferred
+    ///
+    /// Has one entry for each entry of [`CacheKey::environment`]. Inferring
+    /// the subtree can tell us more about the types of local names and the
+    /// enclosing function. Those entries are `Some`.
+    pub environment: Vec<Option<InferredType>>,
+
+    pub output: InferredType,
+
+    /// # The types within the subtree, by the path relative to its root
+    pub types: Vec<(Vec<ChildIndex>, InferredType)>,
+
+    /// # The errors within the subtree, by the path relative to its root
+    pub errors: Vec<(Vec<ChildIndex>, TypeError)>,
+}
+
+fn collect_hashes(
+    hash: &NodeHash,
+    nodes: &Nodes,
+    hashes: &mut BTreeSet<NodeHash>,
+) {
+    if !hashes.insert(*hash) {
+        // Identical subtrees have already been visited.
+        return;
+    }
+
+    for child in nodes.get(hash).children().hashes {
+        collect_hashes(child, nodes, hashes);
+    }
+}
@@ -0,0 +1,1374 @@
+use std::{collections::BTreeMap, mem};
+
+use itertools::Itertools;
+
+use crate::language::{
+    code::{
+        ChildIndex, Codebase, Expression, LocatedNode, NodeHash, NodePath,
+        Nodes, SyntaxNode, Type, TypedNode,
+    },
+    runtime::HostFunctions,
+};
+
+use super::{
+    InferredType, TypeError,
+    cache::{Cache, CacheKey, CachedResult, FreeNames, Outside},
+};
+
+/// # Infer the types of all expressions in the codebase
+///
+/// Returns the inferred types, and the type errors that were found, by the
+/// paths of the nodes they belong to. The types of host functions come from
+/// the provided registry.
+///
+/// The results for subtrees are reused from the provided cache, where
+/// possible, and the cache is updated with the new ones.
+///
+/// ## Implementation Note
+///
+/// This is a variant of Hindley-Milner type inference: Types that aren't known
+/// yet are represented by variables, which unification then resolves.
+///
+/// Top-level definitions can be used with different types in different places,
+/// as their types are generalized. Local bindings are not, to keep things
+/// simple for now.
+pub fn infer(
+    codebase: &Codebase,
+    host_functions: &HostFunctions,
+    cache: &mut Cache,
+) -> (
+    BTreeMap<NodePath, InferredType>,
+    BTreeMap<NodePath, TypeError>,
+) {
+    let mut inference = Inference {
+        codebase,
+        nodes: codebase.nodes(),
+        host_functions,
+        cache,
+        frames: Vec::new(),
+        scope_depth: 0,
+        substitution: Vec::new(),
+        types: Vec::new(),
+        errors: Vec::new(),
+        definitions: BTreeMap::new(),
+        scope: Vec::new(),
+        functions: Vec::new(),
+    };
+
+    inference.infer(codebase.root().path);
+
+    let types = inference
+        .types
+        .iter()
+        .map(|(path, type_)| {
+            (path.clone(), inference.resolve(type_).normalize())
+        })
+        .collect();
+
+    let mut errors = BTreeMap::new();
+    for (path, error) in &inference.errors {
+        // Only the first error found at a node is reported. Any further ones
+        // are likely to be consequences of that.
+        errors
+            .entry(path.clone())
+            .or_insert_with(|| inference.resolve_error(error));
+    }
+
+    (types, errors)
+}
+
+struct Inference<'r> {
+    codebase: &'r Codebase,
+    nodes: &'r Nodes,
+    host_functions: &'r HostFunctions,
+    cache: &'r mut Cache,
+
+    /// # The subtrees that are currently being inferred, to cache the results
+    ///
+    /// The innermost one comes last.
+    frames: Vec<Frame>,
+
+    /// # The number of times that inference has switched to an empty scope
+    ///
+    /// Local names are only visible to the subtrees that were entered after
+    /// the most recent switch.
+    scope_depth: usize,
+
+    /// # The types that variables have been resolved to, indexed by their ID
+    substitution: Vec<Option<InferredType>>,
+
+    types: Vec<(NodePath, InferredType)>,
+    errors: Vec<(NodePath, TypeError)>,
+    definitions: BTreeMap<String, DefinitionState>,
+
+    /// # The local names that are currently in scope, with their types
+    ///
+    /// Later bindings shadow earlier ones.
+    scope: Vec<(String, InferredType)>,
+
+    /// # The types of the functions that are currently being inferred
+    ///
+    /// The innermost one is the one that recursion refers to.
+    functions: Vec<InferredType>,
+}
+
+impl<'r> Inference<'r> {
+    fn infer(&mut self, path: NodePath) -> InferredType {
+        if !self.is_cacheable(&path) {
+            let type_ = self.infer_expression(&path);
+            self.types.push((path, type_.clone()));
+            return type_;
+        }
+
+        let environment = self.environment();
+        if let Some(type_) = self.reuse(&path, &environment) {
+            return type_;
+        }
+
+        self.frames.push(Frame {
+            environment,
+            scope_depth: self.scope_depth,
+            types: self.types.len(),
+            errors: self.errors.len(),
+            free_names: FreeNames::default(),
+            is_cacheable: true,
+        });
+
+        let type_ = self.infer_expression(&path);
+        self.types.push((path.clone(), type_.clone()));
+
+        let Some(frame) = self.frames.pop() else {
+            unreachable!("Just pushed a frame. It must still be there.");
+        };
+        self.store(&path, frame, &type_);
+
+        type_
+    }
+
+    /// # Indicate whether the result of inferring a subtree can be cached
+    ///
+    /// The root contains the top-level definitions, which are inferred where
+    /// they are referred to, so it's not cached. Definitions and let nodes
+    /// affect the names that are in scope outside of them. And nodes without
+    /// children are not worth caching.
+    fn is_cacheable(&self, path: &NodePath) -> bool {
+        let node = self.nodes.get(path.hash());
+
+        path.parent().is_some()
+            && !node.children().hashes.is_empty()
+            && !matches!(
+                node,
+                SyntaxNode::Definition { .. } | SyntaxNode::Let { .. }
+            )
+    }
+
+    /// # The names in scope, with their types as they are currently known
+    fn environment(&self) -> Environment {
+        Environment {
+            scope: self
+                .scope
+                .iter()
+                .map(|(name, type_)| (name.clone(), self.resolve(type_)))
+                .collect(),
+            functions: self.functions.len(),
+            recursion: self.functions.last().map(|type_| self.resolve(type_)),
+        }
+    }
+
+    /// # Reuse the cached result for a subtree, if there is one
+    fn reuse(
+        &mut self,
+        path: &NodePath,
+        environment: &Environment,
+    ) -> Option<InferredType> {
+        let free_names = self.cache.free_names.get(path.hash())?.clone();
+        let (key, ids, actual) =
+            self.key(*path.hash(), &free_names, environment)?;
+        let result = self.cache.results.remove(&key)?;
+
+        // The variables in the environment are the same as the ones here.
+        // Any other variables are new.
+        let mut variables = ids
+            .into_iter()
+            .map(|id| InferredType::Variable { id })
+            .collect();
+
+        // Inferring the subtree told us more about the environment. That
+        // needs to be the case here too.
+        for (actual, after) in actual.iter().zip(&result.environment) {
+            if let (Some(actual), Some(after)) = (actual, after) {
+                let after = self.cached_type(after, &mut variables);
+                self.unify_at(path.clone(), actual, &after);
+            }
+        }
+
+        // The enclosing subtrees depend on the same names as this one.
+        for name in &free_names.names {
+            if let Some(index) =
+                self.scope.iter().rposition(|(other, _)| other == name)
+            {
+                self.note_local(name, index);
+            } else {
+                self.note_global(name);
+            }
+        }
+        if free_names.recursion {
+            self.note_recursion();
+        }
+
+        for (relative, type_) in &result.types {
+            if let Some(child) = self.descend(path, relative) {
+                let type_ = self.cached_type(type_, &mut variables);
+                self.types.push((child, type_));
+            }
+        }
+        for (relative, error) in &result.errors {
+            if let Some(child) = self.descend(path, relative) {
+                let error = map_error(error, |type_| {
+                    self.cached_type(type_, &mut variables)
+                });
+                self.errors.push((child, error));
+            }
+        }
+
+        let output = self.cached_type(&result.output, &mut variables);
+        self.cache.results.insert(key, result);
+
+        Some(output)
+    }
+
+    /// # Store the result of inferring a subtree in the cache
+    fn store(&mut self, path: &NodePath, frame: Frame, output: &InferredType) {
+        if !frame.is_cacheable
+            || self.scope.len() != frame.environment.scope.len()
+        {
+            // The subtree depends on a type that isn't fully known yet, or it
+            // bound names that are visible outside of it.
+            return;
+        }
+
+        let Some((key, mut ids, actual)) =
+            self.key(*path.hash(), &frame.free_names, &frame.environment)
+        else {
+            return;
+        };
+
+        let environment = actual
+            .iter()
+            .map(|type_| {
+                type_
+                    .as_ref()
+                    .map(|type_| self.resolve(type_).renumber(&mut ids))
+            })
+            .collect();
+        let output = self.resolve(output).renumber(&mut ids);
+
+        let types = self.types[frame.types..]
+            .iter()
+            .filter_map(|(child, type_)| {
+                let relative = relative(path, child)?;
+                Some((relative, self.resolve(type_).renumber(&mut ids)))
+            })
+            .collect();
+        let errors = self.errors[frame.errors..]
+            .iter()
+            .filter_map(|(child, error)| {
+                let relative = relative(path, child)?;
+                let error = map_error(error, |type_| {
+                    self.resolve(type_).renumber(&mut ids)
+                });
+                Some((relative, error))
+            })
+            .collect();
+
+        self.cache.free_names.insert(*path.hash(), frame.free_names);
+        self.cache.results.insert(
+            key,
+            CachedResult {
+                environment,
+                output,
+                types,
+                errors,
+            },
+        );
+    }
+
+    /// # Determine the key of a subtree's result in the cache
+    ///
+    /// Also returns the IDs of the variables in the environment, in the order
+    /// they appear in the key, and the types of the names that the subtree
+    /// can tell us more about.
+    ///
+    /// Returns `None`, if the subtree refers to a top-level definition whose
+    /// type is still being inferred.
+    fn key(
+        &mut self,
+        hash: NodeHash,
+        free_names: &FreeNames,
+        environment: &Environment,
+    ) -> Option<(CacheKey, Vec<u32>, Vec<Option<InferredType>>)> {
+        let mut outside = Vec::new();
+        let mut actual = Vec::new();
+
+        for name in &free_names.names {
+            if let Some((_, type_)) = environment
+                .scope
+                .iter()
+                .rev()
+                .find(|(other, _)| other == name)
+            {
+                outside.push((
+                    name.clone(),
+                    Outside::Local {
+                        type_: type_.clone(),
+                    },
+                ));
+                actual.push(Some(type_.clone()));
+                continue;
+            }
+
+            if !self.definitions.contains_key(name) {
+                // Make sure the definition has been inferred, if there is one.
+                self.definition(name);
+            }
+
+            let outside_ = match self.definitions.get(name) {
+                Some(DefinitionState::Inferring { .. }) => {
+                    return None;
+                }
+                Some(DefinitionState::Inferred { type_ }) => {
+                    Outside::Definition {
+                        type_: self.resolve(type_),
+                    }
+                }
+                None => Outside::Provided,
+            };
+
+            outside.push((name.clone(), outside_));
+            actual.push(None);
+        }
+
+        if free_names.recursion {
+            outside.push((
+                String::new(),
+                Outside::Recursion {
+                    type_: environment.recursion.clone(),
+                },
+            ));
+            actual.push(environment.recursion.clone());
+        }
+
+        let mut ids = Vec::new();
+        for (_, outside) in &mut outside {
+            match outside {
+                Outside::Local { type_ }
+                | Outside::Definition { type_ }
+                | Outside::Recursion { type_: Some(type_) } => {
+                    *type_ = type_.renumber(&mut ids);
+                }
+                Outside::Provided | Outside::Recursion { type_: None } => {}
+            }
+        }
+
+        let key = CacheKey {
+            hash,
+            environment: outside,
+        };
+
+        Some((key, ids, actual))
+    }
+
+    /// # Convert a type from the cache, using the provided variables
+    ///
+    /// Variables in the cached type are indices into the provided ones. Fresh
+    /// variables are added, as needed.
+    fn cached_type(
+        &mut self,
+        type_: &InferredType,
+        variables: &mut Vec<InferredType>,
+    ) -> InferredType {
+        match type_ {
+            InferredType::Function { parameter, output } => {
+                let parameter = self.cached_type(parameter, variables);
+                let output = self.cached_type(output, variables);
+                InferredType::function(parameter, output)
+            }
+            InferredType::List { item } => {
+                InferredType::list(self.cached_type(item, variables))
+            }
+            InferredType::Record { fields } => InferredType::Record {
+                fields: fields
+                    .iter()
+                    .map(|(name, type_)| {
+                        (name.clone(), self.cached_type(type_, variables))
+                    })
+                    .collect(),
+            },
+            InferredType::Tuple { values } => InferredType::Tuple {
+                values: values
+                    .iter()
+                    .map(|type_| self.cached_type(type_, variables))
+                    .collect(),
+            },
+            InferredType::Variable { id } => {
+                let index = *id as usize;
+                while variables.len() <= index {
+                    let variable = self.fresh();
+                    variables.push(variable);
+                }
+
+                variables[index].clone()
+            }
+            InferredType::Boolean
+            | InferredType::Float
+            | InferredType::Integer
+            | InferredType::Integer64
+            | InferredType::String
+            | InferredType::Unsigned => type_.clone(),
+        }
+    }
+
+    /// # The path of a node, relative to the provided subtree root
+    fn descend(
+        &self,
+        root: &NodePath,
+        relative: &[ChildIndex],
+    ) -> Option<NodePath> {
+        let mut path = root.clone();
+
+        for index in relative {
+            let hash = **self
+                .nodes
+                .get(path.hash())
+                .children()
+                .hashes
+                .get(index.index)?;
+            path = NodePath::new(hash, Some((path, *index)), self.nodes);
+        }
+
+        Some(path)
+    }
+
+    /// # Record that the subtrees being inferred refer to a local name
+    ///
+    /// The index is the one of the name in the current scope.
+    fn note_local(&mut self, name: &str, index: usize) {
+        for frame in &mut self.frames {
+            if frame.scope_depth == self.scope_depth
+                && index < frame.environment.scope.len()
+            {
+                frame.free_names.names.insert(name.to_string());
+            }
+        }
+    }
+
+    /// # Record that the subtrees being inferred refer to a non-local name
+    fn note_global(&mut self, name: &str) {
+        for frame in &mut self.frames {
+            frame.free_names.names.insert(name.to_string());
+        }
+    }
+
+    /// # Record that the subtrees being inferred refer to a function
+    fn note_recursion(&mut self) {
+        for frame in &mut self.frames {
+            if frame.scope_depth == self.scope_depth
+                && self.functions.len() <= frame.environment.functions
+            {
+                frame.free_names.recursion = true;
+            }
+        }
+    }
+
+    fn infer_expression(&mut self, path: &NodePath) -> InferredType {
+        let nodes = self.nodes;
+
+        let TypedNode::Expression { expression } =
+            TypedNode::from_hash(path.hash(), nodes)
+        else {
+            // Patterns are inferred where they are expected. If we end up
+            // here, then the syntax tree is not well-formed.
+            return self.fresh();
+        };
+
+        match expression {
+            Expression::Apply { apply } => {
+                let function_path =
+                    apply.expression().into_path(path.clone(), nodes);
+                let argument_path =
+                    apply.argument().into_path(path.clone(), nodes);
+
+                let function = self.infer(function_path.clone());
+                let argument = self.infer(argument_path.clone());
+
+                if let InferredType::Function { parameter, output } =
+                    self.shallow(&function)
+                {
+                    self.unify_at(argument_path, &parameter, &argument);
+                    *output
+                } else {
+                    let output = self.fresh();
+                    self.unify_at(
+                        function_path,
+                        &InferredType::function(argument, output.clone()),
+                        &function,
+                    );
+                    output
+                }
+            }
+            Expression::Body { body } => {
+                let scope = self.scope.len();
+
+                let mut output = InferredType::nothing();
+                for child in body.children().to_paths(path, nodes) {
+                    output = self.infer(child);
+                }
+
+                // Any names that were bound within the body go out of scope.
+                self.scope.truncate(scope);
+
+                output
+            }
+            Expression::Boolean { .. } => InferredType::Boolean,
+            Expression::Conflict => {
+                // The conflicting versions are not evaluated, so there's no
+                // way to tell what this is going to be.
+                self.fresh()
+            }
+            Expression::Definition => {
+                if let Some([binding, value]) = self.children(path)
+                    && let SyntaxNode::Binding { name } = binding.node
+                {
+                    if self.codebase.definition(name).as_ref()
+                        == Some(&value.path)
+                    {
+                        self.definition(name);
+                    } else {
+                        // This definition is shadowed by an earlier one with
+                        // the same name, or it's not a top-level definition.
+                        // It can't be referred to, but it still has types.
+                        self.in_empty_scope(|inference| {
+                            inference.infer(value.path);
+                        });
+                    }
+                }
+
+                InferredType::nothing()
+            }
+            Expression::Empty => InferredType::nothing(),
+            Expression::FieldAccess { access } => {
+                let record =
+                    self.infer(access.record().into_path(path.clone(), nodes));
+                self.field(path, &record, &access.field(nodes).name)
+            }
+            Expression::FieldUpdate { update } => {
+                let record =
+                    self.infer(update.record().into_path(path.clone(), nodes));
+
+                for (name, value_path) in
+                    self.fields(update.fields().into_path(path.clone(), nodes))
+                {
+                    let value = self.infer(value_path.clone());
+                    let expected = self.field(path, &record, &name);
+                    self.unify_at(value_path, &expected, &value);
+                }
+
+                record
+            }
+            Expression::Float { .. } => InferredType::Float,
+            Expression::Function { function } => {
+                let parameter = self.fresh();
+                let output = self.fresh();
+                let type_ =
+                    InferredType::function(parameter.clone(), output.clone());
+
+                if let Some([parameter_node, body]) = self.children(path) {
+                    self.types.push((parameter_node.path, parameter.clone()));
+
+                    let scope = self.scope.len();
+                    self.scope.push((function.parameter.name, parameter));
+                    self.functions.push(type_.clone());
+
+                    let body_type = self.infer(body.path.clone());
+                    self.unify_at(body.path, &output, &body_type);
+
+                    self.functions.pop();
+                    self.scope.truncate(scope);
+                }
+
+                type_
+            }
+            Expression::Identifier { name } => {
+                // Names are resolved like the evaluator resolves them: local
+                // names first, then top-level definitions. Anything else is a
+                // provided function.
+                let local =
+                    self.scope.iter().rposition(|(other, _)| other == &name);
+
+                if let Some(index) = local {
+                    self.note_local(&name, index);
+                    self.scope[index].1.clone()
+                } else {
+                    self.note_global(&name);
+
+                    if let Some(type_) = self.definition(&name) {
+                        if let Some(DefinitionState::Inferring { .. }) =
+                            self.definitions.get(&name)
+                        {
+                            // The type of the definition isn't fully known
+                            // yet, so the result can't be reused.
+                            for frame in &mut self.frames {
+                                frame.is_cacheable = false;
+                            }
+                        }
+
+                        type_
+                    } else {
+                        self.provided_function(path, &name)
+                    }
+                }
+            }
+            Expression::Integer64 { .. } => InferredType::Integer64,
+            Expression::Let { let_ } => {
+                if let Some([binding, value]) = self.children(path) {
+                    let value = self.infer(value.path);
+
+                    // The name is visible to the following siblings within
+                    // the parent body, which removes it from scope once it's
+                    // finished.
+                    self.types.push((binding.path, value.clone()));
+                    self.scope.push((let_.binding.name, value));
+                }
+
+                InferredType::nothing()
+            }
+            Expression::List { .. } => {
+                let item = self.fresh();
+
+                for value in self.values(path) {
+                    let type_ = self.infer(value.clone());
+                    self.unify_at(value, &item, &type_);
+                }
+
+                InferredType::list(item)
+            }
+            Expression::Match { match_ } => {
+                let scrutinee = self
+                    .infer(match_.scrutinee().into_path(path.clone(), nodes));
+                let output = self.fresh();
+
+                let arms = match_.arms().into_path(path.clone(), nodes);
+                for arm in self.codebase.node_at(&arms).children(nodes) {
+                    let Some([pattern, body]) =
+                        arm.children(nodes).collect_array()
+                    else {
+                        continue;
+                    };
+
+                    // The names that the pattern binds are only visible in the
+                    // arm's body.
+                    let scope = self.scope.len();
+
+                    self.pattern(pattern, &scrutinee);
+                    let type_ = self.infer(body.path.clone());
+                    self.unify_at(body.path, &output, &type_);
+
+                    self.scope.truncate(scope);
+                }
+
+                output
+            }
+            Expression::Number { .. } => InferredType::Integer,
+            Expression::Record { record } => {
+                let mut fields: Vec<(String, InferredType)> = Vec::new();
+
+                for (name, value_path) in
+                    self.fields(record.fields().into_path(path.clone(), nodes))
+                {
+                    let value = self.infer(value_path);
+
+                    // If a field is defined more than once, the last
+                    // definition wins.
+                    if let Some((_, type_)) =
+                        fields.iter_mut().find(|(field, _)| field == &name)
+                    {
+                        *type_ = value;
+                    } else {
+                        fields.push((name, value));
+                    }
+                }
+
+                InferredType::Record { fields }
+            }
+            Expression::Recursion => {
+                self.note_recursion();
+
+                match self.functions.last() {
+                    Some(function) => function.clone(),
+                    None => self.fresh(),
+                }
+            }
+            Expression::String { .. } => InferredType::String,
+            Expression::Tuple { .. } => {
+                let values = self
+                    .values(path)
+                    .into_iter()
+                    .map(|value| self.infer(value))
+                    .collect();
+
+                InferredType::Tuple { values }
+            }
+            Expression::Unsigned { .. } => InferredType::Unsigned,
+        }
+    }
+
+    /// # Infer the types within a pattern, and bind the names it introduces
+    ///
+    /// The provided type is the one of the value that the pattern is matched
+    /// against.
+    fn pattern(&mut self, pattern: LocatedNode, expected: &InferredType) {
+        let nodes = self.nodes;
+
+        let type_ = match pattern.node {
+            SyntaxNode::Binding { name } => {
+                self.scope.push((name.clone(), expected.clone()));
+                expected.clone()
+            }
+            SyntaxNode::BooleanPattern { .. } => {
+                self.unify_at(
+                    pattern.path.clone(),
+                    expected,
+                    &InferredType::Boolean,
+                );
+                InferredType::Boolean
+            }
+            SyntaxNode::NumberPattern { .. } => {
+                self.unify_at(
+                    pattern.path.clone(),
+                    expected,
+                    &InferredType::Integer,
+                );
+                InferredType::Integer
+            }
+            SyntaxNode::RecordPattern { .. } => {
+                for field in self.values(&pattern.path) {
+                    let SyntaxNode::Binding { name } = nodes.get(field.hash())
+                    else {
+                        continue;
+                    };
+
+                    let type_ = self.field(&pattern.path, expected, name);
+                    self.types.push((field, type_.clone()));
+                    self.scope.push((name.clone(), type_));
+                }
+
+                expected.clone()
+            }
+            SyntaxNode::TuplePattern { .. } => {
+                let values = self.values(&pattern.path);
+                let types = values.iter().map(|_| self.fresh()).collect_vec();
+
+                self.unify_at(
+                    pattern.path.clone(),
+                    expected,
+                    &InferredType::Tuple {
+                        values: types.clone(),
+                    },
+                );
+
+                for (value, type_) in values.into_iter().zip(&types) {
+                    let value = self.codebase.node_at(&value);
+                    self.pattern(value, type_);
+                }
+
+                InferredType::Tuple { values: types }
+            }
+            SyntaxNode::Wildcard => expected.clone(),
+            _ => {
+                // Not a pattern. The syntax tree is not well-formed, but that
+                // is not for type inference to report.
+                return;
+            }
+        };
+
+        self.types.push((pattern.path, type_));
+    }
+
+    /// # The type of the top-level definition with the provided name, if any
+    fn definition(&mut self, name: &str) -> Option<InferredType> {
+        match self.definitions.get(name) {
+            Some(DefinitionState::Inferring { type_ }) => {
+                // The definition refers to itself, directly or indirectly.
+                // Its type is not known yet, and it can't be generalized.
+                return Some(type_.clone());
+            }
+            Some(DefinitionState::Inferred { type_ }) => {
+                let type_ = type_.clone();
+                return Some(self.instantiate(&type_));
+            }
+            None => {}
+        }
+
+        let path = self.codebase.definition(name)?;
+
+        let placeholder = self.fresh();
+        self.definitions.insert(
+            name.to_string(),
+            DefinitionState::Inferring {
+                type_: placeholder.clone(),
+            },
+        );
+
+        // Top-level definitions don't see any local names. And the subtrees
+        // that are being inferred only depend on its generalized type, not on
+        // anything within it.
+        let frames = mem::take(&mut self.frames);
+        let type_ =
+            self.in_empty_scope(|inference| inference.infer(path.clone()));
+        self.frames = frames;
+        self.unify_at(path, &placeholder, &type_);
+
+        let type_ = self.resolve(&type_);
+        self.definitions.insert(
+            name.to_string(),
+            DefinitionState::Inferred {
+                type_: type_.clone(),
+            },
+        );
+
+        Some(self.instantiate(&type_))
+    }
+
+    /// # The type of a provided function
+    ///
+    /// Intrinsic functions whose types can be expressed, have those, and so do
+    /// registered host functions. Any other name can't be resolved, which is
+    /// reported at the provided path.
+    fn provided_function(
+        &mut self,
+        path: &NodePath,
+        name: &str,
+    ) -> InferredType {
+        let a = self.fresh();
+
+        let pair = |type_: &InferredType| InferredType::Tuple {
+            values: vec![type_.clone(), type_.clone()],
+        };
+        let tuple = |values: &[InferredType]| InferredType::Tuple {
+            values: values.to_vec(),
+        };
+        let list = InferredType::list(a.clone());
+
+        let (parameter, output) = match name {
+            "%" | "*" | "+" | "-" | "/" | "bit_and" | "bit_or" | "bit_xor"
+            | "concat" | "saturating_add" | "saturating_mul"
+            | "saturating_sub" | "shift_left" | "shift_right"
+            | "wrapping_add" | "wrapping_mul" | "wrapping_sub" => (pair(&a), a),
+            "!=" | "<" | "<=" | "==" | ">" | ">=" => {
+                (pair(&a), InferredType::Boolean)
+            }
+            "and" | "or" => {
+                (pair(&InferredType::Boolean), InferredType::Boolean)
+            }
+            "bit_not" | "identity" | "negate" => (a.clone(), a),
+            "drop" => (a, InferredType::nothing()),
+            "get_item" => (tuple(&[list, InferredType::Integer]), a),
+            "length" => (a, InferredType::Integer),
+            "not" => (InferredType::Boolean, InferredType::Boolean),
+            "pop" => (list.clone(), tuple(&[list, a])),
+            "push" => (tuple(&[list.clone(), a]), list),
+            "set_item" => {
+                (tuple(&[list.clone(), InferredType::Integer, a]), list)
+            }
+            "slice" => (
+                tuple(&[
+                    a.clone(),
+                    InferredType::Integer,
+                    InferredType::Integer,
+                ]),
+                a,
+            ),
+            "to_float" => (a, InferredType::Float),
+            "to_integer" => (a, InferredType::Integer),
+            "to_integer64" => (a, InferredType::Integer64),
+            "to_string" => (a, InferredType::String),
+            "to_unsigned" => (a, InferredType::Unsigned),
+            "abort" | "filter" | "fold" | "for_each" | "handle" | "map"
+            | "raise" => {
+                // These intrinsic functions accept both lists and tuples, or
+                // raise effects that could have any type. Neither can be
+                // expressed as a type yet.
+                let output = self.fresh();
+                (a, output)
+            }
+            _ => {
+                if let Some(function) = self.host_functions.get(name) {
+                    let parameter = self.runtime_type(&function.parameter);
+                    let output = self.runtime_type(&function.output);
+                    (parameter, output)
+                } else {
+                    // Applying this would trigger an effect that no host is
+                    // going to handle, unless a handler in Crosscut code does.
+                    self.errors.push((
+                        path.clone(),
+                        TypeError::UnresolvedName {
+                            name: name.to_string(),
+                        },
+                    ));
+
+                    let output = self.fresh();
+                    (a, output)
+                }
+            }
+        };
+
+        InferredType::function(parameter, output)
+    }
+
+    /// # The type of a field of a record
+    ///
+    /// Reports an error at the provided path, if the record doesn't have that
+    /// field.
+    fn field(
+        &mut self,
+        path: &NodePath,
+        record: &InferredType,
+        name: &str,
+    ) -> InferredType {
+        match self.resolve(record) {
+            InferredType::Record { fields } => {
+                if let Some((_, type_)) =
+                    fields.into_iter().find(|(field, _)| field == name)
+                {
+                    return type_;
+                }
+            }
+            InferredType::Variable { .. } => {
+                // The type of the record is not known. There's nothing to
+                // check.
+                return self.fresh();
+            }
+            _ => {}
+        }
+
+        self.errors.push((
+            path.clone(),
+            TypeError::FieldNotFound {
+                name: name.to_string(),
+                record: record.clone(),
+            },
+        ));
+
+        self.fresh()
+    }
+
+    /// # Convert a runtime type into an inferred type
+    ///
+    /// Runtime types don't describe the parameter and output of functions, or
+    /// the items of lists. Those are represented by fresh variables. So is a
+    /// choice between several types, which inferred types can't express.
+    fn runtime_type(&mut self, type_: &Type) -> InferredType {
+        match type_ {
+            Type::Boolean => InferredType::Boolean,
+            Type::Float => InferredType::Float,
+            Type::Function => {
+                let parameter = self.fresh();
+                let output = self.fresh();
+                InferredType::function(parameter, output)
+            }
+            Type::Integer => InferredType::Integer,
+            Type::Integer64 => InferredType::Integer64,
+            Type::List => InferredType::list(self.fresh()),
+            Type::OneOf { .. } => self.fresh(),
+            Type::Record { fields } => InferredType::Record {
+                fields: fields
+                    .iter()
+                    .map(|(name, type_)| {
+                        (name.clone(), self.runtime_type(type_))
+                    })
+                    .collect(),
+            },
+            Type::String => InferredType::String,
+            Type::Tuple { values } => InferredType::Tuple {
+                values: values
+                    .iter()
+                    .map(|type_| self.runtime_type(type_))
+                    .collect(),
+            },
+            Type::Unsigned => InferredType::Unsigned,
+        }
+    }
+
+    fn in_empty_scope<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let scope = mem::take(&mut self.scope);
+        let functions = mem::take(&mut self.functions);
+        self.scope_depth += 1;
+
+        let output = f(self);
+
+        self.scope = scope;
+        self.functions = functions;
+        self.scope_depth -= 1;
+
+        output
+    }
+
+    fn fresh(&mut self) -> InferredType {
+        let Ok(id) = self.substitution.len().try_into() else {
+            panic!("Ran out of IDs for type variables.");
+        };
+        self.substitution.push(None);
+
+        InferredType::Variable { id }
+    }
+
+    /// # Replace the variables in a type with fresh ones
+    ///
+    /// This is how generalized types are used in different places, without
+    /// the types in one place affecting the others.
+    fn instantiate(&mut self, type_: &InferredType) -> InferredType {
+        let type_ = self.resolve(type_);
+
+        let mut variables = Vec::new();
+        self.replace_variables(&type_, &mut variables)
+    }
+
+    fn replace_variables(
+        &mut self,
+        type_: &InferredType,
+        variables: &mut Vec<(u32, InferredType)>,
+    ) -> InferredType {
+        match type_ {
+            InferredType::Function { parameter, output } => {
+                let parameter = self.replace_variables(parameter, variables);
+                let output = self.replace_variables(output, variables);
+                InferredType::function(parameter, output)
+            }
+            InferredType::List { item } => {
+                InferredType::list(self.replace_variables(item, variables))
+            }
+            InferredType::Record { fields } => InferredType::Record {
+                fields: fields
+                    .iter()
+                    .map(|(name, type_)| {
+                        (name.clone(), self.replace_variables(type_, variables))
+                    })
+                    .collect(),
+            },
+            InferredType::Tuple { values } => InferredType::Tuple {
+                values: values
+                    .iter()
+                    .map(|type_| self.replace_variables(type_, variables))
+                    .collect(),
+            },
+            InferredType::Variable { id } => {
+                if let Some((_, type_)) =
+                    variables.iter().find(|(other, _)| other == id)
+                {
+                    type_.clone()
+                } else {
+                    let type_ = self.fresh();
+                    variables.push((*id, type_.clone()));
+                    type_
+                }
+            }
+            InferredType::Boolean
+            | InferredType::Float
+            | InferredType::Integer
+            | InferredType::Integer64
+            | InferredType::String
+            | InferredType::Unsigned => type_.clone(),
+        }
+    }
+
+    /// # Make two types equal, or report a mismatch at the provided path
+    fn unify_at(
+        &mut self,
+        path: NodePath,
+        expected: &InferredType,
+        actual: &InferredType,
+    ) {
+        if !self.unify(expected, actual) {
+            self.errors.push((
+                path,
+                TypeError::Mismatch {
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                },
+            ));
+        }
+    }
+
+    /// # Make two types equal, by resolving the variables in them
+    ///
+    /// Returns `false`, if that is not possible.
+    fn unify(&mut self, a: &InferredType, b: &InferredType) -> bool {
+        match (self.shallow(a), self.shallow(b)) {
+            (
+                InferredType::Variable { id: a },
+                InferredType::Variable { id: b },
+            ) if a == b => true,
+            (InferredType::Variable { id }, type_)
+            | (type_, InferredType::Variable { id }) => {
+                // A type that contains itself could never be written down.
+                if self.occurs(id, &type_) {
+                    return false;
+                }
+
+                self.substitution[id as usize] = Some(type_);
+                true
+            }
+            (
+                InferredType::Function {
+                    parameter: parameter_a,
+                    output: output_a,
+                },
+                InferredType::Function {
+                    parameter: parameter_b,
+                    output: output_b,
+                },
+            ) => {
+                self.unify(&parameter_a, &parameter_b)
+                    && self.unify(&output_a, &output_b)
+            }
+            (
+                InferredType::List { item: item_a },
+                InferredType::List { item: item_b },
+            ) => self.unify(&item_a, &item_b),
+            (
+                InferredType::Record { fields: fields_a },
+                InferredType::Record { fields: fields_b },
+            ) => {
+                fields_a.len() == fields_b.len()
+                    && fields_a.iter().all(|(name, type_a)| {
+                        fields_b
+                            .iter()
+                            .find(|(other, _)| other == name)
+                            .is_some_and(|(_, type_b)| {
+                                self.unify(type_a, type_b)
+                            })
+                    })
+            }
+            (
+                InferredType::Tuple { values: values_a },
+                InferredType::Tuple { values: values_b },
+            ) => {
+                values_a.len() == values_b.len()
+                    && values_a
+                        .iter()
+                        .zip(&values_b)
+                        .all(|(type_a, type_b)| self.unify(type_a, type_b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    fn occurs(&self, id: u32, type_: &InferredType) -> bool {
+        match self.shallow(type_) {
+            InferredType::Function { parameter, output } => {
+                self.occurs(id, &parameter) || self.occurs(id, &output)
+            }
+            InferredType::List { item } => self.occurs(id, &item),
+            InferredType::Record { fields } => {
+                fields.iter().any(|(_, type_)| self.occurs(id, type_))
+            }
+            InferredType::Tuple { values } => {
+                values.iter().any(|type_| self.occurs(id, type_))
+            }
+            InferredType::Variable { id: other } => id == other,
+            InferredType::Boolean
+            | InferredType::Float
+            | InferredType::Integer
+            | InferredType::Integer64
+            | InferredType::String
+            | InferredType::Unsigned => false,
+        }
+    }
+
+    /// # Follow resolved variables, until reaching something else
+    fn shallow(&self, type_: &InferredType) -> InferredType {
+        let mut type_ = type_;
+
+        while let InferredType::Variable { id } = type_
+            && let Some(Some(resolved)) = self.substitution.get(*id as usize)
+        {
+            type_ = resolved;
+        }
+
+        type_.clone()
+    }
+
+    /// # Replace all resolved variables within a type
+    fn resolve(&self, type_: &InferredType) -> InferredType {
+        match self.shallow(type_) {
+            InferredType::Function { parameter, output } => {
+                InferredType::function(
+                    self.resolve(&parameter),
+                    self.resolve(&output),
+                )
+            }
+            InferredType::List { item } => {
+                InferredType::list(self.resolve(&item))
+            }
+            InferredType::Record { fields } => InferredType::Record {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, type_)| (name, self.resolve(&type_)))
+                    .collect(),
+            },
+            InferredType::Tuple { values } => InferredType::Tuple {
+                values: values
+                    .iter()
+                    .map(|type_| self.resolve(type_))
+                    .collect(),
+            },
+            type_ => type_,
+        }
+    }
+
+    fn resolve_error(&self, error: &TypeError) -> TypeError {
+        match error {
+            TypeError::Mismatch { expected, actual } => {
+                // Both types are normalized together, so variables that they
+                // share keep sharing a name.
+                let both = InferredType::Tuple {
+                    values: vec![self.resolve(expected), self.resolve(actual)],
+                }
+                .normalize();
+
+                let InferredType::Tuple { values } = both else {
+                    unreachable!("Normalizing a tuple results in a tuple.");
+                };
+                let Some([expected, actual]) =
+                    values.into_iter().collect_array()
+                else {
+                    unreachable!("Normalizing a tuple keeps its values.");
+                };
+
+                TypeError::Mismatch { expected, actual }
+            }
+            TypeError::FieldNotFound { name, record } => {
+                TypeError::FieldNotFound {
+                    name: name.clone(),
+                    record: self.resolve(record).normalize(),
+                }
+            }
+            TypeError::UnresolvedName { .. } => error.clone(),
+        }
+    }
+
+    fn children<const N: usize>(
+        &self,
+        path: &NodePath,
+    ) -> Option<[LocatedNode<'r>; N]> {
+        self.codebase
+            .node_at(path)
+            .children(self.nodes)
+            .collect_array()
+    }
+
+    /// # The paths of the children of a node's only child, which is a body
+    ///
+    /// This is how lists, tuples, and some patterns store their values.
+    fn values(&self, path: &NodePath) -> Vec<NodePath> {
+        let Some([body]) = self.children(path) else {
+            return Vec::new();
+        };
+
+        body.children(self.nodes)
+            .filter(|value| !matches!(value.node, SyntaxNode::Add))
+            .map(|value| value.path)
+            .collect()
+    }
+
+    /// # The fields defined in a body, with the paths of their values
+    ///
+    /// Used for record literals and updates. Each field is a definition. Any
+    /// other children of the body are not fields, and are ignored.
+    fn fields(&self, body: NodePath) -> Vec<(String, NodePath)> {
+        self.codebase
+            .node_at(&body)
+            .children(self.nodes)
+            .filter_map(|definition| {
+                let SyntaxNode::Definition { .. } = definition.node else {
+                    return None;
+                };
+                let [binding, value] =
+                    definition.children(self.nodes).collect_array()?;
+                let SyntaxNode::Binding { name } = binding.node else {
+                    return None;
+                };
+
+                Some((name.clone(), value.path))
+            })
+            .collect()
+    }
+}
+
+enum DefinitionState {
+    Inferring { type_: InferredType },
+    Inferred { type_: InferredType },
+}
+
+/// # The names in scope where a subtree is located, with their types
+struct Environment {
+    scope: Vec<(String, InferredType)>,
+
+    /// # The number of functions that enclose the subtree
+    functions: usize,
+
+    /// # The type of the innermost function that encloses the subtree
+    recursion: Option<InferredType>,
+}
+
+/// # A subtree that is being inferred, whose result is going to be cached
+struct Frame {
+    environment: Environment,
+    scope_depth: usize,
+
+    /// # The number of types that were inferred before the subtree
+    types: usize,
+
+    /// # The number of errors that were found before the subtree
+    errors: usize,
+
+    free_names: FreeNames,
+
+    /// # Indicate whether the result can be cached
+    is_cacheable: bool,
+}
+
+/// # The path of a node, relative to the root of a subtree that contains it
+///
+/// Returns `None`, if the node is not within the subtree.
+fn relative(root: &NodePath, path: &NodePath) -> Option<Vec<ChildIndex>> {
+    let mut indices = Vec::new();
+    let mut current = path;
+
+    while current != root {
+        let (parent, index) = current.parent()?;
+        indices.push(index);
+        current = parent;
+    }
+
+    indices.reverse();
+    Some(indices)
+}
+
+/// # Apply a function to all the types within a type error
+fn map_error(
+    error: &TypeError,
+    mut f: impl FnMut(&InferredType) -> InferredType,
+) -> TypeError {
+    match error {
+        TypeError::Mismatch { expected, actual } => TypeError::Mismatch {
+            expected: f(expected),
+            actual: f(actual),
+        },
+        TypeError::FieldNotFound { name, record } => TypeError::FieldNotFound {
+            name: name.clone(),
+            record: f(record),
+        },
+        TypeError::UnresolvedName { .. } => error.clone(),
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use crate::language::code::{display_record, display_tuple};
+
+/// # The type of an expression, as determined by type inference
+///
+/// Unlike [`Type`], which describes values at runtime, this can describe the
+/// parameter and output of functions, and the items of lists. It can also
+/// refer to types that are not known (yet), as variables.
+///
+/// [`Type`]: crate::language::code::Type
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum InferredType {
+    Boolean,
+    Float,
+    Function {
+        parameter: Box<InferredType>,
+        output: Box<InferredType>,
+    },
+    Integer,
+    Integer64,
+    List {
+        item: Box<InferredType>,
+    },
+    Record {
+        fields: Vec<(String, InferredType)>,
+    },
+    String,
+    Tuple {
+        values: Vec<InferredType>,
+    },
+    Unsigned,
+
+    /// # A type that is not known
+    ///
+    /// Two variables with the same ID stand for the same type.
+    Variable {
+        id: u32,
+    },
+}
+
+impl InferredType {
+    pub fn nothing() -> Self {
+        Self::Tuple { values: Vec::new() }
+    }
+
+    pub fn function(parameter: Self, output: Self) -> Self {
+        Self::Function {
+            parameter: Box::new(parameter),
+            output: Box::new(output),
+        }
+    }
+
+    pub fn list(item: Self) -> Self {
+        Self::List {
+            item: Box::new(item),
+        }
+    }
+
+    /// # Renumber the variables, in the order they appear in
+    ///
+    /// The IDs of variables are only meaningful within the inference pass that
+    /// created them. This makes types that are equivalent also equal, which
+    /// makes them easier to compare and display.
+    pub fn normalize(&self) -> Self {
+        let mut ids = Vec::new();
+        self.renumber(&mut ids)
+    }
+
+    /// # Renumber the variables, continuing with the provided IDs
+    ///
+    /// Each variable gets the index of its ID in the provided list, which new
+    /// IDs are added to. Using the same list for multiple types, numbers the
+    /// variables consistently across all of them.
+    pub(super) fn renumber(&self, ids: &mut Vec<u32>) -> Self {
+        match self {
+            Self::Function { parameter, output } => {
+                let parameter = parameter.renumber(ids);
+                let output = output.renumber(ids);
+                Self::function(parameter, output)
+            }
+            Self::List { item } => Self::list(item.renumber(ids)),
+            Self::Record { fields } => Self::Record {
+                fields: fields
+                    .iter()
+                    .map(|(name, type_)| (name.clone(), type_.renumber(ids)))
+                    .collect(),
+            },
+            Self::Tuple { values } => Self::Tuple {
+                values: values
+                    .iter()
+                    .map(|type_| type_.renumber(ids))
+                    .collect(),
+            },
+            Self::Variable { id } => {
+                let index = ids.iter().position(|other| other == id);
+                let index = index.unwrap_or_else(|| {
+                    ids.push(*id);
+                    ids.len() - 1
+                });
+
+                let Ok(id) = index.try_into() else {
+                    unreachable!(
+                        "A type can't have more variables than there are \
+                        variable IDs."
+                    );
+                };
+
+                Self::Variable { id }
+            }
+            Self::Boolean
+            | Self::Float
+            | Self::Integer
+            | Self::Integer64
+            | Self::String
+            | Self::Unsigned => self.clone(),
+        }
+    }
+}
+
+impl fmt::Display for InferredType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Boolean => {
+                write!(f, "Boolean")?;
+            }
+            Self::Float => {
+                write!(f, "Float")?;
+            }
+            Self::Function { parameter, output } => {
+                if let Self::Function { .. } = **parameter {
+                    write!(f, "({parameter}) -> {output}")?;
+                } else {
+                    write!(f, "{parameter} -> {output}")?;
+                }
+            }
+            Self::Integer => {
+                write!(f, "Integer")?;
+            }
+            Self::Integer64 => {
+                write!(f, "Integer64")?;
+            }
+            Self::List { item } => {
+                write!(f, "[{item}]")?;
+            }
+            Self::Record { fields } => {
+                display_record(fields, f)?;
+            }
+            Self::String => {
+                write!(f, "String")?;
+            }
+            Self::Tuple { values } => {
+                display_tuple(values, f)?;
+            }
+            Self::Unsigned => {
+                write!(f, "Unsigned")?;
+            }
+            Self::Variable { id } => {
+                // Variables are displayed as lowercase letters, which sets them
+                // apart from the names of known types.
+                match u8::try_from(*id) {
+                    Ok(id) if id < 26 => {
+                        write!(f, "{}", char::from(b'a' + id))?
+                    }
+                    _ => write!(f, "t{id}")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
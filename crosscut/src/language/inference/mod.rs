@@ -0,0 +1,12 @@
+mod cache;
+mod infer;
+mod inferred_type;
+mod types;
+
+pub use self::{
+    inferred_type::InferredType,
+    types::{TypeError, Types},
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,346 @@
+use crate::language::{
+    code::{Codebase, NodePath, Type},
+    language::Language,
+    runtime::{HostFunction, HostFunctions},
+    text::import,
+};
+
+use super::{InferredType, TypeError, Types};
+
+#[test]
+fn infer_type_of_expression() {
+    let (codebase, types) = infer(
+        "
+        apply
+            +
+            tuple
+                1
+                2
+        ",
+    );
+
+    assert_eq!(root_type(&codebase, &types), "Integer");
+    assert_eq!(errors(&codebase, &types), []);
+}
+
+#[test]
+fn infer_parameter_and_output_of_function() {
+    let (codebase, types) = infer(
+        "
+        fn
+            x
+            apply
+                +
+                tuple
+                    x
+                    1.0
+        ",
+    );
+
+    assert_eq!(root_type(&codebase, &types), "Float -> Float");
+}
+
+#[test]
+fn infer_generic_function() {
+    // Types that aren't known are represented by variables. Top-level
+    // definitions can be used with different types.
+
+    let (codebase, types) = infer(
+        "
+        def
+            id
+            fn
+                x
+                x
+        tuple
+            id
+            apply
+                id
+                true
+            apply
+                id
+                \"a\"
+        ",
+    );
+
+    assert_eq!(root_type(&codebase, &types), "{ a -> a, Boolean, String }");
+    assert_eq!(errors(&codebase, &types), []);
+}
+
+#[test]
+fn report_mismatch() {
+    // Type errors are attached to the node where they were found.
+
+    let (codebase, types) = infer(
+        "
+        apply
+            +
+            tuple
+                1
+                \"a\"
+        ",
+    );
+
+    assert_eq!(
+        errors(&codebase, &types),
+        [(
+            "tuple".to_string(),
+            TypeError::Mismatch {
+                expected: InferredType::Tuple {
+                    values: vec![InferredType::Integer, InferredType::Integer],
+                },
+                actual: InferredType::Tuple {
+                    values: vec![InferredType::Integer, InferredType::String],
+                },
+            }
+        )],
+    );
+}
+
+#[test]
+fn report_application_of_non_function() {
+    let (codebase, types) = infer(
+        "
+        apply
+            1
+            2
+        ",
+    );
+
+    assert_eq!(
+        errors(&codebase, &types),
+        [(
+            "1".to_string(),
+            TypeError::Mismatch {
+                expected: InferredType::function(
+                    InferredType::Integer,
+                    InferredType::Variable { id: 0 },
+                ),
+                actual: InferredType::Integer,
+            }
+        )],
+    );
+}
+
+#[test]
+fn report_missing_field() {
+    let (codebase, types) = infer(
+        "
+        get
+            record
+                def
+                    a
+                    1
+            b
+        ",
+    );
+
+    assert_eq!(
+        errors(&codebase, &types),
+        [(
+            "get".to_string(),
+            TypeError::FieldNotFound {
+                name: "b".to_string(),
+                record: InferredType::Record {
+                    fields: vec![("a".to_string(), InferredType::Integer)],
+                },
+            }
+        )],
+    );
+}
+
+#[test]
+fn report_unresolved_name() {
+    // Names that are neither defined in the code, nor intrinsic or registered
+    // host functions, can't be resolved.
+
+    let (codebase, types) = infer(
+        "
+        apply
+            unknown
+            1
+        ",
+    );
+
+    assert_eq!(
+        errors(&codebase, &types),
+        [(
+            "unknown".to_string(),
+            TypeError::UnresolvedName {
+                name: "unknown".to_string(),
+            }
+        )],
+    );
+}
+
+#[test]
+fn check_application_of_host_function() {
+    // The signatures of registered host functions are taken into account.
+
+    let mut host_functions = HostFunctions::default();
+    host_functions.register(HostFunction {
+        name: "sleep_ms".to_string(),
+        parameter: Type::Integer,
+        output: Type::Tuple { values: Vec::new() },
+        documentation: String::new(),
+    });
+
+    let codebase = import(
+        "
+        tuple
+            apply
+                sleep_ms
+                1
+            apply
+                sleep_ms
+                true
+        ",
+    )
+    .expect("Test code should be valid.");
+    let types = Types::new(&codebase, &host_functions);
+
+    assert_eq!(root_type(&codebase, &types), "{ {}, {} }");
+    assert_eq!(
+        errors(&codebase, &types),
+        [(
+            "true".to_string(),
+            TypeError::Mismatch {
+                expected: InferredType::Integer,
+                actual: InferredType::Boolean,
+            }
+        )],
+    );
+}
+
+#[test]
+fn update_types_on_edit() {
+    // The types are kept up to date, as the code is edited.
+
+    let mut language = Language::new();
+    language.code("apply").down().code("not").down().code("1");
+
+    let root = language.codebase().root().path;
+    assert_eq!(language.types().errors().count(), 1);
+    assert_eq!(
+        language
+            .types()
+            .type_of(&root)
+            .map(|type_| type_.to_string()),
+        Some("Boolean".to_string()),
+    );
+
+    language.remove_left().code("true");
+
+    assert_eq!(language.types().errors().count(), 0);
+}
+
+#[test]
+fn incremental_update_matches_inference_from_scratch() {
+    // Results for subtrees are reused after edits. That must not make a
+    // difference compared to inferring everything again.
+
+    let mut language = Language::import(
+        "
+        def
+            double
+            fn
+                x
+                apply
+                    +
+                    tuple
+                        x
+                        x
+        def
+            main
+            fn
+                n
+                let
+                    y
+                    apply
+                        double
+                        n
+                tuple
+                    apply
+                        double
+                        1
+                    match
+                        y
+                        arm
+                            0
+                            true
+                        arm
+                            _
+                            apply
+                                self
+                                y
+                    record
+                        def
+                            a
+                            y
+        ",
+    );
+
+    // Each edit replaces the first character of a token.
+    for (token, replacement) in [
+        ("1", "2"),
+        ("x", "z"),
+        ("true", "f"),
+        ("a", "b"),
+        ("+", "-"),
+    ] {
+        language.find(token).remove_right().code(replacement);
+
+        let from_scratch =
+            Types::new(language.codebase(), &HostFunctions::default());
+
+        assert_eq!(
+            all_types(language.codebase(), language.types()),
+            all_types(language.codebase(), &from_scratch),
+        );
+        assert_eq!(
+            language.types().errors().collect::<Vec<_>>(),
+            from_scratch.errors().collect::<Vec<_>>(),
+        );
+    }
+
+    fn all_types(
+        codebase: &Codebase,
+        types: &Types,
+    ) -> Vec<(NodePath, String)> {
+        let mut all = Vec::new();
+        let mut queue = vec![codebase.root()];
+
+        while let Some(node) = queue.pop() {
+            if let Some(type_) = types.type_of(&node.path) {
+                all.push((node.path.clone(), type_.to_string()));
+            }
+
+            queue.extend(node.children(codebase.nodes()));
+        }
+
+        all
+    }
+}
+
+fn infer(code: &str) -> (Codebase, Types) {
+    let codebase = import(code).expect("Test code should be valid.");
+    let types = Types::new(&codebase, &HostFunctions::default());
+
+    (codebase, types)
+}
+
+fn root_type(codebase: &Codebase, types: &Types) -> String {
+    types
+        .type_of(&codebase.root().path)
+        .expect("Root should have a type.")
+        .to_string()
+}
+
+fn errors(codebase: &Codebase, types: &Types) -> Vec<(String, TypeError)> {
+    types
+        .errors()
+        .map(|(path, error)| {
+            (codebase.node_at(path).node.to_string(), error.clone())
+        })
+        .collect()
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use crate::language::{
+    code::{Codebase, NodeHash, NodePath},
+    runtime::HostFunctions,
+};
+
+use super::{InferredType, cache::Cache, infer::infer};
+
+/// # The inferred types of all expressions in the current syntax tree
+///
+/// Also keeps track of the type errors that inference found, attached to the
+/// nodes they were found at.
+#[derive(Debug, Default)]
+pub struct Types {
+    /// # The root of the syntax tree that the types were inferred for
+    root: Option<NodeHash>,
+
+    types: BTreeMap<NodePath, InferredType>,
+    errors: BTreeMap<NodePath, TypeError>,
+
+    /// # The results for subtrees, which are reused after edits
+    cache: Cache,
+}
+
+impl Types {
+    pub fn new(codebase: &Codebase, host_functions: &HostFunctions) -> Self {
+        let mut types = Self::default();
+        types.update(codebase, host_functions);
+        types
+    }
+
+    /// # Infer the types of the current syntax tree, if it has changed
+    ///
+    /// This is expected to be called after every change to the code.
+    ///
+    /// Inference runs incrementally: The results for subtrees that haven't
+    /// changed are reused, as long as the names they refer to still have the
+    /// same types. Only the changed subtrees, and the ones that depend on them,
+    /// are inferred again.
+    pub fn update(
+        &mut self,
+        codebase: &Codebase,
+        host_functions: &HostFunctions,
+    ) {
+        let root = *codebase.root().path.hash();
+
+        if self.root == Some(root) {
+            return;
+        }
+
+        let (types, errors) = infer(codebase, host_functions, &mut self.cache);
+        self.cache.retain(codebase);
+
+        self.root = Some(root);
+        self.types = types;
+        self.errors = errors;
+    }
+
+    /// # The inferred type of the expression at the provided path
+    ///
+    /// Returns `None`, if the path doesn't point to an expression (or a
+    /// binding) within the current syntax tree.
+    pub fn type_of(&self, path: &NodePath) -> Option<&InferredType> {
+        self.types.get(path)
+    }
+
+    /// # The type error found at the provided path, if any
+    pub fn error_at(&self, path: &NodePath) -> Option<&TypeError> {
+        self.errors.get(path)
+    }
+
+    /// # All type errors, with the paths of the nodes they were found at
+    pub fn errors(&self) -> impl Iterator<Item = (&NodePath, &TypeError)> {
+        self.errors.iter()
+    }
+}
+
+/// # A problem that type inference found
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum TypeError {
+    #[error("Expected `{expected}`, found `{actual}`.")]
+    Mismatch {
+        expected: InferredType,
+        actual: InferredType,
+    },
+
+    #[error("Field `{name}` not found in `{record}`.")]
+    FieldNotFound { name: String, record: InferredType },
+
+    #[error("`{name}` is not defined, and not a known provided function.")]
+    UnresolvedName { name: String },
+}
@@ -1,8 +1,11 @@
 use super::{
-    code::{Codebase, NodePath},
+    code::{Codebase, CodebaseEvent, NodePath, ObserverId},
     editor::{Editor, EditorCommand, EditorInput},
+    inference::Types,
     runtime::{
-        Effect, Evaluator, RuntimeState, Value, apply_intrinsic_function,
+        Effect, EffectOperation, Environment, Evaluator, HostFunction,
+        HostFunctions, Iteration, RuntimeState, Value,
+        apply_intrinsic_function,
     },
 };
 
@@ -11,18 +14,28 @@ pub struct Language {
     codebase: Codebase,
     editor: Editor,
     evaluator: Evaluator,
+    host_functions: HostFunctions,
+    types: Types,
 }
 
 impl Language {
     pub fn new() -> Self {
-        let codebase = Codebase::new();
+        Self::with_codebase(Codebase::new())
+    }
+
+    pub fn with_codebase(codebase: Codebase) -> Self {
         let editor = Editor::new(codebase.root().path, &codebase);
-        let evaluator = Evaluator::default();
+        let mut evaluator = Evaluator::default();
+        evaluator.reset(&codebase);
+        let host_functions = HostFunctions::default();
+        let types = Types::new(&codebase, &host_functions);
 
         Self {
             codebase,
             editor,
             evaluator,
+            host_functions,
+            types,
         }
     }
 
@@ -30,6 +43,23 @@ impl Language {
         &self.codebase
     }
 
+    /// # Register an observer that is notified of every change to the code
+    ///
+    /// See [`Codebase::subscribe`].
+    pub fn subscribe(
+        &mut self,
+        observer: impl FnMut(&CodebaseEvent) + 'static,
+    ) -> ObserverId {
+        self.codebase.subscribe(observer)
+    }
+
+    /// # Remove an observer that was registered before
+    ///
+    /// See [`Codebase::unsubscribe`].
+    pub fn unsubscribe(&mut self, id: ObserverId) -> bool {
+        self.codebase.unsubscribe(id)
+    }
+
     pub fn editor(&self) -> &Editor {
         &self.editor
     }
@@ -38,9 +68,38 @@ impl Language {
         &self.evaluator
     }
 
+    /// # Register the functions that the host provides
+    ///
+    /// Registered functions are taken into account by type inference, which
+    /// reports applications of unknown functions, or applications with the
+    /// wrong input. Their documentation is shown in the editor.
+    ///
+    /// Types are inferred again once all functions have been registered.
+    pub fn register_host_functions(
+        &mut self,
+        functions: impl IntoIterator<Item = HostFunction>,
+    ) {
+        for function in functions {
+            self.host_functions.register(function);
+        }
+        self.types = Types::new(&self.codebase, &self.host_functions);
+    }
+
+    pub fn host_functions(&self) -> &HostFunctions {
+        &self.host_functions
+    }
+
+    /// # The inferred types of the current code
+    ///
+    /// These are kept up to date with every change to the code.
+    pub fn types(&self) -> &Types {
+        &self.types
+    }
+
     pub fn on_editor_input(&mut self, input: EditorInput) {
         self.editor
             .on_input(input, &mut self.codebase, &mut self.evaluator);
+        self.types.update(&self.codebase, &self.host_functions);
     }
 
     pub fn on_editor_command(
@@ -52,15 +111,26 @@ impl Language {
             &mut self.codebase,
             &mut self.evaluator,
         )?;
+        self.types.update(&self.codebase, &self.host_functions);
 
         Ok(())
     }
 
-    pub fn apply_function(&mut self, body: NodePath) {
+    /// # Remove code that is no longer needed
+    ///
+    /// See [`Codebase::collect_garbage`].
+    pub fn collect_garbage(&mut self, retained_change_sets: usize) {
+        let live_roots = self.evaluator.roots();
+        self.codebase
+            .collect_garbage(retained_change_sets, live_roots);
+    }
+
+    pub fn apply_function(&mut self, body: NodePath, environment: Environment) {
         self.evaluator.apply_function(
             "".to_string(),
             body,
             Value::nothing(),
+            environment,
             self.codebase.nodes(),
         );
     }
@@ -68,24 +138,52 @@ impl Language {
     pub fn step(&mut self) -> &RuntimeState {
         self.evaluator.step(&self.codebase);
 
-        if let RuntimeState::Effect {
+        let RuntimeState::Effect {
             effect: Effect::ApplyProvidedFunction { name, input },
             ..
         } = self.evaluator.state()
-        {
-            match apply_intrinsic_function(name, input) {
-                Some(Ok(value)) => {
+        else {
+            return self.evaluator.state();
+        };
+
+        if let Some(result) = apply_intrinsic_function(name, input) {
+            match result {
+                Ok(value) => {
                     self.evaluator.exit_from_provided_function(value);
                 }
-                Some(Err(effect)) => {
+                Err(effect) => {
                     self.evaluator.trigger_effect(effect);
                 }
-                None => {
-                    // Function is not an intrinsic function and was not
-                    // handled. Nothing else to do here. The host can take
-                    // care of the effect.
+            }
+        } else if let Some(result) = Iteration::start(name, input) {
+            match result {
+                Ok(iteration) => {
+                    self.evaluator
+                        .exit_from_provided_function_into_iteration(iteration);
+                }
+                Err(effect) => {
+                    self.evaluator.trigger_effect(effect);
                 }
             }
+        } else if let Some(result) = EffectOperation::new(name, input) {
+            match result {
+                Ok(operation) => {
+                    self.evaluator
+                        .exit_from_provided_function_into_effect_operation(
+                            operation,
+                            &self.codebase,
+                        );
+                }
+                Err(effect) => {
+                    self.evaluator.trigger_effect(effect);
+                }
+            }
+        } else {
+            // Function is not an intrinsic function. Applying it raises an
+            // effect, which Crosscut code might handle. If it doesn't, the
+            // host can take care of it.
+            let (name, input) = (name.clone(), input.clone());
+            self.evaluator.raise_effect(name, input, &self.codebase);
         }
 
         self.evaluator.state()
@@ -103,12 +201,15 @@ impl Language {
 #[cfg(test)]
 impl Language {
     pub fn import(code: &str) -> Self {
-        super::import::import(code)
+        let codebase =
+            super::text::import(code).expect("Test code should be valid.");
+        Self::with_codebase(codebase)
     }
 
     pub fn code(&mut self, code: &str) -> &mut Self {
         self.editor
             .on_code(code, &mut self.codebase, &mut self.evaluator);
+        self.types.update(&self.codebase, &self.host_functions);
         self
     }
 
@@ -132,6 +233,16 @@ impl Language {
         self
     }
 
+    pub fn undo(&mut self) -> &mut Self {
+        self.on_editor_input(EditorInput::Undo);
+        self
+    }
+
+    pub fn redo(&mut self) -> &mut Self {
+        self.on_editor_input(EditorInput::Redo);
+        self
+    }
+
     pub fn remove_left(&mut self) -> &mut Self {
         self.on_editor_input(EditorInput::RemoveLeft { whole_node: false });
         self
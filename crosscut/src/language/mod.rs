@@ -1,11 +1,10 @@
 pub mod code;
 pub mod compiler;
 pub mod editor;
+pub mod inference;
 pub mod language;
 pub mod runtime;
-
-#[cfg(test)]
-mod import;
+pub mod text;
 
 #[cfg(test)]
 pub mod tests;
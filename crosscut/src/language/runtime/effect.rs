@@ -4,7 +4,44 @@ use super::Value;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Effect {
-    ApplyProvidedFunction { name: String, input: Value },
+    AbortOutsideOfHandler,
+    ApplyProvidedFunction {
+        name: String,
+        input: Value,
+    },
+    Arithmetic {
+        error: ArithmeticError,
+        input: Value,
+    },
+    FieldNotFound {
+        name: String,
+        record: Value,
+    },
+    IndexOutOfRange {
+        index: i32,
+        value: Value,
+    },
+    NoMatchingArm {
+        value: Value,
+    },
+    NumberOutOfRange {
+        value: Value,
+        target: Type,
+    },
     ProvidedFunctionNotFound,
-    UnexpectedInput { expected: Type, actual: Value },
+    UnexpectedInput {
+        expected: Type,
+        actual: Value,
+    },
+    UnresolvedConflict,
+}
+
+/// # An error that occurred while applying an arithmetic intrinsic
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArithmeticError {
+    /// # The second input of a division or remainder is zero
+    DivisionByZero,
+
+    /// # The result can't be represented by the type of the inputs
+    Overflow,
 }
@@ -1,9 +1,13 @@
 use std::collections::VecDeque;
 
+use itertools::Itertools;
+
 use crate::language::code::{
-    Body, Expression, NodePath, Nodes, SyntaxNode, TypedNode,
+    Body, Expression, LocatedNode, NodePath, Nodes, SyntaxNode, TypedNode,
 };
 
+use super::{Handler, Iteration, Pattern, Value};
+
 #[derive(Clone, Debug)]
 pub enum EvalStep {
     /// # An evaluation step that was derived from a syntax node
@@ -50,10 +54,18 @@ impl EvalStep {
 pub enum DerivedEvalStep {
     Apply { is_tail_call: bool },
     Body,
+    Conflict,
+    Definition,
     Empty,
+    FieldAccess { field: String },
+    FieldUpdate { fields: Vec<String> },
     Function { parameter: String, body: NodePath },
     Identifier { name: String },
-    Number { value: i32 },
+    Let { name: String },
+    List,
+    Match { arms: Vec<(Pattern, NodePath)> },
+    Literal { value: Value },
+    Record { fields: Vec<String> },
     Recursion,
     Tuple,
 }
@@ -102,7 +114,41 @@ impl DerivedEvalStep {
 
                 Self::Body
             }
+            Expression::Conflict => {
+                // The conflicting versions are not evaluated. Which one of
+                // them should be, is for the user to decide.
+                Self::Conflict
+            }
+            Expression::Definition => {
+                // The value of a definition is evaluated where it's referred
+                // to, not where it's defined.
+                Self::Definition
+            }
             Expression::Empty => Self::Empty,
+            Expression::FieldAccess { access } => {
+                eval_queue
+                    .push_front(access.record().into_path(path.clone(), nodes));
+
+                Self::FieldAccess {
+                    field: access.field(nodes).name,
+                }
+            }
+            Expression::FieldUpdate { update } => {
+                let (fields, values): (Vec<_>, Vec<_>) = fields(
+                    update.fields().into_path(path.clone(), nodes),
+                    nodes,
+                )
+                .into_iter()
+                .unzip();
+
+                for value in values.into_iter().rev() {
+                    eval_queue.push_front(value);
+                }
+                eval_queue
+                    .push_front(update.record().into_path(path.clone(), nodes));
+
+                Self::FieldUpdate { fields }
+            }
             Expression::Function { function } => {
                 let body = function.body().into_path(path, nodes);
                 let parameter = function.parameter.name;
@@ -112,7 +158,81 @@ impl DerivedEvalStep {
             Expression::Identifier { name } => {
                 Self::Identifier { name: name.clone() }
             }
-            Expression::Number { value } => Self::Number { value },
+            Expression::Let { let_ } => {
+                eval_queue.push_front(let_.value().into_path(path, nodes));
+
+                Self::Let {
+                    name: let_.binding.name,
+                }
+            }
+            Expression::Match { match_ } => {
+                let arms = match_.arms().into_path(path.clone(), nodes);
+                let arms = LocatedNode {
+                    node: nodes.get(arms.hash()),
+                    path: arms,
+                };
+
+                let arms = arms
+                    .children(nodes)
+                    .filter_map(|arm| {
+                        let [pattern, body] =
+                            arm.children(nodes).collect_array()?;
+                        let pattern =
+                            Pattern::from_hash(pattern.path.hash(), nodes);
+
+                        Some((pattern, body.path))
+                    })
+                    .collect();
+
+                eval_queue
+                    .push_front(match_.scrutinee().into_path(path, nodes));
+
+                Self::Match { arms }
+            }
+            Expression::Boolean { value } => Self::Literal {
+                value: Value::Boolean { value },
+            },
+            Expression::Float { value } => Self::Literal {
+                value: Value::Float { value },
+            },
+            Expression::Integer64 { value } => Self::Literal {
+                value: Value::Integer64 { value },
+            },
+            Expression::Number { value } => Self::Literal {
+                value: Value::Integer { value },
+            },
+            Expression::String { value } => Self::Literal {
+                value: Value::String { value },
+            },
+            Expression::Unsigned { value } => Self::Literal {
+                value: Value::Unsigned { value },
+            },
+            Expression::Record { record } => {
+                let (fields, values): (Vec<_>, Vec<_>) = fields(
+                    record.fields().into_path(path.clone(), nodes),
+                    nodes,
+                )
+                .into_iter()
+                .unzip();
+
+                for value in values.into_iter().rev() {
+                    eval_queue.push_front(value);
+                }
+
+                Self::Record { fields }
+            }
+            Expression::List { list } => {
+                let values = Body::from_hash(&list.values, nodes);
+                let parent = list.values().into_path(path.clone(), nodes);
+
+                for child_path in
+                    values.children().to_paths(&parent, nodes).rev()
+                {
+                    eval_queue.push_front(child_path);
+                }
+
+                Self::List
+            }
             Expression::Recursion => Self::Recursion,
             Expression::Tuple { tuple } => {
                 let values = Body::from_hash(&tuple.values, nodes);
@@ -130,7 +250,64 @@ impl DerivedEvalStep {
     }
 }
 
+/// # The fields defined in a body, with the paths of their values
+///
+/// Used for record literals and updates. Each field is a definition. Any other
+/// children of the body are not fields, and are ignored.
+fn fields(body: NodePath, nodes: &Nodes) -> Vec<(String, NodePath)> {
+    let body = LocatedNode {
+        node: nodes.get(body.hash()),
+        path: body,
+    };
+
+    body.children(nodes)
+        .filter_map(|definition| {
+            let SyntaxNode::Definition { name, .. } = definition.node else {
+                return None;
+            };
+            let SyntaxNode::Binding { name } = nodes.get(name) else {
+                return None;
+            };
+            let [_, value] = definition.children(nodes).collect_array()?;
+
+            Some((name.clone(), value.path))
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub enum SyntheticEvalStep {
+    /// # An effect handler that is installed around the step above it
+    ///
+    /// The path is the one of the apply node that applied `handle`.
+    Handler {
+        handler: Box<Handler>,
+        path: NodePath,
+    },
+
+    /// # A higher-order intrinsic function that is being applied
+    ///
+    /// The path is the one of the apply node that applied the function.
+    Iteration {
+        iteration: Box<Iteration>,
+        path: NodePath,
+    },
+
     PopStackFrame,
+
+    /// # Stands in for a provided function that a synthetic step applied
+    ///
+    /// Applying a provided function triggers an effect. Once the output of the
+    /// provided function is available, this step is removed from the stack, the
+    /// same way an apply node would be.
+    ProvidedFunction,
+
+    /// # Marks the application of a handler function
+    ///
+    /// Once the handler function is finished, its output becomes the output of
+    /// the application that raised the effect. The index refers to the
+    /// handler's step on the evaluation stack.
+    Resume {
+        handler: usize,
+    },
 }
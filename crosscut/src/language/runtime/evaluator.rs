@@ -1,12 +1,19 @@
-use std::collections::VecDeque;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    sync::Arc,
+};
 
 use itertools::Itertools;
 
-use crate::language::code::{Codebase, NodePath, Nodes, Type};
+use crate::language::code::{
+    Codebase, NodeHash, NodePath, Nodes, SyntaxNode, Type,
+};
 
 use super::{
-    Effect, RuntimeState, Value,
+    Effect, EffectOperation, Environment, Handler, Iteration, RuntimeState,
+    Value,
     eval_step::{DerivedEvalStep, EvalStep, SyntheticEvalStep},
+    iteration::IterationStep,
 };
 
 #[derive(Debug, Default)]
@@ -43,20 +50,36 @@ impl Evaluator {
             return;
         }
 
+        if let RuntimeState::Effect {
+            effect: Effect::UnresolvedConflict,
+            ..
+        } = self.state
+        {
+            // Evaluation can't get past an unresolved conflict. The change
+            // might have resolved it, so the best we can do is start over.
+            self.reset(codebase);
+            return;
+        }
+
         // The following update code is not complete. But neither is the test
         // coverage, so far.
 
         for path in &mut self.eval_queue {
             *path = codebase.latest_version_of(path).clone();
         }
+
+        // Steps on the stack are left as they are. They were derived from the
+        // old version of their node, and their paths must keep matching that.
+        // See `Evaluator::end_scope`, for how bindings still go out of scope.
     }
 
     pub fn reset(&mut self, codebase: &Codebase) {
         *self = Self::default();
         self.apply_function(
             "".to_string(),
-            codebase.root().path,
+            codebase.entry_point(),
             Value::nothing(),
+            Arc::new([]),
             codebase.nodes(),
         );
     }
@@ -66,6 +89,7 @@ impl Evaluator {
         parameter: String,
         body: NodePath,
         argument: Value,
+        environment: Environment,
         nodes: &Nodes,
     ) {
         self.eval_stack.push(EvalStep::derived(
@@ -77,6 +101,8 @@ impl Evaluator {
             parameter,
             argument,
             root: body,
+            environment,
+            bindings: Vec::new(),
         });
     }
 
@@ -107,6 +133,212 @@ impl Evaluator {
         self.finish_step(output);
     }
 
+    /// # Continue the application of a provided function as an iteration
+    ///
+    /// This is how higher-order intrinsic functions are applied. Instead of
+    /// providing their output right away, they apply other functions, step by
+    /// step, until the output is known.
+    pub fn exit_from_provided_function_into_iteration(
+        &mut self,
+        iteration: Iteration,
+    ) {
+        let path = self.take_provided_function_application("start iteration");
+
+        self.eval_stack.push(EvalStep::Synthetic {
+            step: SyntheticEvalStep::Iteration {
+                iteration: Box::new(iteration),
+                path,
+            },
+        });
+        self.state = RuntimeState::Running;
+    }
+
+    /// # Continue the application of a provided function as an effect operation
+    ///
+    /// See [`EffectOperation`].
+    pub fn exit_from_provided_function_into_effect_operation(
+        &mut self,
+        operation: EffectOperation,
+        codebase: &Codebase,
+    ) {
+        match operation {
+            EffectOperation::Handle {
+                name,
+                function,
+                body,
+            } => {
+                let path = self.take_provided_function_application("handle");
+
+                let handler = Handler {
+                    name,
+                    function,
+                    is_handling: false,
+                    call_stack_len: self.call_stack.len(),
+                    eval_queue_len: self.eval_queue.len(),
+                    evaluated_children_len: self.evaluated_children.len(),
+                };
+                self.eval_stack.push(EvalStep::Synthetic {
+                    step: SyntheticEvalStep::Handler {
+                        handler: Box::new(handler),
+                        path: path.clone(),
+                    },
+                });
+
+                self.state = RuntimeState::Running;
+                self.apply_value(body, Value::nothing(), path, codebase);
+            }
+            EffectOperation::Raise { name, payload } => {
+                self.raise_effect(name, payload, codebase);
+            }
+            EffectOperation::Abort { output } => {
+                self.abort(output);
+            }
+        }
+    }
+
+    /// # Raise an effect, to be handled by a matching handler
+    ///
+    /// This is expected to be called while a provided function is being
+    /// applied, which the effect replaces. If a handler matches the name of the
+    /// effect, the handler function is applied to the payload, and its output
+    /// becomes the output of the provided function.
+    ///
+    /// Otherwise, the effect is left to the host, in the form of an application
+    /// of a provided function with the name of the effect.
+    pub fn raise_effect(
+        &mut self,
+        name: String,
+        payload: Value,
+        codebase: &Codebase,
+    ) {
+        // Handlers that are already handling an effect are skipped. Otherwise,
+        // a handler function could not raise the effect it handles, to defer
+        // to an outer handler.
+        let handler = self.eval_stack.iter().rposition(|eval_step| {
+            matches!(
+                eval_step,
+                EvalStep::Synthetic {
+                    step: SyntheticEvalStep::Handler { handler, .. },
+                } if handler.name == name && !handler.is_handling
+            )
+        });
+
+        let Some(index) = handler else {
+            let Some(path) = self.state.path() else {
+                unreachable!(
+                    "An effect is only raised while a provided function is \
+                    being applied, which is a state that comes with a path."
+                );
+            };
+
+            self.state = RuntimeState::Effect {
+                effect: Effect::ApplyProvidedFunction {
+                    name,
+                    input: payload,
+                },
+                path: path.clone(),
+            };
+
+            return;
+        };
+
+        let path = self.take_provided_function_application("raise effect");
+
+        let EvalStep::Synthetic {
+            step: SyntheticEvalStep::Handler { handler, .. },
+        } = &mut self.eval_stack[index]
+        else {
+            unreachable!("Just found handler at this index.");
+        };
+        handler.is_handling = true;
+        let function = handler.function.clone();
+
+        self.eval_stack.push(EvalStep::Synthetic {
+            step: SyntheticEvalStep::Resume { handler: index },
+        });
+
+        self.state = RuntimeState::Running;
+        self.apply_value(function, payload, path, codebase);
+    }
+
+    /// # Abort from the handler function that is currently being applied
+    ///
+    /// Everything that was evaluated since the handler was installed, is
+    /// discarded. The provided output becomes the output of the application
+    /// of `handle`.
+    pub fn abort(&mut self, output: Value) {
+        let resume = self.eval_stack.iter().rev().find_map(|eval_step| {
+            if let EvalStep::Synthetic {
+                step: SyntheticEvalStep::Resume { handler },
+            } = eval_step
+            {
+                Some(*handler)
+            } else {
+                None
+            }
+        });
+
+        let Some(index) = resume else {
+            self.trigger_effect(Effect::AbortOutsideOfHandler);
+            return;
+        };
+
+        self.eval_stack.truncate(index + 1);
+        let Some(EvalStep::Synthetic {
+            step: SyntheticEvalStep::Handler { handler, .. },
+        }) = self.eval_stack.pop()
+        else {
+            unreachable!("Resume steps refer to the index of their handler.");
+        };
+
+        self.call_stack.truncate(handler.call_stack_len);
+        self.evaluated_children
+            .truncate(handler.evaluated_children_len);
+
+        // New steps are added to the front of the queue. So anything that was
+        // added since the handler was installed, is there.
+        let Some(added_to_queue) =
+            self.eval_queue.len().checked_sub(handler.eval_queue_len)
+        else {
+            unreachable!(
+                "The steps that were queued before the handler was installed \
+                are still in the queue, as the handler was still on the stack."
+            );
+        };
+        self.eval_queue.drain(..added_to_queue);
+
+        self.finish_step(output);
+    }
+
+    /// # Remove the step that applied a provided function from the stack
+    ///
+    /// Returns the path of the apply node that applied it.
+    fn take_provided_function_application(&mut self, action: &str) -> NodePath {
+        let RuntimeState::Effect {
+            effect: Effect::ApplyProvidedFunction { .. },
+            path,
+        } = &self.state
+        else {
+            panic!(
+                "Trying to {action}, but no provided function is currently \
+                being applied.\n\
+                \n\
+                Current state is `{:#?}`.",
+                self.state,
+            );
+        };
+        let path = path.clone();
+
+        let Some(_) = self.eval_stack.pop() else {
+            unreachable!(
+                "Effect has been triggered, but no node that could have \
+                triggered it is available."
+            );
+        };
+
+        path
+    }
+
     pub fn trigger_effect(&mut self, effect: Effect) {
         let Some(path) = self.state.path() else {
             panic!(
@@ -135,9 +367,14 @@ impl Evaluator {
             // Evaluation stack is empty, which means there's nothing we can do.
 
             if !self.state.is_finished() {
-                self.state = RuntimeState::Finished {
-                    output: Value::nothing(),
-                };
+                // If the last step was synthetic, the output of the step
+                // before that might still be waiting for it.
+                let output = self
+                    .evaluated_children
+                    .pop()
+                    .unwrap_or_else(Value::nothing);
+
+                self.state = RuntimeState::Finished { output };
             }
 
             return;
@@ -209,7 +446,11 @@ impl Evaluator {
                 };
 
                 match function {
-                    Value::Function { parameter, body } => {
+                    Value::Function {
+                        parameter,
+                        body,
+                        environment,
+                    } => {
                         if is_tail_call {
                             self.call_stack.pop();
                         } else {
@@ -222,6 +463,7 @@ impl Evaluator {
                             parameter.clone(),
                             body.clone(),
                             argument.clone(),
+                            environment.clone(),
                             codebase.nodes(),
                         );
                     }
@@ -251,44 +493,266 @@ impl Evaluator {
             }
             EvalStep::Derived {
                 step: DerivedEvalStep::Body,
+                ref path,
                 ..
             } => {
+                // Any names that were bound within the body go out of scope.
+                self.end_scope(codebase, path, |scope, body| scope == body);
+
                 let value =
                     evaluated_children.pop().unwrap_or_else(Value::nothing);
                 self.finish_step(value);
             }
             EvalStep::Derived {
+                step: DerivedEvalStep::Conflict,
+                ref path,
+                ..
+            } => {
+                self.state = RuntimeState::Effect {
+                    effect: Effect::UnresolvedConflict,
+                    path: path.clone(),
+                };
+                self.eval_stack.push(eval_step);
+            }
+            EvalStep::Derived {
+                step: DerivedEvalStep::Definition,
+                ..
+            }
+            | EvalStep::Derived {
                 step: DerivedEvalStep::Empty,
                 ..
             } => {
                 self.finish_step(Value::nothing());
             }
+            EvalStep::Derived {
+                step: DerivedEvalStep::FieldAccess { ref field },
+                ref path,
+                ..
+            } => {
+                let Some([record]) =
+                    evaluated_children.into_iter().collect_array()
+                else {
+                    unreachable!(
+                        "Field access nodes have exactly one child that is \
+                        evaluated. And unless it has been evaluated, the child \
+                        handling code above wouldn't have let us arrive here."
+                    );
+                };
+
+                if let Some(value) = record.field(field) {
+                    let value = value.clone();
+                    self.finish_step(value);
+                } else {
+                    self.field_not_found(field.clone(), record, path.clone());
+                    self.eval_stack.push(eval_step);
+                }
+            }
+            EvalStep::Derived {
+                step: DerivedEvalStep::FieldUpdate { ref fields },
+                ref path,
+                ..
+            } => {
+                // Like for records, the new values are evaluated as part of the
+                // field update, and their body's scope ends here.
+                self.end_scope(codebase, path, |scope, step| {
+                    scope.parent().map(|(parent, _)| parent) == Some(step)
+                });
+
+                let mut values = evaluated_children.into_iter();
+                let Some(mut record) = values.next() else {
+                    unreachable!(
+                        "The record that is updated is the first child of a \
+                        field update node. Unless it has been evaluated, the \
+                        child handling code above wouldn't have let us arrive \
+                        here."
+                    );
+                };
+
+                let missing_field =
+                    fields.iter().zip(values).find_map(|(field, value)| {
+                        if let Value::Record { fields } = &mut record
+                            && let Some((_, old_value)) = fields
+                                .iter_mut()
+                                .find(|(name, _)| name == field)
+                        {
+                            *old_value = value;
+                            None
+                        } else {
+                            Some(field)
+                        }
+                    });
+
+                if let Some(field) = missing_field {
+                    self.field_not_found(field.clone(), record, path.clone());
+                    self.eval_stack.push(eval_step);
+                } else {
+                    self.finish_step(record);
+                }
+            }
             EvalStep::Derived {
                 step: DerivedEvalStep::Function { parameter, body },
                 ..
             } => {
-                self.finish_step(Value::Function { parameter, body });
+                // The function captures the names that are in scope here, so
+                // it can refer to them wherever it is applied.
+                let environment = self
+                    .call_stack
+                    .last()
+                    .map(|stack_frame| stack_frame.capture())
+                    .unwrap_or_default();
+
+                self.finish_step(Value::Function {
+                    parameter,
+                    body,
+                    environment,
+                });
             }
             EvalStep::Derived {
                 step: DerivedEvalStep::Identifier { name },
                 ..
             } => {
-                let mut value = Value::ProvidedFunction { name: name.clone() };
+                // Names are resolved lexically. Only the current function can
+                // see its own bindings, and those it captured where it was
+                // defined.
+                let value = self
+                    .call_stack
+                    .last()
+                    .and_then(|stack_frame| stack_frame.resolve(&name))
+                    .cloned();
 
-                for stack_frame in self.call_stack.iter().rev() {
-                    if stack_frame.parameter == name {
-                        value = stack_frame.argument.clone();
-                        break;
-                    }
+                if let Some(value) = value {
+                    self.finish_step(value);
+                } else if let Some(value) = codebase.definition(&name) {
+                    // The identifier refers to a top-level definition. Its
+                    // value takes the place of the identifier, and whatever it
+                    // evaluates to is the output of this step.
+                    //
+                    // Top-level definitions don't see any local names, so
+                    // their value is evaluated in a stack frame of its own.
+                    self.eval_stack.push(EvalStep::Synthetic {
+                        step: SyntheticEvalStep::PopStackFrame,
+                    });
+                    self.apply_function(
+                        "".to_string(),
+                        value,
+                        Value::nothing(),
+                        Arc::new([]),
+                        codebase.nodes(),
+                    );
+                } else {
+                    self.finish_step(Value::ProvidedFunction { name });
+                }
+            }
+            EvalStep::Derived {
+                step: DerivedEvalStep::Let { name },
+                ref path,
+                ..
+            } => {
+                let Some([value]) =
+                    evaluated_children.into_iter().collect_array()
+                else {
+                    unreachable!(
+                        "Let nodes have exactly one child that is evaluated. \
+                        And unless it has been evaluated, the child handling \
+                        code above wouldn't have let us arrive here."
+                    );
+                };
+
+                // The name is visible to the following siblings within the
+                // enclosing body. It goes out of scope, once that is finished.
+                let scope = enclosing_body(path, codebase.nodes());
+
+                if let Some(scope) = scope
+                    && let Some(stack_frame) = self.call_stack.last_mut()
+                {
+                    stack_frame.bindings.push(LocalBinding {
+                        name,
+                        value,
+                        scope,
+                    });
                 }
 
+                self.finish_step(Value::nothing());
+            }
+            EvalStep::Derived {
+                step: DerivedEvalStep::Match { ref arms },
+                ref path,
+                ..
+            } => {
+                let Some([value]) =
+                    evaluated_children.into_iter().collect_array()
+                else {
+                    unreachable!(
+                        "Match nodes have exactly one child that is evaluated. \
+                        And unless it has been evaluated, the child handling \
+                        code above wouldn't have let us arrive here."
+                    );
+                };
+
+                let arm = arms.iter().find_map(|(pattern, body)| {
+                    pattern.bind(&value).map(|bindings| (bindings, body))
+                });
+
+                if let Some((bindings, body)) = arm {
+                    // The names that the pattern binds are visible in the
+                    // arm's body. The body of the arm takes the place of the
+                    // match, and whatever it evaluates to is the output of
+                    // this step.
+                    if let Some(stack_frame) = self.call_stack.last_mut() {
+                        stack_frame.bindings.extend(bindings.into_iter().map(
+                            |(name, value)| LocalBinding {
+                                name,
+                                value,
+                                scope: body.clone(),
+                            },
+                        ));
+                    }
+
+                    self.eval_stack.push(EvalStep::derived(
+                        body.clone(),
+                        &mut self.eval_queue,
+                        codebase.nodes(),
+                    ));
+                } else {
+                    self.state = RuntimeState::Effect {
+                        effect: Effect::NoMatchingArm { value },
+                        path: path.clone(),
+                    };
+                    self.eval_stack.push(eval_step);
+                }
+            }
+            EvalStep::Derived {
+                step: DerivedEvalStep::Literal { value },
+                ..
+            } => {
                 self.finish_step(value);
             }
             EvalStep::Derived {
-                step: DerivedEvalStep::Number { value },
+                step: DerivedEvalStep::Record { fields: names },
+                ref path,
                 ..
             } => {
-                self.finish_step(Value::Integer { value });
+                // The values of the fields are evaluated as part of the
+                // record, not its body. So that body's scope ends here.
+                self.end_scope(codebase, path, |scope, step| {
+                    scope.parent().map(|(parent, _)| parent) == Some(step)
+                });
+
+                let mut fields: Vec<(String, Value)> = Vec::new();
+
+                for (name, value) in names.into_iter().zip(evaluated_children) {
+                    // If a field is defined more than once, the last
+                    // definition wins.
+                    if let Some((_, old_value)) =
+                        fields.iter_mut().find(|(field, _)| field == &name)
+                    {
+                        *old_value = value;
+                    } else {
+                        fields.push((name, value));
+                    }
+                }
+
+                self.finish_step(Value::Record { fields });
             }
             EvalStep::Derived {
                 step: DerivedEvalStep::Recursion,
@@ -299,27 +763,178 @@ impl Evaluator {
                         StackFrame {
                             parameter: "".to_string(),
                             argument: Value::nothing(),
-                            root: codebase.root().path,
+                            root: codebase.entry_point(),
+                            environment: Arc::new([]),
+                            bindings: Vec::new(),
                         }
                     });
 
                 self.finish_step(Value::Function {
                     parameter: stack_frame.parameter,
                     body: stack_frame.root,
+                    environment: stack_frame.environment,
+                });
+            }
+            EvalStep::Derived {
+                step: DerivedEvalStep::List,
+                ref path,
+                ..
+            } => {
+                // Like for tuples, the values of the list are evaluated as
+                // part of the list, and their body's scope ends here.
+                self.end_scope(codebase, path, |scope, step| {
+                    scope.parent().map(|(parent, _)| parent) == Some(step)
                 });
+
+                let values = evaluated_children;
+                self.finish_step(Value::List { values });
             }
             EvalStep::Derived {
                 step: DerivedEvalStep::Tuple,
+                ref path,
                 ..
             } => {
+                // The values of the tuple are evaluated as part of the tuple,
+                // not their body. So that body's scope ends here.
+                self.end_scope(codebase, path, |scope, step| {
+                    scope.parent().map(|(parent, _)| parent) == Some(step)
+                });
+
                 let values = evaluated_children;
                 self.finish_step(Value::Tuple { values });
             }
+            EvalStep::Synthetic {
+                step:
+                    SyntheticEvalStep::Iteration {
+                        mut iteration,
+                        path,
+                    },
+            } => {
+                let previous_output = if iteration.is_applying() {
+                    self.evaluated_children.pop()
+                } else {
+                    None
+                };
+
+                match iteration.next(previous_output) {
+                    Ok(IterationStep::Apply { function, argument }) => {
+                        // The iteration stays on the stack below the
+                        // application, and receives its output once it's
+                        // finished.
+                        self.eval_stack.push(EvalStep::Synthetic {
+                            step: SyntheticEvalStep::Iteration {
+                                iteration,
+                                path: path.clone(),
+                            },
+                        });
+                        self.apply_value(function, argument, path, codebase);
+                    }
+                    Ok(IterationStep::Finish { output }) => {
+                        self.finish_step(output);
+                    }
+                    Err(effect) => {
+                        self.state = RuntimeState::Effect {
+                            effect,
+                            path: path.clone(),
+                        };
+                        self.eval_stack.push(EvalStep::Synthetic {
+                            step: SyntheticEvalStep::Iteration {
+                                iteration,
+                                path,
+                            },
+                        });
+                    }
+                }
+            }
+            EvalStep::Synthetic {
+                step: SyntheticEvalStep::Handler { .. },
+            } => {
+                // The function that the handler was installed around is
+                // finished. Its output is the output of `handle`.
+                let output = self
+                    .evaluated_children
+                    .pop()
+                    .unwrap_or_else(Value::nothing);
+                self.finish_step(output);
+            }
             EvalStep::Synthetic {
                 step: SyntheticEvalStep::PopStackFrame,
             } => {
                 self.call_stack.pop();
             }
+            EvalStep::Synthetic {
+                step: SyntheticEvalStep::ProvidedFunction,
+            } => {
+                unreachable!(
+                    "This step is removed, once the output of the provided \
+                    function is available. Until then, the evaluator is \
+                    stuck on the effect, and doesn't evaluate any steps."
+                );
+            }
+            EvalStep::Synthetic {
+                step: SyntheticEvalStep::Resume { handler },
+            } => {
+                if let Some(EvalStep::Synthetic {
+                    step: SyntheticEvalStep::Handler { handler, .. },
+                }) = self.eval_stack.get_mut(handler)
+                {
+                    handler.is_handling = false;
+                }
+
+                // The output of the handler function is the output of the
+                // application that raised the effect.
+                let output = self
+                    .evaluated_children
+                    .pop()
+                    .unwrap_or_else(Value::nothing);
+                self.finish_step(output);
+            }
+        }
+    }
+
+    /// # Apply a function on behalf of a synthetic step
+    ///
+    /// The synthetic step is expected to be on the stack already. It receives
+    /// the output of the function, once that is finished.
+    fn apply_value(
+        &mut self,
+        function: Value,
+        argument: Value,
+        path: NodePath,
+        codebase: &Codebase,
+    ) {
+        match function {
+            Value::Function {
+                parameter,
+                body,
+                environment,
+            } => {
+                self.eval_stack.push(EvalStep::Synthetic {
+                    step: SyntheticEvalStep::PopStackFrame,
+                });
+                self.apply_function(
+                    parameter,
+                    body,
+                    argument,
+                    environment,
+                    codebase.nodes(),
+                );
+            }
+            Value::ProvidedFunction { name } => {
+                self.state = RuntimeState::Effect {
+                    effect: Effect::ApplyProvidedFunction {
+                        name,
+                        input: argument,
+                    },
+                    path,
+                };
+                self.eval_stack.push(EvalStep::Synthetic {
+                    step: SyntheticEvalStep::ProvidedFunction,
+                });
+            }
+            value => {
+                self.unexpected_input(Type::Function, value, path);
+            }
         }
     }
 
@@ -335,6 +950,33 @@ impl Evaluator {
         };
     }
 
+    /// # Remove the let bindings whose scope ends with the provided step
+    ///
+    /// After an update, the step that ends a scope might still have the old
+    /// version of its path, while a binding has the new version of its scope,
+    /// or the other way around. So both are compared in their latest version.
+    fn end_scope(
+        &mut self,
+        codebase: &Codebase,
+        step: &NodePath,
+        has_ended: impl Fn(&NodePath, &NodePath) -> bool,
+    ) {
+        let step = codebase.latest_version_of(step);
+
+        if let Some(stack_frame) = self.call_stack.last_mut() {
+            stack_frame.bindings.retain(|binding| {
+                !has_ended(codebase.latest_version_of(&binding.scope), step)
+            });
+        }
+    }
+
+    fn field_not_found(&mut self, name: String, record: Value, path: NodePath) {
+        self.state = RuntimeState::Effect {
+            effect: Effect::FieldNotFound { name, record },
+            path,
+        };
+    }
+
     fn finish_step(&mut self, output: Value) {
         // When this is called, the current step has already been removed from
         // the stack.
@@ -350,6 +992,102 @@ impl Evaluator {
     pub fn state(&self) -> &RuntimeState {
         &self.state
     }
+
+    /// # The roots of all syntax trees that the evaluator refers to
+    ///
+    /// While running, the evaluator might hold on to nodes from older versions
+    /// of the code. Those must not be removed from the codebase.
+    pub fn roots(&self) -> BTreeSet<NodeHash> {
+        let mut paths = Vec::new();
+
+        for eval_step in &self.eval_stack {
+            match eval_step {
+                EvalStep::Derived { path, step, .. } => {
+                    paths.push(path);
+
+                    if let DerivedEvalStep::Function { body, .. } = step {
+                        paths.push(body);
+                    }
+                }
+                EvalStep::Synthetic {
+                    step: SyntheticEvalStep::Handler { handler, path },
+                } => {
+                    paths.push(path);
+                    paths.extend(handler.function.paths());
+                }
+                EvalStep::Synthetic {
+                    step: SyntheticEvalStep::Iteration { iteration, path },
+                } => {
+                    paths.push(path);
+                    paths.extend(iteration.paths());
+                }
+                EvalStep::Synthetic {
+                    step:
+                        SyntheticEvalStep::PopStackFrame
+                        | SyntheticEvalStep::ProvidedFunction
+                        | SyntheticEvalStep::Resume { .. },
+                } => {}
+            }
+        }
+        paths.extend(&self.eval_queue);
+        for value in &self.evaluated_children {
+            paths.extend(value.paths());
+        }
+        for stack_frame in &self.call_stack {
+            paths.push(&stack_frame.root);
+            paths.extend(stack_frame.argument.paths());
+            paths.extend(
+                stack_frame
+                    .environment
+                    .iter()
+                    .flat_map(|(_, value)| value.paths()),
+            );
+
+            for binding in &stack_frame.bindings {
+                paths.push(&binding.scope);
+                paths.extend(binding.value.paths());
+            }
+        }
+
+        match &self.state {
+            RuntimeState::Started | RuntimeState::Running => {}
+            RuntimeState::Effect { effect, path } => {
+                paths.push(path);
+
+                match effect {
+                    Effect::ApplyProvidedFunction {
+                        name: _,
+                        input: value,
+                    }
+                    | Effect::Arithmetic {
+                        error: _,
+                        input: value,
+                    }
+                    | Effect::FieldNotFound {
+                        name: _,
+                        record: value,
+                    }
+                    | Effect::IndexOutOfRange { index: _, value }
+                    | Effect::NoMatchingArm { value }
+                    | Effect::NumberOutOfRange { value, target: _ }
+                    | Effect::UnexpectedInput {
+                        expected: _,
+                        actual: value,
+                    } => {
+                        paths.extend(value.paths());
+                    }
+                    Effect::AbortOutsideOfHandler
+                    | Effect::ProvidedFunctionNotFound
+                    | Effect::UnresolvedConflict => {}
+                }
+            }
+            RuntimeState::Finished { output } => {
+                paths.extend(output.paths());
+            }
+        }
+
+        paths.into_iter().map(|path| *path.root_hash()).collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -357,6 +1095,77 @@ struct StackFrame {
     parameter: String,
     argument: Value,
     root: NodePath,
+
+    /// # The names that the function captured where it was defined
+    ///
+    /// The parameter shadows these.
+    environment: Environment,
+
+    /// # The names bound by let nodes, that are currently in scope
+    ///
+    /// Later bindings shadow earlier ones, as well as the parameter.
+    bindings: Vec<LocalBinding>,
+}
+
+impl StackFrame {
+    /// # The value of the provided name, if it is in scope
+    fn resolve(&self, name: &str) -> Option<&Value> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|binding| binding.name == name)
+            .map(|binding| &binding.value)
+            .or_else(|| (self.parameter == name).then_some(&self.argument))
+            .or_else(|| {
+                self.environment
+                    .iter()
+                    .rev()
+                    .find_map(|(other, value)| (other == name).then_some(value))
+            })
+    }
+
+    /// # All names that are currently in scope, for a function to capture
+    fn capture(&self) -> Environment {
+        self.environment
+            .iter()
+            .cloned()
+            .chain([(self.parameter.clone(), self.argument.clone())])
+            .chain(
+                self.bindings.iter().map(|binding| {
+                    (binding.name.clone(), binding.value.clone())
+                }),
+            )
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct LocalBinding {
+    name: String,
+    value: Value,
+
+    /// # The body that the binding is visible in
+    scope: NodePath,
+}
+
+/// # The path of the nearest body that encloses the provided node
+///
+/// Nodes like records and tuples contain a body, and end the scopes of the
+/// bindings within it, once they are finished. This only ends up as `None`, if
+/// the node is not within any body, which isn't the case for a valid syntax
+/// tree.
+fn enclosing_body(path: &NodePath, nodes: &Nodes) -> Option<NodePath> {
+    let mut current = path;
+
+    while let Some((parent, _)) = current.parent() {
+        if let SyntaxNode::Body { .. } = nodes.get(parent.hash()) {
+            return Some(parent.clone());
+        }
+
+        current = parent;
+    }
+
+    None
 }
 
 #[cfg(test)]
@@ -394,7 +1203,12 @@ mod tests {
 
         evaluator.step(&codebase);
         let RuntimeState::Finished {
-            output: Value::Function { parameter: _, body },
+            output:
+                Value::Function {
+                    parameter: _,
+                    body,
+                    environment: _,
+                },
         } = evaluator.state()
         else {
             panic!();
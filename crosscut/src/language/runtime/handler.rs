@@ -0,0 +1,135 @@
+use itertools::Itertools;
+
+use crate::language::code::Type;
+
+use super::{Effect, Value};
+
+/// # An effect handler that is installed around the application of a body
+///
+/// Effects are raised by applying provided functions that are not intrinsic,
+/// or explicitly through the `raise` intrinsic. In both cases, the name of the
+/// function or effect is used to find a matching handler, and the input
+/// becomes the handler function's argument.
+///
+/// The output of the handler function is the output of the application that
+/// raised the effect, and evaluation resumes from there. Alternatively, the
+/// handler function can apply `abort`, which finishes the application of
+/// `handle` right away, with the output provided to `abort`.
+///
+/// Effects that no handler matches, are left to the host.
+#[derive(Clone, Debug)]
+pub struct Handler {
+    /// # The name of the effect that this handler handles
+    pub name: String,
+
+    /// # The function that is applied to the payload of the effect
+    pub function: Value,
+
+    /// # Indicate whether the handler function is currently being applied
+    ///
+    /// While that is the case, effects raised by the handler function are not
+    /// handled by the same handler again.
+    pub is_handling: bool,
+
+    /// # The evaluator's state when the handler was installed
+    ///
+    /// Aborting needs to discard everything that happened since.
+    pub call_stack_len: usize,
+    pub eval_queue_len: usize,
+    pub evaluated_children_len: usize,
+}
+
+/// # An intrinsic function that installs, raises, or aborts from handlers
+///
+/// Unlike most other intrinsic functions, these need access to the evaluator,
+/// which is why they are handled separately.
+#[derive(Debug)]
+pub enum EffectOperation {
+    /// # Apply a function with a handler installed around it
+    ///
+    /// The input of `handle` is a tuple of the name of the effect, the handler
+    /// function, and the function that the handler is installed around. The
+    /// latter is applied to nothing.
+    Handle {
+        name: String,
+        function: Value,
+        body: Value,
+    },
+
+    /// # Raise a named effect with a payload
+    ///
+    /// The input of `raise` is a tuple of the name of the effect and its
+    /// payload.
+    Raise { name: String, payload: Value },
+
+    /// # Finish the application of the currently active handler's `handle`
+    Abort { output: Value },
+}
+
+impl EffectOperation {
+    /// # Create the operation that the provided function refers to
+    ///
+    /// Returns `None`, if no such function with the provided name exists.
+    pub fn new(name: &str, input: &Value) -> Option<Result<Self, Effect>> {
+        let operation = match name {
+            "abort" => Ok(Self::Abort {
+                output: input.clone(),
+            }),
+            "handle" => {
+                if let Value::Tuple { values } = input
+                    && let Some([Value::String { value: name }, function, body]) =
+                        values.iter().collect_array()
+                {
+                    Ok(Self::Handle {
+                        name: name.clone(),
+                        function: function.clone(),
+                        body: body.clone(),
+                    })
+                } else {
+                    Err(Effect::UnexpectedInput {
+                        expected: Type::Tuple {
+                            values: vec![
+                                Type::String,
+                                Type::Function,
+                                Type::Function,
+                            ],
+                        },
+                        actual: input.clone(),
+                    })
+                }
+            }
+            "raise" => {
+                if let Value::Tuple { values } = input
+                    && let Some([Value::String { value: name }, payload]) =
+                        values.iter().collect_array()
+                {
+                    Ok(Self::Raise {
+                        name: name.clone(),
+                        payload: payload.clone(),
+                    })
+                } else {
+                    // Any payload is accepted. Reporting the type of the one
+                    // that was actually provided, if any, is more helpful.
+                    let payload = match input {
+                        Value::Tuple { values } => values.get(1),
+                        _ => None,
+                    }
+                    .map(|payload| payload.type_())
+                    .unwrap_or_else(|| Type::Tuple { values: Vec::new() });
+
+                    Err(Effect::UnexpectedInput {
+                        expected: Type::Tuple {
+                            values: vec![Type::String, payload],
+                        },
+                        actual: input.clone(),
+                    })
+                }
+            }
+            _ => {
+                return None;
+            }
+        };
+
+        Some(operation)
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use crate::language::code::Type;
+
+/// # A function that the host provides to Crosscut code
+///
+/// Host functions are applied like any other provided function: Applying them
+/// triggers [`Effect::ApplyProvidedFunction`], and the host provides the
+/// output.
+///
+/// Type inference checks applications of registered host functions ahead of
+/// time, and the editor shows their documentation. Applying a name that is
+/// neither defined nor registered is reported as an error, although the host
+/// could still provide an output for it at runtime.
+///
+/// [`Effect::ApplyProvidedFunction`]: super::Effect::ApplyProvidedFunction
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HostFunction {
+    pub name: String,
+    pub parameter: Type,
+    pub output: Type,
+    pub documentation: String,
+}
+
+/// # The host functions that the host has registered
+#[derive(Clone, Debug, Default)]
+pub struct HostFunctions {
+    functions: BTreeMap<String, HostFunction>,
+}
+
+impl HostFunctions {
+    /// # Register a host function
+    ///
+    /// Replaces any function that was registered under the same name before.
+    pub fn register(&mut self, function: HostFunction) {
+        self.functions.insert(function.name.clone(), function);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&HostFunction> {
+        self.functions.get(name)
+    }
+}
@@ -1,56 +1,755 @@
+use std::cmp::Ordering;
+
 use itertools::Itertools;
 
-use crate::language::code::Type;
+use crate::language::code::{Float, Type};
 
-use super::{Effect, Value};
+use super::{ArithmeticError, Effect, Value};
 
 pub fn apply_intrinsic_function(
     name: &str,
     input: &Value,
 ) -> Option<Result<Value, Effect>> {
     match name {
-        "+" => {
-            if let Value::Tuple { values } = &input {
-                if let Some([a, b]) = values.iter().collect_array() {
-                    if let [
-                        Value::Integer { value: a },
-                        Value::Integer { value: b },
-                    ] = [a, b]
-                    {
-                        return Some(Ok(Value::Integer { value: *a + *b }));
-                    }
-                }
+        "!=" => Some(equality(input, |equal| !equal)),
+        "%" => Some(arithmetic(input, REM)),
+        "*" => Some(arithmetic(input, MUL)),
+        "+" => Some(arithmetic(input, ADD)),
+        "-" => Some(arithmetic(input, SUB)),
+        "/" => Some(arithmetic(input, DIV)),
+        "<" => Some(comparison(input, Ordering::is_lt)),
+        "<=" => Some(comparison(input, Ordering::is_le)),
+        "==" => Some(equality(input, |equal| equal)),
+        ">" => Some(comparison(input, Ordering::is_gt)),
+        ">=" => Some(comparison(input, Ordering::is_ge)),
+        "and" => Some(logic(input, |a, b| a && b)),
+        "bit_and" => Some(arithmetic(input, BIT_AND)),
+        "bit_not" => Some(bit_not(input)),
+        "bit_or" => Some(arithmetic(input, BIT_OR)),
+        "bit_xor" => Some(arithmetic(input, BIT_XOR)),
+        "concat" => Some(concat(input)),
+        "drop" => Some(Ok(Value::nothing())),
+        "get_item" => Some(get_item(input)),
+        "identity" => Some(Ok(input.clone())),
+        "length" => Some(length(input)),
+        "negate" => Some(negate(input)),
+        "not" => Some(not(input)),
+        "or" => Some(logic(input, |a, b| a || b)),
+        "pop" => Some(pop(input)),
+        "push" => Some(push(input)),
+        "saturating_add" => Some(arithmetic(input, SATURATING_ADD)),
+        "saturating_mul" => Some(arithmetic(input, SATURATING_MUL)),
+        "saturating_sub" => Some(arithmetic(input, SATURATING_SUB)),
+        "set_item" => Some(set_item(input)),
+        "shift_left" => Some(arithmetic(input, SHIFT_LEFT)),
+        "shift_right" => Some(arithmetic(input, SHIFT_RIGHT)),
+        "slice" => Some(slice(input)),
+        "to_float" => Some(convert(input, Type::Float)),
+        "to_integer" => Some(convert(input, Type::Integer)),
+        "to_integer64" => Some(convert(input, Type::Integer64)),
+        "to_string" => Some(to_string(input)),
+        "to_unsigned" => Some(convert(input, Type::Unsigned)),
+        "wrapping_add" => Some(arithmetic(input, WRAPPING_ADD)),
+        "wrapping_mul" => Some(arithmetic(input, WRAPPING_MUL)),
+        "wrapping_sub" => Some(arithmetic(input, WRAPPING_SUB)),
+        _ => None,
+    }
+}
+
+/// # An arithmetic operation on two numbers of the same type
+///
+/// The integer operations return `None`, if the result can't be represented.
+/// Operations that have no floating-point variant, like wrapping or bitwise
+/// ones, only accept integers.
+struct Operation {
+    integer: fn(i32, i32) -> Option<i32>,
+    integer64: fn(i64, i64) -> Option<i64>,
+    unsigned: fn(u32, u32) -> Option<u32>,
+    float: Option<fn(f64, f64) -> f64>,
+}
+
+const ADD: Operation = Operation {
+    integer: i32::checked_add,
+    integer64: i64::checked_add,
+    unsigned: u32::checked_add,
+    float: Some(|a, b| a + b),
+};
+const SUB: Operation = Operation {
+    integer: i32::checked_sub,
+    integer64: i64::checked_sub,
+    unsigned: u32::checked_sub,
+    float: Some(|a, b| a - b),
+};
+const MUL: Operation = Operation {
+    integer: i32::checked_mul,
+    integer64: i64::checked_mul,
+    unsigned: u32::checked_mul,
+    float: Some(|a, b| a * b),
+};
+const DIV: Operation = Operation {
+    integer: i32::checked_div,
+    integer64: i64::checked_div,
+    unsigned: u32::checked_div,
+    float: Some(|a, b| a / b),
+};
+const REM: Operation = Operation {
+    integer: i32::checked_rem,
+    integer64: i64::checked_rem,
+    unsigned: u32::checked_rem,
+    float: Some(|a, b| a % b),
+};
+const SATURATING_ADD: Operation = Operation {
+    integer: |a, b| Some(a.saturating_add(b)),
+    integer64: |a, b| Some(a.saturating_add(b)),
+    unsigned: |a, b| Some(a.saturating_add(b)),
+    float: None,
+};
+const SATURATING_SUB: Operation = Operation {
+    integer: |a, b| Some(a.saturating_sub(b)),
+    integer64: |a, b| Some(a.saturating_sub(b)),
+    unsigned: |a, b| Some(a.saturating_sub(b)),
+    float: None,
+};
+const SATURATING_MUL: Operation = Operation {
+    integer: |a, b| Some(a.saturating_mul(b)),
+    integer64: |a, b| Some(a.saturating_mul(b)),
+    unsigned: |a, b| Some(a.saturating_mul(b)),
+    float: None,
+};
+const WRAPPING_ADD: Operation = Operation {
+    integer: |a, b| Some(a.wrapping_add(b)),
+    integer64: |a, b| Some(a.wrapping_add(b)),
+    unsigned: |a, b| Some(a.wrapping_add(b)),
+    float: None,
+};
+const WRAPPING_SUB: Operation = Operation {
+    integer: |a, b| Some(a.wrapping_sub(b)),
+    integer64: |a, b| Some(a.wrapping_sub(b)),
+    unsigned: |a, b| Some(a.wrapping_sub(b)),
+    float: None,
+};
+const WRAPPING_MUL: Operation = Operation {
+    integer: |a, b| Some(a.wrapping_mul(b)),
+    integer64: |a, b| Some(a.wrapping_mul(b)),
+    unsigned: |a, b| Some(a.wrapping_mul(b)),
+    float: None,
+};
+const BIT_AND: Operation = Operation {
+    integer: |a, b| Some(a & b),
+    integer64: |a, b| Some(a & b),
+    unsigned: |a, b| Some(a & b),
+    float: None,
+};
+const BIT_OR: Operation = Operation {
+    integer: |a, b| Some(a | b),
+    integer64: |a, b| Some(a | b),
+    unsigned: |a, b| Some(a | b),
+    float: None,
+};
+const BIT_XOR: Operation = Operation {
+    integer: |a, b| Some(a ^ b),
+    integer64: |a, b| Some(a ^ b),
+    unsigned: |a, b| Some(a ^ b),
+    float: None,
+};
+const SHIFT_LEFT: Operation = Operation {
+    integer: |a, b| a.checked_shl(b.try_into().ok()?),
+    integer64: |a, b| a.checked_shl(b.try_into().ok()?),
+    unsigned: |a, b| a.checked_shl(b),
+    float: None,
+};
+const SHIFT_RIGHT: Operation = Operation {
+    integer: |a, b| a.checked_shr(b.try_into().ok()?),
+    integer64: |a, b| a.checked_shr(b.try_into().ok()?),
+    unsigned: |a, b| a.checked_shr(b),
+    float: None,
+};
+
+/// # Apply an arithmetic operation to a tuple of two numbers
+///
+/// Both numbers must be of the same type.
+fn arithmetic(input: &Value, operation: Operation) -> Result<Value, Effect> {
+    let accepts_float = operation.float.is_some();
+    let accepts = |type_: &Type| {
+        is_integer(type_) || (accepts_float && type_ == &Type::Float)
+    };
+
+    let Some((a, b)) = same_type_pair(input, accepts) else {
+        return Err(unexpected_pair(input, accepts, Type::Integer));
+    };
+
+    let value = match (a, b) {
+        (Value::Float { value: a }, Value::Float { value: b }) => {
+            operation.float.map(|float| Value::Float {
+                value: Float::new(float(a.value(), b.value())),
+            })
+        }
+        (Value::Integer { value: a }, Value::Integer { value: b }) => {
+            (operation.integer)(*a, *b).map(|value| Value::Integer { value })
+        }
+        (Value::Integer64 { value: a }, Value::Integer64 { value: b }) => {
+            (operation.integer64)(*a, *b)
+                .map(|value| Value::Integer64 { value })
+        }
+        (Value::Unsigned { value: a }, Value::Unsigned { value: b }) => {
+            (operation.unsigned)(*a, *b).map(|value| Value::Unsigned { value })
+        }
+        _ => {
+            unreachable!("Pair of numbers has already been checked.");
+        }
+    };
+
+    value.ok_or_else(|| {
+        // None of the operations fail with a second input of zero, except for
+        // division and remainder.
+        let is_zero = matches!(
+            b,
+            Value::Integer { value: 0 }
+                | Value::Integer64 { value: 0 }
+                | Value::Unsigned { value: 0 }
+        );
+        let error = if is_zero {
+            ArithmeticError::DivisionByZero
+        } else {
+            ArithmeticError::Overflow
+        };
+
+        Effect::Arithmetic {
+            error,
+            input: input.clone(),
+        }
+    })
+}
+
+/// # Compare a tuple of two numbers
+///
+/// Both numbers must be of the same type. Floating-point numbers that are not
+/// comparable, because one of them is not a number, are neither less, nor
+/// greater than, nor equal to each other.
+fn comparison(
+    input: &Value,
+    is_true: fn(Ordering) -> bool,
+) -> Result<Value, Effect> {
+    let Some((a, b)) = same_type_pair(input, is_number) else {
+        return Err(unexpected_pair(input, is_number, Type::Integer));
+    };
+
+    let ordering = match (a, b) {
+        (Value::Float { value: a }, Value::Float { value: b }) => {
+            a.value().partial_cmp(&b.value())
+        }
+        (Value::Integer { value: a }, Value::Integer { value: b }) => {
+            Some(a.cmp(b))
+        }
+        (Value::Integer64 { value: a }, Value::Integer64 { value: b }) => {
+            Some(a.cmp(b))
+        }
+        (Value::Unsigned { value: a }, Value::Unsigned { value: b }) => {
+            Some(a.cmp(b))
+        }
+        _ => {
+            unreachable!("Pair of numbers has already been checked.");
+        }
+    };
+
+    Ok(Value::Boolean {
+        value: ordering.is_some_and(is_true),
+    })
+}
+
+/// # Determine whether two values of the same type are equal
+///
+/// Floating-point numbers follow the usual rules, under which a number that is
+/// not a number is not equal to anything, including itself. That includes
+/// numbers within tuples, lists, and records. All other values are equal, if
+/// they have the same structure.
+fn equality(input: &Value, is_true: fn(bool) -> bool) -> Result<Value, Effect> {
+    let Some((a, b)) = same_type_pair(input, |_| true) else {
+        return Err(unexpected_pair(input, |_| true, Type::Integer));
+    };
+
+    Ok(Value::Boolean {
+        value: is_true(is_equal(a, b)),
+    })
+}
+
+fn is_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float { value: a }, Value::Float { value: b }) => {
+            a.value() == b.value()
+        }
+        (Value::List { values: a }, Value::List { values: b })
+        | (Value::Tuple { values: a }, Value::Tuple { values: b }) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| is_equal(a, b))
+        }
+        (Value::Record { fields: a }, Value::Record { fields: b }) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|((name_a, a), (name_b, b))| {
+                    name_a == name_b && is_equal(a, b)
+                })
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn logic(
+    input: &Value,
+    operation: fn(bool, bool) -> bool,
+) -> Result<Value, Effect> {
+    let is_boolean = |type_: &Type| type_ == &Type::Boolean;
+
+    let Some((Value::Boolean { value: a }, Value::Boolean { value: b })) =
+        same_type_pair(input, is_boolean)
+    else {
+        return Err(unexpected_pair(input, is_boolean, Type::Boolean));
+    };
+
+    Ok(Value::Boolean {
+        value: operation(*a, *b),
+    })
+}
+
+fn negate(input: &Value) -> Result<Value, Effect> {
+    let value = match input {
+        Value::Float { value } => Some(Value::Float {
+            value: Float::new(-value.value()),
+        }),
+        Value::Integer { value } => {
+            value.checked_neg().map(|value| Value::Integer { value })
+        }
+        Value::Integer64 { value } => {
+            value.checked_neg().map(|value| Value::Integer64 { value })
+        }
+        _ => {
+            return Err(Effect::UnexpectedInput {
+                expected: Type::Integer,
+                actual: input.clone(),
+            });
+        }
+    };
+
+    value.ok_or_else(|| Effect::Arithmetic {
+        error: ArithmeticError::Overflow,
+        input: input.clone(),
+    })
+}
+
+fn not(input: &Value) -> Result<Value, Effect> {
+    let Value::Boolean { value } = input else {
+        return Err(Effect::UnexpectedInput {
+            expected: Type::Boolean,
+            actual: input.clone(),
+        });
+    };
+
+    Ok(Value::Boolean { value: !value })
+}
+
+fn bit_not(input: &Value) -> Result<Value, Effect> {
+    match input {
+        Value::Integer { value } => Ok(Value::Integer { value: !value }),
+        Value::Integer64 { value } => Ok(Value::Integer64 { value: !value }),
+        Value::Unsigned { value } => Ok(Value::Unsigned { value: !value }),
+        _ => Err(Effect::UnexpectedInput {
+            expected: Type::Integer,
+            actual: input.clone(),
+        }),
+    }
+}
+
+/// # Concatenate two strings or two lists
+fn concat(input: &Value) -> Result<Value, Effect> {
+    let is_collection =
+        |type_: &Type| matches!(type_, Type::List | Type::String);
+
+    let value = match same_type_pair(input, is_collection) {
+        Some((Value::List { values: a }, Value::List { values: b })) => {
+            Value::List {
+                values: a.iter().chain(b).cloned().collect(),
             }
+        }
+        Some((Value::String { value: a }, Value::String { value: b })) => {
+            Value::String {
+                value: format!("{a}{b}"),
+            }
+        }
+        _ => {
+            return Err(unexpected_pair(input, is_collection, collection()));
+        }
+    };
+
+    Ok(value)
+}
+
+/// # The number of characters in a string, or items in a list
+fn length(input: &Value) -> Result<Value, Effect> {
+    let length = match input {
+        Value::List { values } => values.len(),
+        Value::String { value } => value.chars().count(),
+        _ => {
+            return Err(Effect::UnexpectedInput {
+                expected: collection(),
+                actual: input.clone(),
+            });
+        }
+    };
+
+    let length = length.try_into().map_err(|_| Effect::Arithmetic {
+        error: ArithmeticError::Overflow,
+        input: input.clone(),
+    })?;
+
+    Ok(Value::Integer { value: length })
+}
+
+/// # The part of a string or list, from a start index up to an end index
+///
+/// The start index is inclusive, the end index exclusive. Strings are indexed
+/// by character.
+fn slice(input: &Value) -> Result<Value, Effect> {
+    if let Value::Tuple { values } = input
+        && let Some(
+            [
+                collection @ (Value::List { .. } | Value::String { .. }),
+                Value::Integer { value: start },
+                Value::Integer { value: end },
+            ],
+        ) = values.iter().collect_array()
+    {
+        return slice_collection(collection, *start, *end);
+    }
 
-            Some(Err(Effect::UnexpectedInput {
-                expected: Type::Tuple {
-                    values: vec![Type::Integer, Type::Integer],
+    let collection = match input {
+        Value::Tuple { values }
+            if let Some(Value::String { .. }) = values.first() =>
+        {
+            Type::String
+        }
+        _ => Type::List,
+    };
+
+    Err(Effect::UnexpectedInput {
+        expected: Type::Tuple {
+            values: vec![collection, Type::Integer, Type::Integer],
+        },
+        actual: input.clone(),
+    })
+}
+
+fn slice_collection(
+    collection: &Value,
+    start: i32,
+    end: i32,
+) -> Result<Value, Effect> {
+    let length = match collection {
+        Value::List { values } => values.len(),
+        Value::String { value } => value.chars().count(),
+        _ => {
+            unreachable!("Collection has already been checked.");
+        }
+    };
+
+    // The end of the range may be equal to the length, so both indices are
+    // checked against the length plus one.
+    let start_index = index(start, length + 1, collection)?;
+    let end_index = index(end, length + 1, collection)?;
+
+    if start_index > end_index {
+        return Err(Effect::IndexOutOfRange {
+            index: start,
+            value: collection.clone(),
+        });
+    }
+
+    let value = match collection {
+        Value::List { values } => Value::List {
+            values: values[start_index..end_index].to_vec(),
+        },
+        Value::String { value } => Value::String {
+            value: value
+                .chars()
+                .skip(start_index)
+                .take(end_index - start_index)
+                .collect(),
+        },
+        _ => {
+            unreachable!("Collection has already been checked.");
+        }
+    };
+
+    Ok(value)
+}
+
+/// # Add an item to the end of a list
+fn push(input: &Value) -> Result<Value, Effect> {
+    if let Value::Tuple { values } = input
+        && let Some([Value::List { values: list }, item]) =
+            values.iter().collect_array()
+    {
+        let mut list = list.clone();
+        list.push(item.clone());
+
+        return Ok(Value::List { values: list });
+    }
+
+    Err(unexpected_list_input(input, vec![item_type(input, 1)]))
+}
+
+/// # Remove the last item from a list
+///
+/// Returns a tuple of the remaining list and the removed item.
+fn pop(input: &Value) -> Result<Value, Effect> {
+    let Value::List { values } = input else {
+        return Err(Effect::UnexpectedInput {
+            expected: Type::List,
+            actual: input.clone(),
+        });
+    };
+
+    let mut list = values.clone();
+    let Some(item) = list.pop() else {
+        // There is no last item, whose index would be one less than the
+        // length of the list.
+        return Err(Effect::IndexOutOfRange {
+            index: -1,
+            value: input.clone(),
+        });
+    };
+
+    Ok(Value::Tuple {
+        values: vec![Value::List { values: list }, item],
+    })
+}
+
+/// # Access the item at an index of a list
+fn get_item(input: &Value) -> Result<Value, Effect> {
+    if let Value::Tuple { values } = input
+        && let Some([list @ Value::List { values }, Value::Integer { value }]) =
+            values.iter().collect_array()
+    {
+        let index = index(*value, values.len(), list)?;
+        return Ok(values[index].clone());
+    }
+
+    Err(unexpected_list_input(input, vec![Type::Integer]))
+}
+
+/// # Replace the item at an index of a list
+fn set_item(input: &Value) -> Result<Value, Effect> {
+    if let Value::Tuple { values } = input
+        && let Some(
+            [
+                list @ Value::List { values },
+                Value::Integer { value },
+                item,
+            ],
+        ) = values.iter().collect_array()
+    {
+        let index = index(*value, values.len(), list)?;
+
+        let mut values = values.clone();
+        values[index] = item.clone();
+
+        return Ok(Value::List { values });
+    }
+
+    Err(unexpected_list_input(
+        input,
+        vec![Type::Integer, item_type(input, 2)],
+    ))
+}
+
+/// # Check that an index is smaller than the length of a collection
+fn index(
+    index: i32,
+    length: usize,
+    collection: &Value,
+) -> Result<usize, Effect> {
+    usize::try_from(index)
+        .ok()
+        .filter(|i| *i < length)
+        .ok_or_else(|| Effect::IndexOutOfRange {
+            index,
+            value: collection.clone(),
+        })
+}
+
+/// # Report that the input is not a tuple of a list and further values
+fn unexpected_list_input(input: &Value, further: Vec<Type>) -> Effect {
+    let mut values = vec![Type::List];
+    values.extend(further);
+
+    Effect::UnexpectedInput {
+        expected: Type::Tuple { values },
+        actual: input.clone(),
+    }
+}
+
+/// # The type of a tuple's value, which any value is acceptable for
+///
+/// Since the value is acceptable, whatever it is, this is used to report the
+/// value's own type as the expected one.
+fn item_type(input: &Value, index: usize) -> Type {
+    if let Value::Tuple { values } = input
+        && let Some(value) = values.get(index)
+    {
+        value.type_()
+    } else {
+        Type::Tuple { values: Vec::new() }
+    }
+}
+
+/// # Convert a number or boolean into a string
+fn to_string(input: &Value) -> Result<Value, Effect> {
+    let value = match input {
+        Value::Boolean { value } => value.to_string(),
+        Value::Float { value } => value.to_string(),
+        Value::Integer { value } => value.to_string(),
+        Value::Integer64 { value } => value.to_string(),
+        Value::Unsigned { value } => value.to_string(),
+        _ => {
+            return Err(Effect::UnexpectedInput {
+                expected: Type::OneOf {
+                    types: vec![
+                        Type::Boolean,
+                        Type::Float,
+                        Type::Integer,
+                        Type::Integer64,
+                        Type::Unsigned,
+                    ],
                 },
                 actual: input.clone(),
-            }))
-        }
-        "-" => {
-            if let Value::Tuple { values } = &input {
-                if let Some([a, b]) = values.iter().collect_array() {
-                    if let [
-                        Value::Integer { value: a },
-                        Value::Integer { value: b },
-                    ] = [a, b]
-                    {
-                        return Some(Ok(Value::Integer { value: *a - *b }));
-                    }
-                }
+            });
+        }
+    };
+
+    Ok(Value::String { value })
+}
+
+/// # Convert a number into a number of the target type
+///
+/// Converting a floating-point number into an integer drops its fractional
+/// part. Converting a large integer into a floating-point number might round
+/// it. Any other conversion is exact, or results in an effect, if the number
+/// can't be represented by the target type.
+fn convert(input: &Value, target: Type) -> Result<Value, Effect> {
+    let out_of_range = || Effect::NumberOutOfRange {
+        value: input.clone(),
+        target: target.clone(),
+    };
+
+    // A type that can represent all integers that can be converted.
+    let integer = match input {
+        Value::Float { value } => {
+            if let Type::Float = target {
+                return Ok(input.clone());
             }
 
-            Some(Err(Effect::UnexpectedInput {
-                expected: Type::Tuple {
-                    values: vec![Type::Integer, Type::Integer],
-                },
+            let value = value.value().trunc();
+            if !value.is_finite() {
+                return Err(out_of_range());
+            }
+
+            // This saturates, but any saturated value is out of range for the
+            // target types anyway.
+            value as i128
+        }
+        Value::Integer { value } => i128::from(*value),
+        Value::Integer64 { value } => i128::from(*value),
+        Value::Unsigned { value } => i128::from(*value),
+        _ => {
+            return Err(Effect::UnexpectedInput {
+                expected: Type::Integer,
                 actual: input.clone(),
-            }))
+            });
         }
-        "drop" => Some(Ok(Value::nothing())),
-        "identity" => Some(Ok(input.clone())),
-        _ => None,
+    };
+
+    let value = match target {
+        Type::Float => Value::Float {
+            value: Float::new(integer as f64),
+        },
+        Type::Integer => Value::Integer {
+            value: integer.try_into().map_err(|_| out_of_range())?,
+        },
+        Type::Integer64 => Value::Integer64 {
+            value: integer.try_into().map_err(|_| out_of_range())?,
+        },
+        Type::Unsigned => Value::Unsigned {
+            value: integer.try_into().map_err(|_| out_of_range())?,
+        },
+        Type::Boolean
+        | Type::Function
+        | Type::List
+        | Type::OneOf { .. }
+        | Type::Record { .. }
+        | Type::String
+        | Type::Tuple { .. } => {
+            unreachable!("Conversion target `{target}` is not a number type.");
+        }
+    };
+
+    Ok(value)
+}
+
+/// # Access the values of a pair, if they are of the same, accepted type
+fn same_type_pair(
+    input: &Value,
+    accepts: impl Fn(&Type) -> bool,
+) -> Option<(&Value, &Value)> {
+    let Value::Tuple { values } = input else {
+        return None;
+    };
+    let [a, b] = values.iter().collect_array()?;
+
+    let type_ = a.type_();
+    (accepts(&type_) && b.type_() == type_).then_some((a, b))
+}
+
+/// # Report that the input is not a pair of values of the same, accepted type
+///
+/// If the first value is of an accepted type, the second one is expected to
+/// be of the same type. Otherwise, both are expected to be of type `expected`.
+/// If that is one of several types, so is the pair.
+fn unexpected_pair(
+    input: &Value,
+    accepts: impl Fn(&Type) -> bool,
+    expected: Type,
+) -> Effect {
+    let pair = |type_: Type| Type::Tuple {
+        values: vec![type_.clone(), type_],
+    };
+
+    let expected = if let Value::Tuple { values } = input
+        && let Some(value) = values.first()
+        && accepts(&value.type_())
+    {
+        pair(value.type_())
+    } else if let Type::OneOf { types } = expected {
+        Type::OneOf {
+            types: types.into_iter().map(pair).collect(),
+        }
+    } else {
+        pair(expected)
+    };
+
+    Effect::UnexpectedInput {
+        expected,
+        actual: input.clone(),
+    }
+}
+
+/// # The types of values that contain a sequence of items
+fn collection() -> Type {
+    Type::OneOf {
+        types: vec![Type::List, Type::String],
     }
 }
+
+fn is_integer(type_: &Type) -> bool {
+    matches!(type_, Type::Integer | Type::Integer64 | Type::Unsigned)
+}
+
+fn is_number(type_: &Type) -> bool {
+    is_integer(type_) || type_ == &Type::Float
+}
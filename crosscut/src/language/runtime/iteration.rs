@@ -0,0 +1,276 @@
+use std::collections::VecDeque;
+
+use itertools::Itertools;
+
+use crate::language::code::{NodePath, Type};
+
+use super::{Effect, Value};
+
+/// # The application of a higher-order intrinsic function
+///
+/// Higher-order intrinsic functions, like `map`, apply a function that was
+/// provided to them to each item of a collection. Those applications happen on
+/// the evaluator, step by step, just like any other function application. This
+/// keeps them interruptible and debuggable.
+///
+/// This type tracks the progress of such an intrinsic function. The evaluator
+/// asks it for the next application, and hands it the output of the previous
+/// one, until the iteration is finished.
+#[derive(Clone, Debug)]
+pub struct Iteration {
+    kind: IterationKind,
+    function: Value,
+    collection: Collection,
+    items: VecDeque<Value>,
+
+    /// # The item that the function is currently being applied to
+    current: Option<Value>,
+
+    /// # The items of the resulting collection, as far as they are known
+    output: Vec<Value>,
+}
+
+impl Iteration {
+    /// # Start the application of a higher-order intrinsic function
+    ///
+    /// Returns `None`, if no such function with the provided name exists.
+    pub fn start(name: &str, input: &Value) -> Option<Result<Self, Effect>> {
+        let kind = match name {
+            "filter" => IterationKind::Filter,
+            "fold" => IterationKind::Fold {
+                // This is a placeholder. The initial value is part of the
+                // input, and replaces it below.
+                accumulator: Value::nothing(),
+            },
+            "for_each" => IterationKind::ForEach,
+            "map" => IterationKind::Map,
+            _ => {
+                return None;
+            }
+        };
+
+        Some(Self::new(kind, input))
+    }
+
+    fn new(mut kind: IterationKind, input: &Value) -> Result<Self, Effect> {
+        let Value::Tuple { values } = input else {
+            return Err(unexpected_input(&kind, input));
+        };
+
+        let (collection, function) = match &mut kind {
+            IterationKind::Fold { accumulator } => {
+                let Some([collection, initial, function]) =
+                    values.iter().collect_array()
+                else {
+                    return Err(unexpected_input(&kind, input));
+                };
+
+                *accumulator = initial.clone();
+
+                (collection, function)
+            }
+            IterationKind::Filter
+            | IterationKind::ForEach
+            | IterationKind::Map => {
+                let Some([collection, function]) =
+                    values.iter().collect_array()
+                else {
+                    return Err(unexpected_input(&kind, input));
+                };
+
+                (collection, function)
+            }
+        };
+
+        let (collection, items) = match collection {
+            Value::List { values } => (Collection::List, values),
+            Value::Tuple { values } => (Collection::Tuple, values),
+            _ => {
+                return Err(unexpected_input(&kind, input));
+            }
+        };
+
+        Ok(Self {
+            kind,
+            function: function.clone(),
+            collection,
+            items: items.iter().cloned().collect(),
+            current: None,
+            output: Vec::new(),
+        })
+    }
+
+    /// # Indicate whether the function is currently being applied
+    ///
+    /// If so, the output of that application needs to be passed to
+    /// [`Iteration::next`].
+    pub fn is_applying(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// # Advance the iteration
+    ///
+    /// Expects the output of the previous application of the function, if
+    /// [`Iteration::is_applying`] indicated one.
+    pub fn next(
+        &mut self,
+        previous_output: Option<Value>,
+    ) -> Result<IterationStep, Effect> {
+        if let Some(item) = self.current.take() {
+            let Some(previous_output) = previous_output else {
+                unreachable!(
+                    "The function has been applied, so its output must be \
+                    available."
+                );
+            };
+
+            match &mut self.kind {
+                IterationKind::Filter => {
+                    let Value::Boolean { value: keep } = previous_output else {
+                        return Err(Effect::UnexpectedInput {
+                            expected: Type::Boolean,
+                            actual: previous_output,
+                        });
+                    };
+
+                    if keep {
+                        self.output.push(item);
+                    }
+                }
+                IterationKind::Fold { accumulator } => {
+                    *accumulator = previous_output;
+                }
+                IterationKind::ForEach => {
+                    // The output of the function is ignored.
+                }
+                IterationKind::Map => {
+                    self.output.push(previous_output);
+                }
+            }
+        }
+
+        let Some(item) = self.items.pop_front() else {
+            let output = match &self.kind {
+                IterationKind::Filter | IterationKind::Map => {
+                    self.collection.with_values(self.output.clone())
+                }
+                IterationKind::Fold { accumulator } => accumulator.clone(),
+                IterationKind::ForEach => Value::nothing(),
+            };
+
+            return Ok(IterationStep::Finish { output });
+        };
+
+        let argument = match &self.kind {
+            IterationKind::Fold { accumulator } => Value::Tuple {
+                values: vec![accumulator.clone(), item.clone()],
+            },
+            IterationKind::Filter
+            | IterationKind::ForEach
+            | IterationKind::Map => item.clone(),
+        };
+
+        self.current = Some(item);
+
+        Ok(IterationStep::Apply {
+            function: self.function.clone(),
+            argument,
+        })
+    }
+
+    /// # The paths of all syntax nodes that the iteration refers to
+    pub fn paths(&self) -> Vec<&NodePath> {
+        let accumulator = match &self.kind {
+            IterationKind::Fold { accumulator } => Some(accumulator),
+            IterationKind::Filter
+            | IterationKind::ForEach
+            | IterationKind::Map => None,
+        };
+
+        [&self.function]
+            .into_iter()
+            .chain(accumulator)
+            .chain(&self.items)
+            .chain(&self.current)
+            .chain(&self.output)
+            .flat_map(|value| value.paths())
+            .collect()
+    }
+}
+
+/// # The next thing the evaluator needs to do for an [`Iteration`]
+pub enum IterationStep {
+    /// # Apply the function to the argument
+    Apply { function: Value, argument: Value },
+
+    /// # The iteration is finished, producing the output
+    Finish { output: Value },
+}
+
+#[derive(Clone, Debug)]
+enum IterationKind {
+    /// # Keep the items for which the function returns `true`
+    Filter,
+
+    /// # Combine all items into one value
+    ///
+    /// The function is applied to a tuple of the value accumulated so far, and
+    /// the next item. Its output becomes the new accumulated value.
+    Fold { accumulator: Value },
+
+    /// # Apply the function to each item, only for its effects
+    ForEach,
+
+    /// # Replace each item with the output of the function
+    Map,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Collection {
+    List,
+    Tuple,
+}
+
+impl Collection {
+    fn with_values(self, values: Vec<Value>) -> Value {
+        match self {
+            Self::List => Value::List { values },
+            Self::Tuple => Value::Tuple { values },
+        }
+    }
+}
+
+fn unexpected_input(kind: &IterationKind, input: &Value) -> Effect {
+    let values = if let Value::Tuple { values } = input {
+        values.as_slice()
+    } else {
+        &[]
+    };
+
+    // Tuples are accepted as well as lists, and `fold` accepts any initial
+    // value. The types of the values that were actually provided, if any,
+    // make for the more helpful report.
+    let collection = match values.first() {
+        Some(value @ Value::Tuple { .. }) => value.type_(),
+        _ => Type::List,
+    };
+
+    let values = match kind {
+        IterationKind::Fold { .. } => {
+            let initial = values
+                .get(1)
+                .map(|value| value.type_())
+                .unwrap_or_else(|| Type::Tuple { values: Vec::new() });
+
+            vec![collection, initial, Type::Function]
+        }
+        IterationKind::Filter | IterationKind::ForEach | IterationKind::Map => {
+            vec![collection, Type::Function]
+        }
+    };
+
+    Effect::UnexpectedInput {
+        expected: Type::Tuple { values },
+        actual: input.clone(),
+    }
+}
@@ -1,11 +1,22 @@
 mod effect;
 mod eval_step;
 mod evaluator;
+mod handler;
+mod host_functions;
 mod intrinsics;
+mod iteration;
+mod pattern;
 mod state;
 mod value;
 
 pub use self::{
-    effect::Effect, evaluator::Evaluator, intrinsics::apply_intrinsic_function,
-    state::RuntimeState, value::Value,
+    effect::{ArithmeticError, Effect},
+    evaluator::Evaluator,
+    handler::{EffectOperation, Handler},
+    host_functions::{HostFunction, HostFunctions},
+    intrinsics::apply_intrinsic_function,
+    iteration::Iteration,
+    pattern::Pattern,
+    state::RuntimeState,
+    value::{Environment, Value},
 };
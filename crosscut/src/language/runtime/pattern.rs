@@ -0,0 +1,114 @@
+use crate::language::code::{NodeHash, Nodes, SyntaxNode};
+
+use super::Value;
+
+/// # A pattern that values can be matched against
+///
+/// This is the runtime representation of the pattern nodes in the syntax tree,
+/// like [`SyntaxNode::Binding`] or [`SyntaxNode::Wildcard`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Pattern {
+    Binding { name: String },
+    Boolean { value: bool },
+    Number { value: i32 },
+    Record { fields: Vec<String> },
+    Tuple { values: Vec<Pattern> },
+    Wildcard,
+}
+
+impl Pattern {
+    pub fn from_hash(hash: &NodeHash, nodes: &Nodes) -> Self {
+        match nodes.get(hash) {
+            SyntaxNode::Binding { name } => {
+                Self::Binding { name: name.clone() }
+            }
+            SyntaxNode::BooleanPattern { value } => {
+                Self::Boolean { value: *value }
+            }
+            SyntaxNode::NumberPattern { value } => {
+                Self::Number { value: *value }
+            }
+            SyntaxNode::RecordPattern { fields } => Self::Record {
+                fields: children(fields, nodes)
+                    .map(|field| {
+                        let SyntaxNode::Binding { name } = nodes.get(field)
+                        else {
+                            panic!("Expected binding.");
+                        };
+
+                        name.clone()
+                    })
+                    .collect(),
+            },
+            SyntaxNode::TuplePattern { values } => Self::Tuple {
+                values: children(values, nodes)
+                    .map(|value| Self::from_hash(value, nodes))
+                    .collect(),
+            },
+            SyntaxNode::Wildcard => Self::Wildcard,
+            node => {
+                // Like other code that walks the syntax tree, this would only
+                // happen if there's a bug, or if somebody messed with the
+                // stored code.
+                panic!("Expected pattern, got `{node:?}`.");
+            }
+        }
+    }
+
+    /// # Match a value against the pattern
+    ///
+    /// Returns the names that the pattern binds, with their values, if the
+    /// value matches.
+    pub fn bind(&self, value: &Value) -> Option<Vec<(String, Value)>> {
+        let mut bindings = Vec::new();
+        self.bind_into(value, &mut bindings).then_some(bindings)
+    }
+
+    fn bind_into(
+        &self,
+        value: &Value,
+        bindings: &mut Vec<(String, Value)>,
+    ) -> bool {
+        match (self, value) {
+            (Self::Binding { name }, value) => {
+                bindings.push((name.clone(), value.clone()));
+                true
+            }
+            (Self::Boolean { value: expected }, Value::Boolean { value }) => {
+                expected == value
+            }
+            (Self::Number { value: expected }, Value::Integer { value }) => {
+                expected == value
+            }
+            (Self::Record { fields }, value @ Value::Record { .. }) => {
+                fields.iter().all(|name| {
+                    let Some(field) = value.field(name) else {
+                        return false;
+                    };
+
+                    bindings.push((name.clone(), field.clone()));
+                    true
+                })
+            }
+            (Self::Tuple { values: patterns }, Value::Tuple { values }) => {
+                patterns.len() == values.len()
+                    && patterns.iter().zip(values).all(|(pattern, value)| {
+                        pattern.bind_into(value, bindings)
+                    })
+            }
+            (Self::Wildcard, _) => true,
+            _ => false,
+        }
+    }
+}
+
+fn children<'r>(
+    body: &NodeHash,
+    nodes: &'r Nodes,
+) -> impl Iterator<Item = &'r NodeHash> {
+    let SyntaxNode::Body { children, .. } = nodes.get(body) else {
+        panic!("Expected body.");
+    };
+
+    children.iter()
+}
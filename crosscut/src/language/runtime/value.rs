@@ -1,15 +1,58 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
-use crate::language::code::{NodePath, display_tuple};
+use crate::language::code::{
+    Float, NodePath, Type, display_record, display_tuple,
+};
 
 #[derive(Clone, Debug, Eq, PartialEq, udigest::Digestable)]
 pub enum Value {
-    Function { parameter: String, body: NodePath },
-    Integer { value: i32 },
-    ProvidedFunction { name: String },
-    Tuple { values: Vec<Value> },
+    Boolean {
+        value: bool,
+    },
+    Function {
+        parameter: String,
+        body: NodePath,
+        environment: Environment,
+    },
+    Float {
+        value: Float,
+    },
+    Integer {
+        value: i32,
+    },
+    Integer64 {
+        value: i64,
+    },
+    List {
+        values: Vec<Value>,
+    },
+    ProvidedFunction {
+        name: String,
+    },
+    Record {
+        fields: Vec<(String, Value)>,
+    },
+    String {
+        value: String,
+    },
+    Tuple {
+        values: Vec<Value>,
+    },
+    Unsigned {
+        value: u32,
+    },
 }
 
+/// # The names that were in scope where a function was defined
+///
+/// Later entries shadow earlier ones.
+///
+/// ## Implementation Note
+///
+/// Function values get copied around a lot, and an environment doesn't change
+/// after it was captured. Sharing it keeps those copies cheap.
+pub type Environment = Arc<[(String, Value)]>;
+
 impl Value {
     pub fn nothing() -> Self {
         Self::Tuple { values: Vec::new() }
@@ -24,9 +67,80 @@ impl Value {
         }
     }
 
-    pub fn into_function_body(self) -> Result<NodePath, Self> {
+    /// # The type of this value
+    pub fn type_(&self) -> Type {
+        match self {
+            Self::Boolean { .. } => Type::Boolean,
+            Self::Float { .. } => Type::Float,
+            Self::Function { .. } | Self::ProvidedFunction { .. } => {
+                Type::Function
+            }
+            Self::Integer { .. } => Type::Integer,
+            Self::Integer64 { .. } => Type::Integer64,
+            Self::List { .. } => Type::List,
+            Self::Record { fields } => Type::Record {
+                fields: fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.type_()))
+                    .collect(),
+            },
+            Self::String { .. } => Type::String,
+            Self::Tuple { values } => Type::Tuple {
+                values: values.iter().map(Self::type_).collect(),
+            },
+            Self::Unsigned { .. } => Type::Unsigned,
+        }
+    }
+
+    /// # The paths of all syntax nodes that this value refers to
+    pub fn paths(&self) -> Vec<&NodePath> {
+        match self {
+            Self::Function {
+                parameter: _,
+                body,
+                environment,
+            } => {
+                let mut paths = vec![body];
+                paths.extend(
+                    environment.iter().flat_map(|(_, value)| value.paths()),
+                );
+                paths
+            }
+            Self::Boolean { value: _ }
+            | Self::Float { value: _ }
+            | Self::Integer { value: _ }
+            | Self::Integer64 { value: _ }
+            | Self::ProvidedFunction { name: _ }
+            | Self::String { value: _ }
+            | Self::Unsigned { value: _ } => Vec::new(),
+            Self::Record { fields } => {
+                fields.iter().flat_map(|(_, value)| value.paths()).collect()
+            }
+            Self::List { values } | Self::Tuple { values } => {
+                values.iter().flat_map(|value| value.paths()).collect()
+            }
+        }
+    }
+
+    /// # The value of the field with the provided name, if this is a record
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        let Self::Record { fields } = self else {
+            return None;
+        };
+
+        fields
+            .iter()
+            .find_map(|(field, value)| (field == name).then_some(value))
+    }
+
+    /// # The body of the function and its environment, if this is a function
+    pub fn into_function(self) -> Result<(NodePath, Environment), Self> {
         match self {
-            Value::Function { parameter: _, body } => Ok(body),
+            Value::Function {
+                parameter: _,
+                body,
+                environment,
+            } => Ok((body, environment)),
             _ => Err(self),
         }
     }
@@ -35,18 +149,53 @@ impl Value {
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Function { parameter, body } => {
+            Self::Boolean { value } => {
+                write!(f, "{value}")?;
+            }
+            Self::Function {
+                parameter,
+                body,
+                environment: _,
+            } => {
                 write!(f, "fn {parameter}: {}", body.hash())?;
             }
+            Self::Float { value } => {
+                write!(f, "{value}")?;
+            }
             Self::Integer { value } => {
                 write!(f, "{value}")?;
             }
+            Self::Integer64 { value } => {
+                write!(f, "{value}i64")?;
+            }
+            Self::List { values } => {
+                write!(f, "[")?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{value}")?;
+                }
+
+                write!(f, "]")?;
+            }
             Self::ProvidedFunction { name } => {
                 write!(f, "provided function `{name}`")?;
             }
+            Self::Record { fields } => {
+                display_record(fields, f)?;
+            }
+            Self::String { value } => {
+                write!(f, "{value:?}")?;
+            }
             Self::Tuple { values } => {
                 display_tuple(values, f)?;
             }
+            Self::Unsigned { value } => {
+                write!(f, "{value}u32")?;
+            }
         }
 
         Ok(())
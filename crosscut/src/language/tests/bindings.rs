@@ -0,0 +1,195 @@
+use crate::language::{
+    language::Language,
+    runtime::{Effect, RuntimeState, Value},
+};
+
+#[test]
+fn let_binding() {
+    // A let node binds a value to a name, that the following expressions can
+    // refer to.
+
+    let mut language = Language::new();
+    language
+        .code("let")
+        .down()
+        .remove_right() // remove the `_` placeholder
+        .code("x") // binding
+        .down()
+        .code("127") // value
+        .down()
+        .code("x");
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+}
+
+#[test]
+fn later_binding_shadows_earlier() {
+    let mut language = Language::import(
+        "
+        apply
+            fn
+                x
+                let
+                    x
+                    255
+                x
+            127
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 255 },
+    );
+}
+
+#[test]
+fn binding_is_only_visible_within_its_body() {
+    let mut language = Language::import(
+        "
+        tuple
+            let
+                x
+                127
+            x
+        x
+        ",
+    );
+
+    // Outside of the body, `x` no longer refers to the bound value.
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::ProvidedFunction {
+            name: "x".to_string(),
+        },
+    );
+}
+
+#[test]
+fn binding_is_not_visible_outside_of_record() {
+    let mut language = Language::import(
+        "
+        record
+            def
+                a
+                let
+                    x
+                    127
+        x
+        ",
+    );
+
+    // The binding is within the body of the record, not the body that
+    // contains the record.
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::ProvidedFunction {
+            name: "x".to_string(),
+        },
+    );
+}
+
+#[test]
+fn binding_goes_out_of_scope_after_update() {
+    // Code that is evaluated after an update might have new paths, or not,
+    // depending on what was edited. Either way, the scope of a binding must
+    // end with the body it's in.
+
+    for edit in ["127", "y"] {
+        let mut language = Language::import(
+            "
+            tuple
+                apply
+                    notify_test
+                    0
+                let
+                    x
+                    127
+                y
+            x
+            ",
+        );
+
+        loop {
+            if let RuntimeState::Effect {
+                effect: Effect::ApplyProvidedFunction { name, .. },
+                ..
+            } = language.step()
+            {
+                assert_eq!(name, "notify_test");
+                break;
+            }
+        }
+
+        language.find(edit).remove_right().code("2");
+        language.exit_from_provided_function(Value::nothing());
+
+        assert_eq!(
+            language.step_until_finished().unwrap(),
+            Value::ProvidedFunction {
+                name: "x".to_string(),
+            },
+        );
+    }
+}
+
+#[test]
+fn edit_function_while_it_is_applied() {
+    // A function can be edited while it is still being applied. The edited code
+    // takes effect, and the bindings within the function still go out of scope
+    // when it returns.
+
+    let mut language = Language::import(
+        "
+        tuple
+            apply
+                fn
+                    x
+                    let
+                        y
+                        x
+                    apply
+                        notify_test
+                        0
+                    tuple
+                        y
+                        3
+                5
+            y
+        ",
+    );
+
+    loop {
+        if let RuntimeState::Effect {
+            effect: Effect::ApplyProvidedFunction { name, .. },
+            ..
+        } = language.step()
+        {
+            assert_eq!(name, "notify_test");
+            break;
+        }
+    }
+
+    language.find("3").remove_right().code("4");
+    language.exit_from_provided_function(Value::nothing());
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Tuple {
+            values: vec![
+                Value::Tuple {
+                    values: vec![
+                        Value::Integer { value: 5 },
+                        Value::Integer { value: 4 },
+                    ],
+                },
+                Value::ProvidedFunction {
+                    name: "y".to_string(),
+                },
+            ],
+        },
+    );
+}
@@ -1,10 +1,13 @@
 use crate::language::{
-    editor::EditorCommand, language::Language, runtime::Value,
+    code::{BundleError, ConflictSide, VersionError},
+    editor::EditorCommand,
+    language::Language,
+    runtime::{Effect, RuntimeState, Value},
 };
 
 #[test]
 fn clear() -> anyhow::Result<()> {
-    // The clear command should reset everything to its initial state.
+    // The clear command should reset the current code to its initial state.
     //
     // This is too dangerous of a capability to keep around long-term, but for
     // right now, it's a useful capability to have during development.
@@ -28,3 +31,276 @@ fn clear() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn tag_and_checkout() -> anyhow::Result<()> {
+    // A tagged version of the code can be restored later.
+
+    let mut language = Language::new();
+
+    language.code("12");
+    language.on_editor_command(EditorCommand::Tag {
+        name: String::from("twelve"),
+    })?;
+
+    language.code("7");
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+
+    language.on_editor_command(EditorCommand::Checkout {
+        tag: String::from("twelve"),
+    })?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+
+    // Checking out a tag is a normal change, that can be undone.
+    language.undo();
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn branch_and_switch() -> anyhow::Result<()> {
+    // Changes on a branch don't affect other branches.
+
+    let mut language = Language::new();
+
+    language.code("12");
+    language.on_editor_command(EditorCommand::Branch {
+        name: String::from("experiment"),
+    })?;
+
+    language.code("7");
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+
+    language.on_editor_command(EditorCommand::Switch {
+        branch: String::from("main"),
+    })?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+
+    // Each branch has its own history.
+    language.undo();
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 1 },
+    );
+
+    language.on_editor_command(EditorCommand::Switch {
+        branch: String::from("experiment"),
+    })?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn switch_keeps_program_running() {
+    // Switching branches doesn't restart the program. That makes it possible to
+    // switch back to a working version, without losing the program's state.
+
+    let mut language = Language::import(
+        "
+        apply
+            notify_test
+            1
+        apply
+            notify_test
+            2
+        ",
+    );
+    language
+        .on_editor_command(EditorCommand::Branch {
+            name: String::from("experiment"),
+        })
+        .unwrap();
+    language.find("2").remove_right().code("3");
+    language
+        .on_editor_command(EditorCommand::Switch {
+            branch: String::from("main"),
+        })
+        .unwrap();
+
+    assert_eq!(
+        wait_for_notification(&mut language),
+        Value::Integer { value: 1 },
+    );
+
+    language
+        .on_editor_command(EditorCommand::Switch {
+            branch: String::from("experiment"),
+        })
+        .unwrap();
+
+    // If the program had been restarted, it would notify `1` again. If it
+    // still ran the code from `main`, it would notify `2`.
+    assert_eq!(
+        wait_for_notification(&mut language),
+        Value::Integer { value: 3 },
+    );
+}
+
+#[test]
+fn switch_to_unknown_branch() {
+    // Referring to a branch that doesn't exist is an error.
+
+    let mut language = Language::new();
+
+    let result = language.on_editor_command(EditorCommand::Switch {
+        branch: String::from("unknown"),
+    });
+
+    assert_eq!(
+        result.unwrap_err().downcast::<VersionError>().unwrap(),
+        VersionError::UnknownBranch {
+            name: String::from("unknown"),
+        },
+    );
+}
+
+#[test]
+fn merge_and_pick() -> anyhow::Result<()> {
+    // Merging a branch whose changes conflict with the current one results in
+    // a conflict, which can be resolved by picking either version.
+
+    let mut language = Language::new();
+
+    language.code("1 2");
+    language.on_editor_command(EditorCommand::Branch {
+        name: String::from("experiment"),
+    })?;
+    language.code("7");
+
+    language.on_editor_command(EditorCommand::Switch {
+        branch: String::from("main"),
+    })?;
+    language.code("3");
+
+    language.on_editor_command(EditorCommand::Merge {
+        branch: String::from("experiment"),
+    })?;
+    assert_eq!(language.codebase().conflicts().len(), 1);
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::UnresolvedConflict),
+    );
+
+    language.on_editor_command(EditorCommand::Pick {
+        side: ConflictSide::Theirs,
+    })?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 27 },
+    );
+
+    // Picking a version is a normal change, that can be undone.
+    language.undo();
+    language.on_editor_command(EditorCommand::Conflict)?;
+    language.on_editor_command(EditorCommand::Pick {
+        side: ConflictSide::Ours,
+    })?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 3 },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn export_and_import() -> anyhow::Result<()> {
+    // Code can be exported to a bundle file, and imported into another
+    // codebase from there.
+
+    let path = std::env::temp_dir()
+        .join("crosscut-tests")
+        .join(format!("export_and_import-{}.json", std::process::id()));
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let path = path.to_string_lossy().into_owned();
+
+    let mut language = Language::new();
+    language.code("127");
+    language.on_editor_command(EditorCommand::Export { path: path.clone() })?;
+
+    let mut language = Language::new();
+    language.on_editor_command(EditorCommand::Import { path })?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn export_and_import_report_errors() -> anyhow::Result<()> {
+    // Importing a bundle that was modified, or exporting where there's no
+    // code, results in an error that can be shown to the user.
+
+    let path = std::env::temp_dir().join("crosscut-tests").join(format!(
+        "export_and_import_report_errors-{}.json",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let path = path.to_string_lossy().into_owned();
+
+    let mut language = Language::new();
+    language.code("127");
+    language.on_editor_command(EditorCommand::Export { path: path.clone() })?;
+
+    let bundle = std::fs::read_to_string(&path)?;
+    std::fs::write(&path, bundle.replace("\"value\": 127", "\"value\": 128"))?;
+
+    let mut language = Language::new();
+    let error = language
+        .on_editor_command(EditorCommand::Import { path: path.clone() })
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<BundleError>(),
+        Some(BundleError::Integrity { .. }),
+    ));
+
+    // The cursor is on the node for adding children. There's no code there.
+    assert!(
+        language
+            .on_editor_command(EditorCommand::Export { path })
+            .is_err()
+    );
+
+    Ok(())
+}
+
+fn wait_for_notification(language: &mut Language) -> Value {
+    for _ in 0..1024 {
+        if let RuntimeState::Effect {
+            effect: Effect::ApplyProvidedFunction { name, input },
+            ..
+        } = language.step()
+        {
+            assert_eq!(name, "notify_test");
+            let input = input.clone();
+
+            language.exit_from_provided_function(Value::nothing());
+
+            return input;
+        }
+    }
+
+    panic!("Expected call to provided function was not received.");
+}
@@ -0,0 +1,95 @@
+use crate::language::{language::Language, runtime::Value};
+
+#[test]
+fn evaluate_main() {
+    // If there is a definition named `main`, evaluation starts there, instead
+    // of with the whole program.
+
+    let mut language = Language::import(
+        "
+        def
+            other
+            127
+        def
+            main
+            255
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 255 },
+    );
+}
+
+#[test]
+fn refer_to_definitions_by_name() {
+    // Definitions can refer to each other by name, regardless of the order in
+    // which they are defined.
+
+    let mut language = Language::import(
+        "
+        def
+            main
+            apply
+                double
+                127
+        def
+            double
+            fn
+                x
+                apply
+                    +
+                    tuple
+                        x
+                        x
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 254 },
+    );
+}
+
+#[test]
+fn binding_shadows_definition() {
+    let mut language = Language::import(
+        "
+        def
+            x
+            127
+        def
+            main
+            apply
+                fn
+                    x
+                    x
+                255
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 255 },
+    );
+}
+
+#[test]
+fn edit_definition() {
+    // Definitions can be created in the editor.
+
+    let mut language = Language::new();
+    language
+        .code("def")
+        .down()
+        .remove_right() // remove the `_` placeholder
+        .code("main")
+        .down()
+        .code("127");
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+}
@@ -0,0 +1,173 @@
+use crate::language::{
+    language::Language,
+    runtime::{Effect, Value},
+};
+
+#[test]
+fn handler_resumes_with_output() {
+    // A handler is installed around the application of a function. If that
+    // raises the effect the handler handles, the output of the handler function
+    // takes the place of the raised effect.
+
+    let mut language = Language::import(
+        r#"
+        apply
+            handle
+            tuple
+                "ask"
+                fn
+                    x
+                    apply
+                        +
+                        tuple
+                            x
+                            1
+                fn
+                    _
+                    apply
+                        +
+                        tuple
+                            apply
+                                raise
+                                tuple
+                                    "ask"
+                                    1
+                            10
+        "#,
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+}
+
+#[test]
+fn handler_aborts() {
+    // Instead of resuming, a handler can abort. Then its output is the output
+    // of `handle`, and the rest of the function is not evaluated.
+
+    let mut language = Language::import(
+        r#"
+        apply
+            handle
+            tuple
+                "fail"
+                fn
+                    x
+                    apply
+                        abort
+                        x
+                fn
+                    _
+                    apply
+                        +
+                        tuple
+                            apply
+                                raise
+                                tuple
+                                    "fail"
+                                    3
+                            apply
+                                unreachable
+                                tuple
+        "#,
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 3 },
+    );
+}
+
+#[test]
+fn handler_intercepts_host_function() {
+    // Applying a function that is not intrinsic raises an effect of the same
+    // name. A handler can take care of that, before the host would.
+
+    let mut language = Language::import(
+        r#"
+        apply
+            handle
+            tuple
+                "double"
+                fn
+                    x
+                    apply
+                        *
+                        tuple
+                            x
+                            2
+                fn
+                    _
+                    apply
+                        double
+                        4
+        "#,
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 8 },
+    );
+}
+
+#[test]
+fn handler_can_defer_to_outer_handler() {
+    // While a handler function is being applied, its handler doesn't handle
+    // further effects of the same name. Those go to an outer handler, or the
+    // host.
+
+    let mut language = Language::import(
+        r#"
+        apply
+            handle
+            tuple
+                "log"
+                fn
+                    x
+                    apply
+                        raise
+                        tuple
+                            "log"
+                            apply
+                                *
+                                tuple
+                                    x
+                                    10
+                fn
+                    _
+                    apply
+                        log
+                        1
+        "#,
+    );
+
+    let mut logged = Vec::new();
+    let output = language.step_until_finished_and_handle_host_functions(
+        |name, input| {
+            assert_eq!(name, "log");
+            logged.push(input.clone());
+            Ok(Value::nothing())
+        },
+    );
+
+    assert_eq!(output, Ok(Value::nothing()));
+    assert_eq!(logged, vec![Value::Integer { value: 10 }]);
+}
+
+#[test]
+fn abort_outside_of_handler() {
+    let mut language = Language::import(
+        "
+        apply
+            abort
+            1
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::AbortOutsideOfHandler),
+    );
+}
@@ -194,3 +194,123 @@ fn tail_call_arguments_have_access_to_function_argument() {
 
     panic!();
 }
+
+#[test]
+fn returned_closure_keeps_its_environment() {
+    // A function can refer to the bindings that were in scope where it was
+    // defined, even after it has been returned from there.
+
+    let mut language = Language::import(
+        "
+        apply
+            apply
+                fn
+                    x
+                    fn
+                        y
+                        x
+                127
+            255
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+}
+
+#[test]
+fn currying() {
+    let mut language = Language::import(
+        "
+        def
+            add
+            fn
+                x
+                fn
+                    y
+                    apply
+                        +
+                        tuple
+                            x
+                            y
+        def
+            main
+            apply
+                apply
+                    add
+                    1
+                2
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 3 },
+    );
+}
+
+#[test]
+fn higher_order_function_applies_closure() {
+    // The closure that is passed to the function still refers to `y`, which is
+    // not in scope where it is applied.
+
+    let mut language = Language::import(
+        "
+        apply
+            fn
+                f
+                apply
+                    f
+                    127
+            apply
+                fn
+                    y
+                    fn
+                        x
+                        apply
+                            +
+                            tuple
+                                x
+                                y
+                1
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 128 },
+    );
+}
+
+#[test]
+fn bindings_of_caller_are_not_visible() {
+    let mut language = Language::import(
+        "
+        def
+            f
+            fn
+                x
+                y
+        def
+            main
+            apply
+                fn
+                    y
+                    apply
+                        f
+                        1
+                127
+        ",
+    );
+
+    // `y` is not in scope within `f`, so it doesn't refer to the caller's
+    // binding.
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::ProvidedFunction {
+            name: "y".to_string(),
+        },
+    );
+}
@@ -0,0 +1,173 @@
+use crate::language::{
+    code::SyntaxNode,
+    editor::EditorCommand,
+    language::Language,
+    runtime::{Effect, RuntimeState, Value},
+};
+
+#[test]
+fn undo_and_redo() {
+    // Edits can be undone and redone, and the program follows along.
+
+    let mut language = Language::new();
+
+    language.code("12");
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+
+    language.undo();
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 1 },
+    );
+
+    language.undo();
+    assert_eq!(language.step_until_finished().unwrap(), Value::nothing());
+
+    language.redo().redo();
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+}
+
+#[test]
+fn undo_restores_cursor() {
+    // After undoing an edit, the cursor should be on the previous version of
+    // the node it was on, so the user can continue editing right there.
+
+    let mut language = Language::new();
+
+    language.code("12").undo();
+    assert_eq!(
+        language
+            .codebase()
+            .node_at(&language.editor().cursor().path)
+            .node,
+        &SyntaxNode::Number { value: 1 },
+    );
+
+    language.code("7");
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 17 },
+    );
+}
+
+#[test]
+fn undo_added_node() {
+    // If the node the cursor is on was added by the undone edit, the cursor
+    // should move to a place where it can be added again.
+
+    let mut language = Language::new();
+
+    language.code("1").undo();
+    assert_eq!(
+        language
+            .codebase()
+            .node_at(&language.editor().cursor().path)
+            .node,
+        &SyntaxNode::Add,
+    );
+
+    language.code("7");
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 7 },
+    );
+}
+
+#[test]
+fn edit_after_undo_discards_redo() {
+    // Once an edit has been made after undoing something, the undone edit can
+    // no longer be redone.
+
+    let mut language = Language::new();
+
+    language.code("12").undo().code("7").redo();
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 17 },
+    );
+}
+
+#[test]
+fn undo_and_redo_commands() -> anyhow::Result<()> {
+    // Undo and redo are also available as commands.
+
+    let mut language = Language::new();
+
+    language.code("12");
+
+    language.on_editor_command(EditorCommand::Undo)?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 1 },
+    );
+
+    language.on_editor_command(EditorCommand::Redo)?;
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn collect_garbage_while_running_old_code() {
+    // Collecting garbage must not remove nodes that the evaluator still refers
+    // to, even if they are no longer part of the current code.
+
+    let mut language = Language::import(
+        "
+        apply
+            fn
+                i
+                apply
+                    notify_test
+                    i
+                apply
+                    self
+                    127
+            0
+        ",
+    );
+
+    assert_eq!(
+        wait_for_notification(&mut language),
+        Value::Integer { value: 0 }
+    );
+
+    // The recursive call is going to apply the function body that was current
+    // when it was first called. That body is no longer part of the code after
+    // this edit, but must not get removed.
+    language.find("127").remove_right();
+    language.collect_garbage(0);
+
+    assert_eq!(
+        wait_for_notification(&mut language),
+        Value::Integer { value: 27 },
+    );
+}
+
+fn wait_for_notification(language: &mut Language) -> Value {
+    for _ in 0..1024 {
+        if let RuntimeState::Effect {
+            effect: Effect::ApplyProvidedFunction { name, input },
+            ..
+        } = language.step()
+        {
+            assert_eq!(name, "notify_test");
+            let input = input.clone();
+
+            language.exit_from_provided_function(Value::nothing());
+
+            return input;
+        }
+    }
+
+    panic!("Expected call to provided function was not received.");
+}
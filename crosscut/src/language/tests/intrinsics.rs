@@ -1,4 +1,4 @@
-use crate::language::{language::Language, runtime::Value};
+use crate::language::{code::Float, language::Language, runtime::Value};
 
 #[test]
 fn drop() {
@@ -29,6 +29,59 @@ fn number_literal() {
     );
 }
 
+#[test]
+fn number_literal_formats() {
+    // Number literals can be written in a variety of formats, some of which
+    // select a different number type.
+
+    let cases = [
+        ("0x7f", Value::Integer { value: 127 }),
+        ("0b101", Value::Integer { value: 5 }),
+        ("-1_000", Value::Integer { value: -1000 }),
+        (
+            "5_000_000_000i64",
+            Value::Integer64 {
+                value: 5_000_000_000,
+            },
+        ),
+        ("0xffff_ffffu32", Value::Unsigned { value: u32::MAX }),
+        (
+            "0.5",
+            Value::Float {
+                value: Float::new(0.5),
+            },
+        ),
+        (
+            "2f64",
+            Value::Float {
+                value: Float::new(2.),
+            },
+        ),
+    ];
+
+    for (literal, value) in cases {
+        let mut language = Language::new();
+        language.code(literal);
+
+        assert_eq!(language.step_until_finished().unwrap(), value);
+    }
+}
+
+#[test]
+fn number_literal_out_of_range() {
+    // A number that doesn't fit into its type is not a number literal.
+
+    let mut language = Language::new();
+    language.code("2147483648");
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::ProvidedFunction {
+            name: String::from("2147483648"),
+        },
+    );
+}
+
 #[test]
 fn identity() {
     // The `identity` function takes any argument and returns it unchanged.
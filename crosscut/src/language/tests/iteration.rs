@@ -0,0 +1,191 @@
+use crate::language::{
+    code::Type,
+    language::Language,
+    runtime::{Effect, RuntimeState, Value},
+};
+
+#[test]
+fn map() {
+    // `map` applies a function to each item of a collection. The function can
+    // refer to the names it captured.
+
+    let mut language = Language::import(
+        "
+        let
+            offset
+            10
+        apply
+            map
+            tuple
+                list
+                    1
+                    2
+                fn
+                    x
+                    apply
+                        +
+                        tuple
+                            x
+                            offset
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::List {
+            values: vec![
+                Value::Integer { value: 11 },
+                Value::Integer { value: 12 },
+            ],
+        },
+    );
+}
+
+#[test]
+fn map_with_intrinsic_function() {
+    // Provided functions can be applied by higher-order intrinsics too, and
+    // tuples can be iterated over like lists.
+
+    let mut language = Language::import(
+        "
+        apply
+            map
+            tuple
+                tuple
+                    1
+                    2
+                negate
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Tuple {
+            values: vec![
+                Value::Integer { value: -1 },
+                Value::Integer { value: -2 },
+            ],
+        },
+    );
+}
+
+#[test]
+fn filter_and_fold() {
+    let mut language = Language::import(
+        "
+        apply
+            fold
+            tuple
+                apply
+                    filter
+                    tuple
+                        list
+                            1
+                            5
+                            2
+                            7
+                        fn
+                            x
+                            apply
+                                >
+                                tuple
+                                    x
+                                    2
+                0
+                +
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 12 },
+    );
+}
+
+#[test]
+fn for_each_with_host_function() {
+    // `for_each` applies the function only for its effects. That includes
+    // host functions.
+
+    let mut language = Language::import(
+        "
+        apply
+            for_each
+            tuple
+                list
+                    1
+                    2
+                print
+        ",
+    );
+
+    let mut printed = Vec::new();
+    let output = language.step_until_finished_and_handle_host_functions(
+        |name, input| {
+            assert_eq!(name, "print");
+            printed.push(input.clone());
+            Ok(Value::nothing())
+        },
+    );
+
+    assert_eq!(output, Ok(Value::nothing()));
+    assert_eq!(
+        printed,
+        vec![Value::Integer { value: 1 }, Value::Integer { value: 2 }],
+    );
+}
+
+#[test]
+fn filter_expects_boolean() {
+    let mut language = Language::import(
+        "
+        apply
+            filter
+            tuple
+                list
+                    1
+                identity
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::UnexpectedInput {
+            expected: Type::Boolean,
+            actual: Value::Integer { value: 1 },
+        }),
+    );
+}
+
+#[test]
+fn iteration_happens_step_by_step() {
+    // Each application of the function happens in evaluation steps of its own,
+    // like any other function application.
+
+    let mut language = Language::import(
+        "
+        apply
+            map
+            tuple
+                list
+                    1
+                    2
+                fn
+                    x
+                    x
+        ",
+    );
+
+    let mut steps = 0;
+    while let RuntimeState::Started | RuntimeState::Running = language.step() {
+        steps += 1;
+    }
+
+    // Just evaluating the apply node and its children takes 7 steps. Each
+    // application of the function adds more.
+    assert!(steps > 7);
+    assert!(matches!(
+        language.evaluator().state(),
+        RuntimeState::Finished { .. },
+    ));
+}
@@ -0,0 +1,149 @@
+use crate::language::{
+    code::Type,
+    language::Language,
+    runtime::{Effect, Value},
+};
+
+#[test]
+fn list_literal() {
+    let mut language = Language::import(
+        "
+        list
+            1
+            2
+        ",
+    );
+
+    assert_eq!(language.step_until_finished().unwrap(), list([1, 2]));
+}
+
+#[test]
+fn list_intrinsics() {
+    // Intrinsic functions don't modify lists, but return modified copies.
+
+    let cases = [
+        ("push", "tuple\n    list\n        1\n    2", list([1, 2])),
+        (
+            "pop",
+            "list\n    1\n    2",
+            Value::Tuple {
+                values: vec![list([1]), Value::Integer { value: 2 }],
+            },
+        ),
+        (
+            "get_item",
+            "tuple\n    list\n        1\n        2\n    1",
+            integer(2),
+        ),
+        (
+            "set_item",
+            "tuple\n    list\n        1\n        2\n    0\n    3",
+            list([3, 2]),
+        ),
+        ("length", "list\n    1\n    2", integer(2)),
+        (
+            "concat",
+            "tuple\n    list\n        1\n    list\n        2",
+            list([1, 2]),
+        ),
+        (
+            "slice",
+            "tuple\n    list\n        1\n        2\n        3\n    1\n    3",
+            list([2, 3]),
+        ),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::import(&format!(
+            "apply\n    {function}\n    {}",
+            argument.replace('\n', "\n    "),
+        ));
+
+        assert_eq!(language.step_until_finished().unwrap(), value);
+    }
+}
+
+#[test]
+fn index_out_of_range() {
+    let mut language = Language::import(
+        "
+        apply
+            get_item
+            tuple
+                list
+                    1
+                1
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::IndexOutOfRange {
+            index: 1,
+            value: list([1]),
+        }),
+    );
+}
+
+#[test]
+fn collection_intrinsics_report_expected_input() {
+    // Intrinsics that accept lists also accept strings. If the input is
+    // neither, the effect names both.
+
+    let cases = [
+        (
+            "concat",
+            "tuple 1\n2",
+            Type::OneOf {
+                types: vec![
+                    Type::Tuple {
+                        values: vec![Type::List, Type::List],
+                    },
+                    Type::Tuple {
+                        values: vec![Type::String, Type::String],
+                    },
+                ],
+            },
+        ),
+        (
+            "length",
+            "1",
+            Type::OneOf {
+                types: vec![Type::List, Type::String],
+            },
+        ),
+    ];
+
+    for (function, argument, expected) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        let Err(Effect::UnexpectedInput {
+            expected: actual, ..
+        }) = language.step_until_finished()
+        else {
+            panic!("Expected `{function}` to reject `{argument}`.");
+        };
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn display_list() {
+    assert_eq!(list([1, 2, 3]).to_string(), "[1, 2, 3]");
+}
+
+fn list<const N: usize>(values: [i32; N]) -> Value {
+    Value::List {
+        values: values.into_iter().map(integer).collect(),
+    }
+}
+
+fn integer(value: i32) -> Value {
+    Value::Integer { value }
+}
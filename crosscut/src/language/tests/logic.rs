@@ -0,0 +1,148 @@
+use crate::language::{
+    code::Type,
+    language::Language,
+    runtime::{Effect, Value},
+};
+
+#[test]
+fn compare_numbers() {
+    // Comparisons produce booleans.
+
+    let cases = [
+        ("==", "tuple 1\n1", true),
+        ("!=", "tuple 1\n1", false),
+        ("<", "tuple 1\n2", true),
+        ("<=", "tuple 2\n1", false),
+        (">", "tuple 0.5\n0.25", true),
+        (">=", "tuple 1u32\n1u32", true),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        assert_eq!(
+            language.step_until_finished().unwrap(),
+            Value::Boolean { value },
+        );
+    }
+}
+
+#[test]
+fn nested_nan_is_not_equal_to_itself() {
+    // A number that is not a number is not equal to anything, including
+    // itself. That doesn't change, if it's part of another value.
+
+    for (function, value) in [("==", false), ("!=", true)] {
+        let mut language = Language::import(&format!(
+            "
+            let
+                nan
+                apply
+                    /
+                    tuple
+                        0.0
+                        0.0
+            apply
+                {function}
+                tuple
+                    tuple
+                        nan
+                        1
+                    tuple
+                        nan
+                        1
+            "
+        ));
+
+        assert_eq!(
+            language.step_until_finished().unwrap(),
+            Value::Boolean { value },
+        );
+    }
+}
+
+#[test]
+fn compare_different_types() {
+    // Only values of the same type can be compared.
+
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("==")
+        .down()
+        .code("tuple 1\ntrue");
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::UnexpectedInput {
+            expected: Type::Tuple {
+                values: vec![Type::Integer, Type::Integer],
+            },
+            actual: Value::Tuple {
+                values: vec![
+                    Value::Integer { value: 1 },
+                    Value::Boolean { value: true },
+                ],
+            },
+        }),
+    );
+}
+
+#[test]
+fn boolean_logic() {
+    let cases = [
+        ("and", "tuple true\nfalse", false),
+        ("or", "tuple true\nfalse", true),
+        ("not", "false", true),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        assert_eq!(
+            language.step_until_finished().unwrap(),
+            Value::Boolean { value },
+        );
+    }
+}
+
+#[test]
+fn match_boolean() {
+    // Booleans can be matched, which makes it possible to branch on the result
+    // of a comparison.
+
+    let mut language = Language::import(
+        "
+        match
+            apply
+                <
+                tuple
+                    1
+                    2
+            arm
+                true
+                127
+            arm
+                false
+                255
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+}
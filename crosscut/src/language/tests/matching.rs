@@ -0,0 +1,109 @@
+use crate::language::{
+    language::Language,
+    runtime::{Effect, Value},
+};
+
+#[test]
+fn match_literal_or_wildcard() {
+    // The first arm whose pattern matches the value is chosen.
+
+    let code = |value: i32| {
+        format!(
+            "
+            match
+                {value}
+                arm
+                    0
+                    127
+                arm
+                    _
+                    255
+            "
+        )
+    };
+
+    for (value, expected) in [(0, 127), (1, 255)] {
+        let mut language = Language::import(&code(value));
+
+        assert_eq!(
+            language.step_until_finished().unwrap(),
+            Value::Integer { value: expected },
+        );
+    }
+}
+
+#[test]
+fn arm_binds_destructured_values() {
+    // Patterns can destructure tuples and records. The names they bind are
+    // visible in the body of the arm.
+
+    let mut language = Language::import(
+        "
+        match
+            tuple
+                record
+                    def
+                        x
+                        127
+                255
+            arm
+                tuple
+                    record
+                        x
+                    y
+                tuple
+                    y
+                    x
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Tuple {
+            values: vec![
+                Value::Integer { value: 255 },
+                Value::Integer { value: 127 },
+            ],
+        },
+    );
+}
+
+#[test]
+fn add_arm_in_editor() {
+    // Typing in place of a new arm results in an arm with that pattern.
+
+    let mut language = Language::new();
+    language
+        .code("match")
+        .down()
+        .code("1") // scrutinee
+        .down()
+        .code("_") // pattern of the new arm
+        .down()
+        .code("127"); // body of the arm
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 127 },
+    );
+}
+
+#[test]
+fn no_matching_arm() {
+    let mut language = Language::import(
+        "
+        match
+            1
+            arm
+                0
+                127
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::NoMatchingArm {
+            value: Value::Integer { value: 1 },
+        }),
+    );
+}
@@ -1,4 +1,8 @@
-use crate::language::{language::Language, runtime::Value};
+use crate::language::{
+    code::{Float, Type},
+    language::Language,
+    runtime::{ArithmeticError, Effect, Value},
+};
 
 #[test]
 fn add() {
@@ -15,3 +19,204 @@ fn add() {
         Value::Integer { value: 3 },
     );
 }
+
+#[test]
+fn add_floats() {
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("+")
+        .down()
+        .code("tuple 0.5\n0.25");
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Float {
+            value: Float::new(0.75),
+        },
+    );
+}
+
+#[test]
+fn add_different_number_types() {
+    // Numbers of different types can't be added without converting them first.
+
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("+")
+        .down()
+        .code("tuple 1i64\n2");
+
+    assert!(matches!(
+        language.step_until_finished(),
+        Err(Effect::UnexpectedInput { .. }),
+    ));
+}
+
+#[test]
+fn convert_numbers() {
+    let cases = [
+        (
+            "to_float",
+            "3",
+            Value::Float {
+                value: Float::new(3.),
+            },
+        ),
+        ("to_integer", "-2.75", Value::Integer { value: -2 }),
+        ("to_integer64", "3u32", Value::Integer64 { value: 3 }),
+        ("to_unsigned", "3i64", Value::Unsigned { value: 3 }),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        assert_eq!(language.step_until_finished().unwrap(), value);
+    }
+}
+
+#[test]
+fn convert_number_out_of_range() {
+    // Converting a number into a type that can't represent it, triggers an
+    // effect.
+
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("to_unsigned")
+        .down()
+        .code("-1");
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::NumberOutOfRange {
+            value: Value::Integer { value: -1 },
+            target: Type::Unsigned,
+        }),
+    );
+}
+
+#[test]
+fn overflow() {
+    // If the result of an arithmetic operation can't be represented, that
+    // triggers an effect instead of a panic.
+
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("+")
+        .down()
+        .code("tuple 2147483647\n1");
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::Arithmetic {
+            error: ArithmeticError::Overflow,
+            input: Value::Tuple {
+                values: vec![
+                    Value::Integer { value: i32::MAX },
+                    Value::Integer { value: 1 },
+                ],
+            },
+        }),
+    );
+}
+
+#[test]
+fn wrapping_and_saturating() {
+    // Code that expects overflow can ask for wrapping or saturating arithmetic
+    // explicitly.
+
+    let cases = [
+        (
+            "wrapping_add",
+            "tuple 2147483647\n1",
+            Value::Integer { value: i32::MIN },
+        ),
+        (
+            "wrapping_sub",
+            "tuple 0u32\n1u32",
+            Value::Unsigned { value: u32::MAX },
+        ),
+        (
+            "saturating_add",
+            "tuple 2147483647\n1",
+            Value::Integer { value: i32::MAX },
+        ),
+        (
+            "saturating_sub",
+            "tuple 0u32\n1u32",
+            Value::Unsigned { value: 0 },
+        ),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        assert_eq!(language.step_until_finished().unwrap(), value);
+    }
+}
+
+#[test]
+fn arithmetic_operations() {
+    let cases = [
+        ("*", "tuple 6\n7", Value::Integer { value: 42 }),
+        ("/", "tuple -7\n2", Value::Integer { value: -3 }),
+        ("%", "tuple -7\n2", Value::Integer { value: -1 }),
+        ("negate", "5i64", Value::Integer64 { value: -5 }),
+        (
+            "bit_xor",
+            "tuple 0b110u32\n0b011u32",
+            Value::Unsigned { value: 5 },
+        ),
+        ("shift_left", "tuple 1\n4", Value::Integer { value: 16 }),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        assert_eq!(language.step_until_finished().unwrap(), value);
+    }
+}
+
+#[test]
+fn division_by_zero() {
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("/")
+        .down()
+        .code("tuple 1\n0");
+
+    assert!(matches!(
+        language.step_until_finished(),
+        Err(Effect::Arithmetic {
+            error: ArithmeticError::DivisionByZero,
+            ..
+        }),
+    ));
+}
@@ -1,9 +1,19 @@
+mod bindings;
 mod commands;
+mod definitions;
 mod editing;
+mod effects;
 mod functions;
+mod history;
 mod host;
 mod intrinsics;
+mod iteration;
+mod lists;
+mod logic;
+mod matching;
 mod math;
+mod records;
+mod strings;
 mod tuples;
 
 pub mod infra;
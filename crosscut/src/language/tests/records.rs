@@ -0,0 +1,111 @@
+use crate::language::{
+    language::Language,
+    runtime::{Effect, Value},
+};
+
+#[test]
+fn named_fields() {
+    // A record literal defines each of its fields with a name.
+
+    let mut language = Language::import(
+        "
+        record
+            def
+                x
+                1
+            def
+                y
+                2
+        ",
+    );
+
+    let output = language.step_until_finished().unwrap();
+    assert_eq!(
+        output,
+        Value::Record {
+            fields: vec![
+                ("x".to_string(), Value::Integer { value: 1 }),
+                ("y".to_string(), Value::Integer { value: 2 }),
+            ],
+        },
+    );
+    assert_eq!(output.to_string(), "{ x: 1, y: 2 }");
+}
+
+#[test]
+fn field_access() {
+    let mut language = Language::import(
+        "
+        get
+            record
+                def
+                    x
+                    1
+                def
+                    y
+                    2
+            y
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Integer { value: 2 },
+    );
+}
+
+#[test]
+fn field_update() {
+    // Updating a field results in a new record. The fields that are not
+    // updated keep their values.
+
+    let mut language = Language::import(
+        "
+        with
+            record
+                def
+                    x
+                    1
+                def
+                    y
+                    2
+            def
+                x
+                3
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::Record {
+            fields: vec![
+                ("x".to_string(), Value::Integer { value: 3 }),
+                ("y".to_string(), Value::Integer { value: 2 }),
+            ],
+        },
+    );
+}
+
+#[test]
+fn missing_field() {
+    let mut language = Language::import(
+        "
+        get
+            record
+                def
+                    x
+                    1
+            z
+        ",
+    );
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::FieldNotFound {
+            name: "z".to_string(),
+            record: Value::Record {
+                fields: vec![("x".to_string(), Value::Integer { value: 1 })],
+            },
+        }),
+    );
+}
@@ -0,0 +1,137 @@
+use crate::language::{
+    code::Type,
+    language::Language,
+    runtime::{Effect, Value},
+};
+
+#[test]
+fn string_literal() {
+    // String literals can contain escape sequences, including ones for
+    // whitespace, which tokens can't contain otherwise.
+
+    let mut language = Language::new();
+    language.code(r#""say\s\"hi\"\n""#);
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::String {
+            value: String::from("say \"hi\"\n"),
+        },
+    );
+}
+
+#[test]
+fn invalid_escape_sequence() {
+    // A token with an unknown escape sequence is not a string literal.
+
+    let mut language = Language::new();
+    language.code(r#""\x""#);
+
+    assert_eq!(
+        language.step_until_finished().unwrap(),
+        Value::ProvidedFunction {
+            name: String::from(r#""\x""#),
+        },
+    );
+}
+
+#[test]
+fn string_intrinsics() {
+    let cases = [
+        ("concat", "tuple \"score:\\s\"\n\"7\"", string("score: 7")),
+        ("length", "\"größe\"", Value::Integer { value: 5 }),
+        ("slice", "tuple \"größe\"\n1\n4", string("röß")),
+        ("to_string", "-12", string("-12")),
+        ("to_string", "0.5", string("0.5")),
+    ];
+
+    for (function, argument, value) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        assert_eq!(language.step_until_finished().unwrap(), value);
+    }
+}
+
+#[test]
+fn string_intrinsics_report_expected_input() {
+    // If the input has the wrong type, the effect names the expected one.
+
+    let cases = [
+        (
+            "concat",
+            "tuple 1\n2",
+            Type::OneOf {
+                types: vec![
+                    Type::Tuple {
+                        values: vec![Type::List, Type::List],
+                    },
+                    Type::Tuple {
+                        values: vec![Type::String, Type::String],
+                    },
+                ],
+            },
+        ),
+        (
+            "to_string",
+            "\"a\"",
+            Type::OneOf {
+                types: vec![
+                    Type::Boolean,
+                    Type::Float,
+                    Type::Integer,
+                    Type::Integer64,
+                    Type::Unsigned,
+                ],
+            },
+        ),
+    ];
+
+    for (function, argument, expected) in cases {
+        let mut language = Language::new();
+        language
+            .code("apply")
+            .down()
+            .code(function)
+            .down()
+            .code(argument);
+
+        let Err(Effect::UnexpectedInput {
+            expected: actual, ..
+        }) = language.step_until_finished()
+        else {
+            panic!("Expected `{function}` to reject `{argument}`.");
+        };
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn slice_out_of_range() {
+    let mut language = Language::new();
+    language
+        .code("apply")
+        .down()
+        .code("slice")
+        .down()
+        .code("tuple \"abc\"\n1\n4");
+
+    assert_eq!(
+        language.step_until_finished(),
+        Err(Effect::IndexOutOfRange {
+            index: 4,
+            value: string("abc"),
+        }),
+    );
+}
+
+fn string(value: &str) -> Value {
+    Value::String {
+        value: value.to_string(),
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use crate::language::{
+    code::{ExpectedNode, NodeHash, Nodes, SyntaxNode, TypedNode},
+    compiler::{self, pattern},
+};
+
+use super::{ARM, EMPTY, INDENTATION, NAME_PREFIX};
+
+/// # Export code to its textual representation
+///
+/// If the exported node is a body, its children are exported as the top-level
+/// lines. Otherwise, the node itself is the only top-level line. Either way,
+/// importing the result produces a body that is identical to the exported one,
+/// or that contains the exported node as its only child.
+///
+/// See [`import`] for a description of the format.
+///
+/// [`import`]: super::import
+pub fn export(root: &NodeHash, nodes: &Nodes) -> Result<String, ExportError> {
+    let mut code = String::new();
+
+    match nodes.get(root) {
+        SyntaxNode::Body { children, add } => {
+            export_body(
+                children,
+                add,
+                ExpectedNode::Expression,
+                0,
+                nodes,
+                &mut code,
+            )?;
+        }
+        _ => {
+            export_expression(root, 0, nodes, &mut code)?;
+        }
+    }
+
+    Ok(code)
+}
+
+/// # An error that can occur while exporting code
+///
+/// These errors can only occur for syntax trees that the editor doesn't create,
+/// and that the text format therefore has no way to represent.
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ExportError {
+    #[error("Body is not the last child of its parent: {parent:?}")]
+    BodyIsNotLastChild { parent: SyntaxNode },
+
+    #[error("Node can't be exported in its position: {node:?}")]
+    UnexpectedNode { node: SyntaxNode },
+
+    #[error("Token `{token}` can't be written on a single line.")]
+    TokenContainsWhitespace { token: String },
+}
+
+fn export_body(
+    children: &[NodeHash],
+    add: &NodeHash,
+    expected: ExpectedNode,
+    indent: usize,
+    nodes: &Nodes,
+    code: &mut String,
+) -> Result<(), ExportError> {
+    let add = nodes.get(add);
+    if add != &SyntaxNode::Add {
+        return Err(ExportError::UnexpectedNode { node: add.clone() });
+    }
+
+    for child in children {
+        match expected {
+            ExpectedNode::Arm => export_arm(child, indent, nodes, code)?,
+            ExpectedNode::Binding | ExpectedNode::Pattern => {
+                export_pattern(child, expected, indent, nodes, code)?;
+            }
+            _ => export_expression(child, indent, nodes, code)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn export_arm(
+    hash: &NodeHash,
+    indent: usize,
+    nodes: &Nodes,
+    code: &mut String,
+) -> Result<(), ExportError> {
+    let node = nodes.get(hash);
+
+    let SyntaxNode::Arm { .. } = node else {
+        return Err(ExportError::UnexpectedNode { node: node.clone() });
+    };
+
+    write_line(ARM, indent, code)?;
+    export_children(node, indent, nodes, code)
+}
+
+fn export_expression(
+    hash: &NodeHash,
+    indent: usize,
+    nodes: &Nodes,
+    code: &mut String,
+) -> Result<(), ExportError> {
+    let node = nodes.get(hash);
+
+    let token = match node {
+        SyntaxNode::Add
+        | SyntaxNode::Arm { .. }
+        | SyntaxNode::Binding { .. }
+        | SyntaxNode::Body { .. }
+        | SyntaxNode::BooleanPattern { .. }
+        | SyntaxNode::Conflict { .. }
+        | SyntaxNode::NumberPattern { .. }
+        | SyntaxNode::RecordPattern { .. }
+        | SyntaxNode::TuplePattern { .. }
+        | SyntaxNode::Wildcard => {
+            return Err(ExportError::UnexpectedNode { node: node.clone() });
+        }
+        SyntaxNode::Identifier { name } if name.is_empty() => {
+            // An empty identifier would be indistinguishable from an empty
+            // node.
+            return Err(ExportError::UnexpectedNode { node: node.clone() });
+        }
+        SyntaxNode::Empty => EMPTY.to_string(),
+        SyntaxNode::Identifier { name }
+            if !compiler::is_identifier(name)
+                || name.starts_with(NAME_PREFIX) =>
+        {
+            format!("{NAME_PREFIX}{name}")
+        }
+        _ => node.to_token(),
+    };
+
+    write_line(&token, indent, code)?;
+    export_children(node, indent, nodes, code)
+}
+
+fn export_pattern(
+    hash: &NodeHash,
+    expected: ExpectedNode,
+    indent: usize,
+    nodes: &Nodes,
+    code: &mut String,
+) -> Result<(), ExportError> {
+    let node = nodes.get(hash);
+
+    let token = match node {
+        SyntaxNode::Binding { name } if name.is_empty() => EMPTY.to_string(),
+        SyntaxNode::Binding { name }
+            if name.starts_with(NAME_PREFIX)
+                || (expected == ExpectedNode::Pattern
+                    && !pattern::is_binding(name)) =>
+        {
+            format!("{NAME_PREFIX}{name}")
+        }
+        SyntaxNode::Binding { .. } => node.to_token(),
+        SyntaxNode::BooleanPattern { .. }
+        | SyntaxNode::NumberPattern { .. }
+        | SyntaxNode::RecordPattern { .. }
+        | SyntaxNode::TuplePattern { .. }
+        | SyntaxNode::Wildcard
+            if expected == ExpectedNode::Pattern =>
+        {
+            node.to_token()
+        }
+        _ => {
+            return Err(ExportError::UnexpectedNode { node: node.clone() });
+        }
+    };
+
+    write_line(&token, indent, code)?;
+    export_children(node, indent, nodes, code)
+}
+
+fn export_children(
+    node: &SyntaxNode,
+    indent: usize,
+    nodes: &Nodes,
+    code: &mut String,
+) -> Result<(), ExportError> {
+    let indent = indent + INDENTATION;
+    let mut children = node.children().hashes.into_iter().peekable();
+
+    while let Some(child) = children.next() {
+        if let SyntaxNode::Body {
+            children: body,
+            add,
+        } = nodes.get(child)
+        {
+            if children.peek().is_some() {
+                return Err(ExportError::BodyIsNotLastChild {
+                    parent: node.clone(),
+                });
+            }
+
+            let expected = ExpectedNode::in_body_of(Some(node));
+            export_body(body, add, expected, indent, nodes, code)?;
+            continue;
+        }
+
+        match TypedNode::from_hash(child, nodes) {
+            TypedNode::Pattern => {
+                let expected = if let SyntaxNode::Arm { .. } = node {
+                    ExpectedNode::Pattern
+                } else {
+                    ExpectedNode::Binding
+                };
+
+                export_pattern(child, expected, indent, nodes, code)?;
+            }
+            TypedNode::Expression { .. } | TypedNode::Other => {
+                export_expression(child, indent, nodes, code)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_line(
+    token: &str,
+    indent: usize,
+    code: &mut String,
+) -> Result<(), ExportError> {
+    if token.contains(char::is_whitespace) {
+        return Err(ExportError::TokenContainsWhitespace {
+            token: token.to_string(),
+        });
+    }
+
+    let Ok(()) = writeln!(code, "{:indent$}{token}", "") else {
+        unreachable!("Writing to a `String` can't fail.");
+    };
+
+    Ok(())
+}
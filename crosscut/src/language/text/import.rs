@@ -0,0 +1,302 @@
+use crate::language::{
+    code::{
+        Body, Codebase, ExpectedNode, NodeHash, Nodes, SyntaxNode, TypedNode,
+    },
+    compiler::{self, pattern},
+};
+
+use super::{ARM, EMPTY, NAME_PREFIX};
+
+/// # Import code from its textual representation
+///
+/// ## Format
+///
+/// The format mirrors what the editor displays:
+///
+/// - Every line holds one node, written as the same token that the editor
+///   shows for it. Lines that only contain whitespace are ignored.
+/// - The children of a node are written on the lines below it, indented deeper
+///   than the node itself. Nodes that are indented the same are siblings.
+/// - Bodies are not written explicitly. The children of a body are written in
+///   place of the body, as children of the body's parent. A body is always the
+///   last child of its parent, so it takes all lines that remain, after the
+///   other children are accounted for. The top-level lines are the children of
+///   the root body.
+/// - The nodes that the editor uses to add children to a body are not written
+///   either. Every imported body gets one.
+/// - If a node has fewer child lines than it has children, the remaining
+///   children keep the placeholders that the editor would create for them.
+///
+/// Tokens are compiled in the same way that the editor compiles them, with one
+/// addition: A backslash (`\`) at the start of a token means that the rest of
+/// the token is taken as a name, without checking for keywords or literals. A
+/// lone backslash stands for an empty token. This makes it possible to write
+/// empty nodes, empty bindings, and identifiers like `\fn` that the editor
+/// would compile into something else.
+///
+/// Where any pattern is possible, like in the arm of a match, tokens are
+/// compiled into patterns instead. There, the backslash makes it possible to
+/// write bindings like `\_` that would otherwise be compiled into a different
+/// pattern.
+///
+/// Here is an example:
+///
+/// ```text
+/// apply
+///     fn
+///         x
+///         apply
+///             +
+///             tuple
+///                 x
+///                 1
+///     127
+/// ```
+///
+/// ## Round-tripping
+///
+/// Importing code that was produced by [`export`] results in a syntax tree
+/// that is identical to the exported one, down to the hashes of all nodes.
+///
+/// [`export`]: super::export
+pub fn import(code: &str) -> Result<Codebase, ImportError> {
+    let mut nodes = Nodes::default();
+    let root = import_into(code, &mut nodes)?;
+
+    Ok(Codebase::from_root(root, nodes))
+}
+
+/// # Import code into existing nodes
+///
+/// Returns the hash of the body that contains the top-level nodes. See
+/// [`import`] for a description of the format.
+pub fn import_into(
+    code: &str,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    let lines = parse_lines(code)?;
+    compile_body(&lines, ExpectedNode::Expression, nodes)
+}
+
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ImportError {
+    #[error("Line {line}: Expected `{ARM}`.")]
+    ExpectedArm { line: usize },
+
+    #[error(
+        "Line {line}: Indentation doesn't match that of any previous line."
+    )]
+    Indentation { line: usize },
+
+    #[error("Line {line}: Node can't have that many children.")]
+    TooManyChildren { line: usize },
+
+    #[error("Line {line}: Node can't have children.")]
+    UnexpectedChildren { line: usize },
+}
+
+#[derive(Debug)]
+struct Line {
+    number: usize,
+    token: String,
+    children: Vec<Line>,
+}
+
+fn parse_lines(code: &str) -> Result<Vec<Line>, ImportError> {
+    let mut top_level = Vec::new();
+
+    // The lines that further lines could be children of, with their
+    // indentation.
+    let mut open: Vec<(usize, Line)> = Vec::new();
+
+    for (index, line) in code.lines().enumerate() {
+        let number = index + 1;
+
+        let Some(indent) = line.find(|ch: char| !ch.is_whitespace()) else {
+            // Only whitespace on this line. Ignore it.
+            continue;
+        };
+
+        // Close all lines that can't be the parent of this one. The last one
+        // we close is this line's previous sibling, so it must be indented
+        // the same.
+        let mut previous_sibling_indent = None;
+
+        while let Some((open_indent, _)) = open.last() {
+            if *open_indent < indent {
+                break;
+            }
+
+            previous_sibling_indent = Some(*open_indent);
+            close_line(&mut open, &mut top_level);
+        }
+
+        if let Some(previous_sibling_indent) = previous_sibling_indent
+            && previous_sibling_indent != indent
+        {
+            return Err(ImportError::Indentation { line: number });
+        }
+
+        open.push((
+            indent,
+            Line {
+                number,
+                token: line.trim().to_string(),
+                children: Vec::new(),
+            },
+        ));
+    }
+
+    while !open.is_empty() {
+        close_line(&mut open, &mut top_level);
+    }
+
+    Ok(top_level)
+}
+
+fn close_line(open: &mut Vec<(usize, Line)>, top_level: &mut Vec<Line>) {
+    let Some((_, line)) = open.pop() else {
+        return;
+    };
+
+    if let Some((_, parent)) = open.last_mut() {
+        parent.children.push(line);
+    } else {
+        top_level.push(line);
+    }
+}
+
+fn compile_body(
+    lines: &[Line],
+    expected: ExpectedNode,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    let children = lines
+        .iter()
+        .map(|line| match expected {
+            ExpectedNode::Arm => compile_arm(line, nodes),
+            ExpectedNode::Binding => compile_binding(line, nodes),
+            ExpectedNode::Pattern => compile_pattern(line, nodes),
+            _ => compile_expression(line, nodes),
+        })
+        .collect::<Result<_, _>>()?;
+
+    let add = nodes.insert(SyntaxNode::Add);
+
+    Ok(nodes.insert(SyntaxNode::Body { children, add }))
+}
+
+fn compile_expression(
+    line: &Line,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    let hash = if line.token == EMPTY {
+        nodes.insert(SyntaxNode::Empty)
+    } else if let Some(name) = line.token.strip_prefix(NAME_PREFIX) {
+        nodes.insert(SyntaxNode::Identifier {
+            name: name.to_string(),
+        })
+    } else {
+        compiler::compile(&line.token, nodes)
+    };
+
+    compile_children(hash, line, nodes)
+}
+
+fn compile_arm(
+    line: &Line,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    if line.token != ARM {
+        return Err(ImportError::ExpectedArm { line: line.number });
+    }
+
+    let pattern = nodes.insert(SyntaxNode::Binding {
+        name: String::new(),
+    });
+    let body = {
+        let node = Body::empty().into_syntax_node(nodes);
+        nodes.insert(node)
+    };
+    let hash = nodes.insert(SyntaxNode::Arm { pattern, body });
+
+    compile_children(hash, line, nodes)
+}
+
+fn compile_pattern(
+    line: &Line,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    let hash = if let Some(name) = line.token.strip_prefix(NAME_PREFIX) {
+        nodes.insert(SyntaxNode::Binding {
+            name: name.to_string(),
+        })
+    } else {
+        pattern::compile(&line.token, nodes)
+    };
+
+    compile_children(hash, line, nodes)
+}
+
+fn compile_binding(
+    line: &Line,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    let name = line
+        .token
+        .strip_prefix(NAME_PREFIX)
+        .unwrap_or(&line.token)
+        .to_string();
+
+    let hash = nodes.insert(SyntaxNode::Binding { name });
+
+    compile_children(hash, line, nodes)
+}
+
+/// # Replace the children of a freshly compiled node with the imported ones
+///
+/// Compiling a token results in a node whose children are the placeholders
+/// that the editor would create. Which kind of node each placeholder is, tells
+/// us how to compile the line that replaces it.
+fn compile_children(
+    hash: NodeHash,
+    line: &Line,
+    nodes: &mut Nodes,
+) -> Result<NodeHash, ImportError> {
+    let mut node = nodes.get(&hash).clone();
+    let mut lines = line.children.as_slice();
+
+    let expected_in_body = ExpectedNode::in_body_of(Some(&node));
+    let is_arm = matches!(node, SyntaxNode::Arm { .. });
+
+    for child in node.children_mut().hashes {
+        if let SyntaxNode::Body { .. } = nodes.get(child) {
+            *child = compile_body(lines, expected_in_body, nodes)?;
+            lines = &[];
+            continue;
+        }
+
+        let Some((first, rest)) = lines.split_first() else {
+            break;
+        };
+        lines = rest;
+
+        *child = match TypedNode::from_hash(child, nodes) {
+            TypedNode::Pattern if is_arm => compile_pattern(first, nodes)?,
+            TypedNode::Pattern => compile_binding(first, nodes)?,
+            TypedNode::Expression { .. } | TypedNode::Other => {
+                compile_expression(first, nodes)?
+            }
+        };
+    }
+
+    if !lines.is_empty() {
+        return Err(if node.children().hashes.is_empty() {
+            ImportError::UnexpectedChildren { line: line.number }
+        } else {
+            ImportError::TooManyChildren { line: line.number }
+        });
+    }
+
+    Ok(nodes.insert(node))
+}
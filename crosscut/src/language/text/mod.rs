@@ -0,0 +1,22 @@
+mod export;
+mod import;
+
+pub use self::{
+    export::{ExportError, export},
+    import::{ImportError, import, import_into},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// # The token that an arm of a match is written as
+const ARM: &str = "arm";
+
+/// # The token that stands for an empty token
+const EMPTY: &str = "\\";
+
+/// # The prefix that marks a token as a plain name
+const NAME_PREFIX: char = '\\';
+
+/// # The number of spaces that children are indented by, relative to a parent
+const INDENTATION: usize = 4;
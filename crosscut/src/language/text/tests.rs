@@ -0,0 +1,250 @@
+use crate::language::code::{NodeHash, Nodes, SyntaxNode};
+
+use super::{ExportError, ImportError, export, import};
+
+#[test]
+fn round_trip() {
+    // Exporting imported code results in the same text. Importing that again
+    // results in the same nodes.
+
+    let code = "\
+apply
+    fn
+        x
+        apply
+            +
+            tuple
+                x
+                1
+    127
+self
+";
+
+    let codebase = import(code).unwrap();
+    let exported = export(codebase.root().path.hash(), codebase.nodes());
+    assert_eq!(exported.as_deref(), Ok(code));
+
+    let reimported = import(&exported.unwrap()).unwrap();
+    assert_eq!(reimported.root().path, codebase.root().path);
+}
+
+#[test]
+fn round_trip_match() {
+    // Arms are written explicitly. Within their patterns, bindings that look
+    // like other patterns need escaping.
+
+    let code = "\
+match
+    x
+    arm
+        tuple
+            0
+            \\_
+        _
+    arm
+        _
+        1
+";
+
+    let codebase = import(code).unwrap();
+    let exported = export(codebase.root().path.hash(), codebase.nodes());
+    assert_eq!(exported.as_deref(), Ok(code));
+
+    let reimported = import(&exported.unwrap()).unwrap();
+    assert_eq!(reimported.root().path, codebase.root().path);
+}
+
+#[test]
+fn round_trip_string() {
+    // String literals are written with escape sequences, which makes them a
+    // single token, even if they contain whitespace.
+
+    let code = "\
+\"a\\s\\\"b\\\"\\n\"
+";
+
+    let codebase = import(code).unwrap();
+    let exported = export(codebase.root().path.hash(), codebase.nodes());
+    assert_eq!(exported.as_deref(), Ok(code));
+}
+
+#[test]
+fn round_trip_nodes_that_need_escaping() {
+    // Nodes that the compiler wouldn't produce from their token, like an
+    // identifier named after a keyword, or an empty binding, survive the round
+    // trip with identical hashes.
+
+    let mut nodes = Nodes::default();
+
+    let apply = {
+        let expression = nodes.insert(SyntaxNode::Identifier {
+            name: String::from("fn"),
+        });
+        let argument = nodes.insert(SyntaxNode::Empty);
+
+        nodes.insert(SyntaxNode::Apply {
+            expression,
+            argument,
+        })
+    };
+    let function = {
+        let parameter = nodes.insert(SyntaxNode::Binding {
+            name: String::new(),
+        });
+        let body = body(&[], &mut nodes);
+
+        nodes.insert(SyntaxNode::Function { parameter, body })
+    };
+    let root = body(&[apply, function], &mut nodes);
+
+    let exported = export(&root, &nodes).unwrap();
+    assert_eq!(import(&exported).unwrap().root().path.hash(), &root);
+
+    // Exporting a node that is not a body results in a body that contains
+    // only that node.
+    let exported = export(&apply, &nodes).unwrap();
+    assert_eq!(
+        import(&exported).unwrap().root().path.hash(),
+        &body(&[apply], &mut nodes),
+    );
+}
+
+#[test]
+fn escape_tokens() {
+    // Identifiers that look like keywords or literals, as well as empty nodes
+    // and bindings, are written with a backslash.
+
+    let code = "\
+apply
+    \\fn
+    \\
+fn
+    \\
+\\\\x
+";
+
+    let codebase = import(code).unwrap();
+    let root = codebase.root();
+
+    let SyntaxNode::Body { children, .. } = root.node else {
+        panic!("Expected root to be a body.");
+    };
+    let [apply, function, identifier] = children[..] else {
+        panic!("Expected three top-level nodes.");
+    };
+    let nodes = codebase.nodes();
+
+    let SyntaxNode::Apply {
+        expression,
+        argument,
+    } = nodes.get(&apply)
+    else {
+        panic!("Expected `apply`.");
+    };
+    assert_eq!(
+        nodes.get(expression),
+        &SyntaxNode::Identifier {
+            name: String::from("fn"),
+        },
+    );
+    assert_eq!(nodes.get(argument), &SyntaxNode::Empty);
+
+    let SyntaxNode::Function { parameter, .. } = nodes.get(&function) else {
+        panic!("Expected `fn`.");
+    };
+    assert_eq!(
+        nodes.get(parameter),
+        &SyntaxNode::Binding {
+            name: String::new(),
+        },
+    );
+
+    assert_eq!(
+        nodes.get(&identifier),
+        &SyntaxNode::Identifier {
+            name: String::from("\\x"),
+        },
+    );
+
+    assert_eq!(export(root.path.hash(), nodes).as_deref(), Ok(code));
+}
+
+#[test]
+fn missing_children_are_placeholders() {
+    // Children that are not written keep the placeholders that the editor
+    // would create.
+
+    let mut nodes = Nodes::default();
+    let empty = nodes.insert(SyntaxNode::Empty);
+    let apply = nodes.insert(SyntaxNode::Apply {
+        expression: empty,
+        argument: empty,
+    });
+    let expected = body(&[apply], &mut nodes);
+
+    let codebase = import("apply").unwrap();
+    assert_eq!(codebase.root().path.hash(), &expected);
+}
+
+#[test]
+fn reject_inconsistent_indentation() {
+    let result = import(
+        "
+        apply
+            1
+          2
+        ",
+    );
+    assert!(matches!(result, Err(ImportError::Indentation { line: 4 })));
+}
+
+#[test]
+fn reject_too_many_children() {
+    let result = import(
+        "
+        apply
+            1
+            2
+            3
+        ",
+    );
+    assert!(matches!(
+        result,
+        Err(ImportError::TooManyChildren { line: 2 })
+    ));
+}
+
+#[test]
+fn reject_unexpected_children() {
+    let result = import(
+        "
+        1
+            2
+        ",
+    );
+    assert!(matches!(
+        result,
+        Err(ImportError::UnexpectedChildren { line: 2 })
+    ));
+}
+
+#[test]
+fn reject_unrepresentable_tokens() {
+    let mut nodes = Nodes::default();
+    let identifier = nodes.insert(SyntaxNode::Identifier {
+        name: String::from("a b"),
+    });
+
+    assert!(matches!(
+        export(&identifier, &nodes),
+        Err(ExportError::TokenContainsWhitespace { .. })
+    ));
+}
+
+fn body(children: &[NodeHash], nodes: &mut Nodes) -> NodeHash {
+    let add = nodes.insert(SyntaxNode::Add);
+    nodes.insert(SyntaxNode::Body {
+        children: children.to_vec(),
+        add,
+    })
+}
@@ -18,7 +18,16 @@ pub use self::{
         Camera, Game, Init, Instance, OrthographicProjection, PureCrosscutGame,
         PureCrosscutGameInit, Renderer,
     },
-    language::language::Language,
+    language::{
+        code::{
+            Bundle, BundleError, Change, Codebase, CodebaseEvent, ConflictSide,
+            Diagnostic, Diff, DiffNode, ExpectedNode, NodeHash, NodeId, Nodes,
+            ObserverId, Type, VersionError, check_integrity, merge,
+        },
+        language::Language,
+        runtime::HostFunction,
+        text,
+    },
 };
 
 pub fn start_and_wait(init: Box<dyn Init + Send>) -> anyhow::Result<()> {
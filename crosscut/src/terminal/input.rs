@@ -33,6 +33,8 @@ pub fn read_terminal_input() -> anyhow::Result<ControlFlow<(), TerminalInput>> {
             // will propagate the shutdown to all other threads.
             return Ok(ControlFlow::Break(()));
         }
+        KeyCode::Char('z') if ctrl_pressed => TerminalInput::Undo,
+        KeyCode::Char('y') if ctrl_pressed => TerminalInput::Redo,
         KeyCode::Char(ch) if ch.is_ascii() => {
             // Only ASCII characters are currently accepted. This limitation is
             // tracked here:
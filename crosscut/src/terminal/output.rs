@@ -49,11 +49,14 @@ pub trait TerminalOutputAdapter: fmt::Write {
     }
 }
 
+#[cfg(test)]
 #[derive(Debug)]
 pub struct DebugOutputAdapter;
 
+#[cfg(test)]
 impl TerminalOutputAdapter for DebugOutputAdapter {}
 
+#[cfg(test)]
 impl fmt::Write for DebugOutputAdapter {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         print!("{s}");
@@ -61,12 +64,15 @@ impl fmt::Write for DebugOutputAdapter {
     }
 }
 
+#[cfg(test)]
 pub struct StringOutputAdapter {
     pub output: String,
 }
 
+#[cfg(test)]
 impl TerminalOutputAdapter for StringOutputAdapter {}
 
+#[cfg(test)]
 impl fmt::Write for StringOutputAdapter {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         write!(self.output, "{s}")?;
@@ -206,13 +206,13 @@ impl World {
     }
 
     fn eat_food(&mut self) {
-        if let Some(food) = self.food {
-            if collision_between(&food, &self.snake) {
-                self.food = None;
-                self.nominal_length += 3;
+        if let Some(food) = self.food
+            && collision_between(&food, &self.snake)
+        {
+            self.food = None;
+            self.nominal_length += 3;
 
-                self.new_walls_left += 3;
-            }
+            self.new_walls_left += 3;
         }
     }
 